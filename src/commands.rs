@@ -2,19 +2,27 @@ mod app;
 mod bucket;
 mod cache;
 mod checkup;
+pub mod completions;
 mod credits;
 mod debug;
 mod depends;
 mod describe;
 mod export;
 mod hook;
+#[cfg(feature = "download")]
+mod import;
 #[cfg(not(feature = "v2"))]
 mod outdated;
+mod schema;
 mod search;
+mod shim;
 mod status;
 #[path = "commands/update_alias.rs"]
 mod update;
+#[cfg(feature = "download")]
+mod verify;
 mod virustotal;
+mod which;
 
 use clap::Subcommand;
 
@@ -78,6 +86,7 @@ where
 pub trait Command {
     const BETA: bool = false;
     const NEEDS_ELEVATION: bool = false;
+    const NEEDS_NETWORK: bool = false;
 
     const DEPRECATED: Option<DeprecationWarning> = None;
 
@@ -97,6 +106,10 @@ pub trait CommandRunner: Command {
             abandon!("This command requires elevation. Please run as an administrator.");
         }
 
+        if Self::NEEDS_NETWORK && crate::is_offline() {
+            abandon!("This command requires network access, which is disabled in offline mode.");
+        }
+
         if Self::BETA {
             eprintln_yellow!(
                 "This command is in beta and may not work as expected. Please report any and all bugs you find!\n",
@@ -146,14 +159,28 @@ pub enum Commands {
     #[cfg_attr(not(feature = "v2"), stripped(ignore))]
     Update(update::ArgsWrapper),
     Export(export::Args),
+    #[stripped(ignore)]
+    #[cfg(feature = "download")]
+    Import(import::Args),
     Checkup(checkup::Args),
     #[cfg(feature = "download")]
     Cache(cache::Args),
     #[clap(alias = "virustotal")]
     Scan(virustotal::Args),
     #[stripped(ignore)]
+    #[cfg(feature = "download")]
+    Verify(verify::Args),
+    #[stripped(ignore)]
+    Shim(shim::Args),
+    #[stripped(ignore)]
+    Which(which::Args),
+    #[stripped(ignore)]
+    Schema(schema::Args),
+    #[stripped(ignore)]
     Credits(credits::Args),
     #[stripped(ignore)]
+    Completions(completions::Args),
+    #[stripped(ignore)]
     #[cfg(debug_assertions)]
     Debug(debug::Args),
 }
@@ -189,10 +216,18 @@ impl Runnable for Commands {
             Commands::Status(args) => args.run(ctx).await,
             Commands::Update(args) => args.run(ctx).await,
             Commands::Export(args) => args.run(ctx).await,
+            #[cfg(feature = "download")]
+            Commands::Import(args) => args.run(ctx).await,
             Commands::Checkup(args) => args.run(ctx).await,
             Commands::Cache(args) => args.run(ctx).await,
             Commands::Scan(args) => args.run(ctx).await,
+            #[cfg(feature = "download")]
+            Commands::Verify(args) => args.run(ctx).await,
+            Commands::Shim(args) => args.run(ctx).await,
+            Commands::Which(args) => args.run(ctx).await,
+            Commands::Schema(args) => args.run(ctx).await,
             Commands::Credits(args) => args.run(ctx).await,
+            Commands::Completions(args) => args.run(ctx).await,
             #[cfg(debug_assertions)]
             Commands::Debug(args) => args.run(ctx).await,
         }
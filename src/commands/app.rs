@@ -2,14 +2,39 @@ pub mod cat;
 pub mod cleanup;
 #[cfg(feature = "download")]
 pub mod download;
+pub mod env;
+pub mod files;
+pub mod hold;
 pub mod home;
 pub mod info;
+#[cfg(feature = "download")]
+pub mod install;
 pub mod list;
+pub mod notes;
+pub mod pin;
 pub mod purge;
+pub mod reset;
+pub mod scripts;
+pub mod shortcuts;
+pub mod size;
+pub mod switch;
+pub mod unhold;
+pub mod uninstall;
+pub mod unpin;
+#[cfg(feature = "download")]
+pub mod update;
+#[cfg(feature = "download")]
+pub mod verify;
 
 use clap::{Parser, Subcommand};
 
-use sprinkles::{config, contexts::ScoopContext};
+use sprinkles::{
+    config,
+    contexts::ScoopContext,
+    packages::{models::install as installed, reference::package},
+};
+
+use crate::abandon;
 
 use super::{Command, CommandRunner, Runnable};
 
@@ -19,10 +44,29 @@ pub enum Commands {
     Cleanup(cleanup::Args),
     #[cfg(feature = "download")]
     Download(download::Args),
+    Env(env::Args),
+    Files(files::Args),
+    Hold(hold::Args),
     Home(home::Args),
     Info(info::Args),
+    #[cfg(feature = "download")]
+    Install(install::Args),
     List(list::Args),
+    Notes(notes::Args),
+    Pin(pin::Args),
     Purge(purge::Args),
+    Reset(reset::Args),
+    Scripts(scripts::Args),
+    Shortcuts(shortcuts::Args),
+    Size(size::Args),
+    Switch(switch::Args),
+    Unhold(unhold::Args),
+    Uninstall(uninstall::Args),
+    Unpin(unpin::Args),
+    #[cfg(feature = "download")]
+    Update(update::Args),
+    #[cfg(feature = "download")]
+    Verify(verify::Args),
 }
 
 impl Runnable for Commands {
@@ -35,10 +79,29 @@ impl Runnable for Commands {
             Commands::Cleanup(args) => args.run(ctx).await,
             #[cfg(feature = "download")]
             Commands::Download(args) => args.run(ctx).await,
+            Commands::Env(args) => args.run(ctx).await,
+            Commands::Files(args) => args.run(ctx).await,
+            Commands::Hold(args) => args.run(ctx).await,
             Commands::Home(args) => args.run(ctx).await,
             Commands::Info(args) => args.run(ctx).await,
+            #[cfg(feature = "download")]
+            Commands::Install(args) => args.run(ctx).await,
             Commands::List(args) => args.run(ctx).await,
+            Commands::Notes(args) => args.run(ctx).await,
+            Commands::Pin(args) => args.run(ctx).await,
             Commands::Purge(args) => args.run(ctx).await,
+            Commands::Reset(args) => args.run(ctx).await,
+            Commands::Scripts(args) => args.run(ctx).await,
+            Commands::Shortcuts(args) => args.run(ctx).await,
+            Commands::Size(args) => args.run(ctx).await,
+            Commands::Switch(args) => args.run(ctx).await,
+            Commands::Unhold(args) => args.run(ctx).await,
+            Commands::Uninstall(args) => args.run(ctx).await,
+            Commands::Unpin(args) => args.run(ctx).await,
+            #[cfg(feature = "download")]
+            Commands::Update(args) => args.run(ctx).await,
+            #[cfg(feature = "download")]
+            Commands::Verify(args) => args.run(ctx).await,
         }
     }
 }
@@ -59,3 +122,47 @@ impl Command for Args {
         self.command.run(ctx).await
     }
 }
+
+/// Get the name of the app a reference points to
+///
+/// Exits if the name could not be determined
+pub fn reference_name(reference: &package::Reference) -> String {
+    let Some(name) = reference.name() else {
+        abandon!("Could not determine the name of {reference}");
+    };
+
+    name
+}
+
+/// Get the names of the apps the references point to
+///
+/// Exits if any of the names could not be determined
+pub fn reference_names(references: &[package::Reference]) -> Vec<String> {
+    references.iter().map(reference_name).collect()
+}
+
+/// Get the names of all installed apps
+///
+/// # Errors
+/// - Listing the installed apps failed
+pub fn installed_names(ctx: &impl ScoopContext) -> anyhow::Result<Vec<String>> {
+    Ok(installed::Manifest::list_all_unchecked(ctx)?
+        .iter()
+        .map(|app| unsafe { app.name() }.to_string())
+        .collect())
+}
+
+/// Get the names of the apps the references point to, or of all installed apps if there are none
+///
+/// # Errors
+/// - Listing the installed apps failed
+pub fn names_or_installed(
+    ctx: &impl ScoopContext,
+    references: &[package::Reference],
+) -> anyhow::Result<Vec<String>> {
+    if references.is_empty() {
+        installed_names(ctx)
+    } else {
+        Ok(reference_names(references))
+    }
+}
@@ -1,8 +1,9 @@
 use std::{path::Path, str::FromStr, time::Duration};
 
 use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
 use futures::{StreamExt, TryFutureExt, stream::FuturesUnordered};
-use itertools::Itertools;
+use itertools::{Either, Itertools};
 use sprinkles::{
     contexts::ScoopContext,
     packages::reference::{manifest, package},
@@ -16,16 +17,18 @@ use sprinkles::{
 
 use crate::{
     abandon,
+    commands::completions,
     handlers::{AppsDecider, ListApps},
     logging::macros::ddbg,
     output::colours::eprintln_green,
+    version_order,
 };
 
 #[derive(Debug, Clone, Parser)]
 #[allow(clippy::struct_excessive_bools)]
 /// Cleanup apps by removing old versions
 pub struct Args {
-    #[clap(help = "The app(s) to cleanup")]
+    #[clap(help = "The app(s) to cleanup", add = ArgValueCandidates::new(completions::installed_apps))]
     apps: Vec<package::Reference>,
 
     #[clap(short, long, help = "Cleanup all installed apps")]
@@ -38,6 +41,13 @@ pub struct Args {
     )]
     cache: bool,
 
+    #[clap(
+        long,
+        help = "Keep the given number of the most recent old versions, in addition to the current version",
+        default_value_t = 0
+    )]
+    keep: usize,
+
     #[clap(from_global)]
     assume_yes: bool,
 
@@ -137,10 +147,23 @@ impl Args {
 
         let versions = app_handle.list_versions()?;
 
-        let old_versions = versions
+        // Newest first, so the versions to keep are at the front
+        let (kept_versions, old_versions): (Vec<_>, Vec<_>) = versions
             .into_iter()
             .filter(|version| version.version() != current_version.as_str())
-            .collect_vec();
+            .sorted_by(|a, b| version_order::compare(b.version(), a.version()))
+            .enumerate()
+            .partition_map(|(index, version)| {
+                if index < self.keep {
+                    Either::Left(version.version().to_string())
+                } else {
+                    Either::Right(version)
+                }
+            });
+
+        if !kept_versions.is_empty() {
+            debug!("Keeping {app} versions: {}", kept_versions.join(", "));
+        }
 
         // Remove old cache entries
         if self.cache {
@@ -153,6 +176,9 @@ impl Args {
                     };
                     if Some(&cache_entry.name) == app.name().as_ref()
                         && cache_entry.version != current_version
+                        && !kept_versions
+                            .iter()
+                            .any(|version| version == cache_entry.version.as_str())
                     {
                         Some((entry.path(), cache_entry))
                     } else {
@@ -23,7 +23,9 @@ use sprinkles::{
 
 use crate::{
     abandon,
+    commands::verify::CachedHash,
     handlers::{AppsDecider, ListApps},
+    install::config::InstallConfig,
     models::status::Info,
     output::colours::{bright_red, eprintln_yellow},
 };
@@ -46,6 +48,7 @@ pub struct Args {
 
 impl super::Command for Args {
     const BETA: bool = true;
+    const NEEDS_NETWORK: bool = true;
 
     async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
         let packages = match AppsDecider::new(ctx, self.list_apps(), self.apps).decide()? {
@@ -65,7 +68,7 @@ impl super::Command for Args {
         let pb = ProgressBar::new_spinner().with_message("Initializing download(s)");
         pb.enable_steady_tick(Duration::from_millis(100));
 
-        let downloaders: Vec<DownloadHandle> =
+        let downloaders: Vec<(Option<CachedHash>, DownloadHandle)> =
             futures::future::try_join_all(packages.into_iter().map(|package| {
                 let mp = mp.clone();
                 async move {
@@ -76,14 +79,26 @@ impl super::Command for Args {
 
                     let dl = Handle::open_manifest(ctx.cache_path(), &manifest, self.arch)?;
 
-                    let downloaders = dl.into_iter().map(|dl| {
+                    let name = unsafe { manifest.name() };
+                    let version = manifest.version.to_string();
+                    let config = InstallConfig::from_manifest(&manifest, self.arch)?;
+                    let urls = config.url.into_vec();
+                    let hashes = config.hash.into_vec();
+
+                    let downloaders = dl.into_iter().enumerate().map(|(index, dl)| {
                         let mp = mp.clone();
                         let package_name = package.name();
+                        let cached_hash =
+                            urls.get(index)
+                                .zip(hashes.get(index))
+                                .and_then(|(url, hash)| {
+                                    CachedHash::new(ctx, name, &version, url, hash)
+                                });
                         async move {
                             match DownloadHandle::new::<AsyncClient>(dl, Some(&mp), package_name)
                                 .await
                             {
-                                Ok(dl) => anyhow::Ok(dl),
+                                Ok(dl) => anyhow::Ok((cached_hash, dl)),
                                 Err(e) => match e {
                                     sprinkles::cache::Error::ErrorCode(status) => {
                                         abandon!("Found {status} error while downloading")
@@ -105,9 +120,9 @@ impl super::Command for Args {
 
         pb.finish_with_message("Generated manifests");
 
-        let threads = downloaders
-            .into_iter()
-            .map(|dl| tokio::spawn(async move { dl.download().await }));
+        let threads = downloaders.into_iter().map(|(cached_hash, dl)| {
+            tokio::spawn(async move { (cached_hash, dl.download().await) })
+        });
 
         let results = futures::future::try_join_all(threads).await?;
 
@@ -119,20 +134,30 @@ impl super::Command for Args {
                 .with_finish(ProgressFinish::WithMessage("✅ Checked all files".into()))
         };
 
-        for result in results {
+        for (cached_hash, result) in results {
             let result = result?;
 
             if !self.no_hash_check {
-                let actual_hash = result.actual_hash.no_prefix();
-
-                if result.actual_hash == result.computed_hash {
-                    pb.tick();
-                } else {
+                // Sprinkles can't compute every algorithm, so some downloads are hashed once cached
+                let mismatch = match cached_hash {
+                    Some(cached_hash) => cached_hash
+                        .mismatch()?
+                        .map(|hash| (cached_hash.expected, hash)),
+                    None => (result.actual_hash != result.computed_hash).then(|| {
+                        (
+                            result.actual_hash.no_prefix().to_string(),
+                            result.computed_hash.no_prefix().to_string(),
+                        )
+                    }),
+                };
+
+                if let Some((actual_hash, hash)) = mismatch {
                     eprintln!();
-                    let hash = result.computed_hash.no_prefix();
                     pb.println(bright_red!(
                         "🔓 Hash mismatch: expected {actual_hash}, found {hash}",
                     ));
+                } else {
+                    pb.tick();
                 }
             }
         }
@@ -0,0 +1,242 @@
+use std::path::{Component, Path};
+
+use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use rayon::prelude::*;
+use schemars::JsonSchema;
+use serde::Serialize;
+use sprinkles::{Architecture, contexts::ScoopContext, packages::reference::package};
+
+use crate::{
+    commands::completions,
+    install::{self, config::InstallConfig, env, scripts::ScriptVariables},
+    output::{
+        colours::eprintln_green,
+        format::Format,
+        structured::{Priority, Structured},
+    },
+};
+
+#[derive(Debug, Clone, Parser)]
+/// Show the environment changes made by installed apps
+///
+/// Lists the `PATH` entries and variables each app sets,
+/// and flags `PATH` entries that point at removed versions or apps
+pub struct Args {
+    #[clap(
+        help = "The apps to show. Defaults to all installed apps",
+        add = ArgValueCandidates::new(completions::installed_apps)
+    )]
+    apps: Vec<package::Reference>,
+
+    #[clap(
+        short,
+        long,
+        help = "Only show entries that are missing, changed or stale"
+    )]
+    problems: bool,
+
+    #[clap(from_global)]
+    arch: Architecture,
+
+    #[clap(from_global)]
+    format: Format,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// The kind of environment change
+enum Kind {
+    /// A directory added to `PATH`, from the manifest's `env_add_path` field
+    Path,
+    /// An environment variable, from the manifest's `env_set` field
+    Variable,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// Whether an environment change is in place
+enum Status {
+    /// The change is in place
+    Ok,
+    /// The directory is not in `PATH`, or the variable is not set
+    Missing,
+    /// The variable is set to a different value
+    Changed,
+    /// The `PATH` entry points at a version or app that isn't current
+    Stale,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+/// An environment change made by an app
+pub(crate) struct Change {
+    /// The app that made the change
+    app: String,
+    kind: Kind,
+    /// The name of the variable, for variables
+    name: Option<String>,
+    /// The directory added to `PATH`, or the value of the variable
+    value: String,
+    status: Status,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let names = super::names_or_installed(ctx, &self.apps)?;
+
+        let path_entries = env::path_entries(crate::is_global())?;
+
+        let mut changes = names
+            .par_iter()
+            .flat_map(|name| self.app_changes(ctx, name, &path_entries))
+            .collect::<Vec<_>>();
+
+        changes.extend(
+            stale_entries(ctx, &path_entries)
+                .into_iter()
+                .filter(|change| self.apps.is_empty() || names.contains(&change.app)),
+        );
+
+        if self.problems {
+            changes.retain(|change| change.status != Status::Ok);
+        }
+
+        changes.sort_by(|a, b| a.app.cmp(&b.app));
+
+        if self.format.is_structured() {
+            self.format.print(&changes)?;
+        } else if changes.is_empty() {
+            eprintln_green!("No environment changes found");
+        } else {
+            let outputs = Structured::new(&changes)
+                .with_priority("app", Priority::Required)
+                .with_priority("value", Priority::Required)
+                .with_priority("status", Priority::High)
+                .with_priority("name", Priority::High)
+                .with_priority("kind", Priority::Low);
+
+            print!("{outputs}");
+        }
+
+        Ok(())
+    }
+}
+
+impl Args {
+    /// List the `PATH` entries and variables an installed app sets, and whether they are in place
+    fn app_changes(
+        &self,
+        ctx: &impl ScoopContext,
+        name: &str,
+        path_entries: &[String],
+    ) -> Vec<Change> {
+        let app_dir = ctx.apps_path().join(name);
+
+        let Ok((manifest, arch)) = install::installed_manifest(&app_dir) else {
+            debug!("Skipping {name}, as its manifest could not be read");
+            return vec![];
+        };
+
+        let arch = arch.unwrap_or(self.arch);
+
+        let Ok(config) = InstallConfig::from_manifest(&manifest, arch) else {
+            debug!("Skipping {name}, as its manifest could not be parsed");
+            return vec![];
+        };
+
+        let current_dir = app_dir.join("current");
+        let mut changes = vec![];
+
+        for dir in env::resolve_paths(&config.env_add_path.clone().into_vec(), &current_dir) {
+            let value = dir.display().to_string();
+            let status = if path_entries
+                .iter()
+                .any(|entry| env::same_entry(entry, &value))
+            {
+                Status::Ok
+            } else {
+                Status::Missing
+            };
+
+            changes.push(Change {
+                app: name.to_string(),
+                kind: Kind::Path,
+                name: None,
+                value,
+                status,
+            });
+        }
+
+        if !config.env_set.is_empty() {
+            let version = manifest.version.to_string();
+            let persist_dir = ctx.persist_path().join(name);
+            let variables = ScriptVariables {
+                app: name,
+                version: &version,
+                dir: &current_dir,
+                original_dir: &current_dir,
+                persist_dir: &persist_dir,
+                architecture: arch.to_string(),
+                global: crate::is_global(),
+                cmd: "env",
+            };
+
+            for (var, value) in &config.env_set {
+                let value = variables.substitute(value);
+                let status = match env::get_var(var, crate::is_global()) {
+                    Ok(Some(current)) if current == value => Status::Ok,
+                    Ok(Some(_)) => Status::Changed,
+                    _ => Status::Missing,
+                };
+
+                changes.push(Change {
+                    app: name.to_string(),
+                    kind: Kind::Variable,
+                    name: Some(var.clone()),
+                    value,
+                    status,
+                });
+            }
+        }
+
+        changes
+    }
+}
+
+/// Find the `PATH` entries in the apps directory that point at a specific version, or a directory that no longer exists
+///
+/// Apps add directories within their `current` junction, so any other entry was left behind by an update or uninstall
+fn stale_entries(ctx: &impl ScoopContext, path_entries: &[String]) -> Vec<Change> {
+    let apps_path = ctx.apps_path();
+
+    path_entries
+        .iter()
+        .filter_map(|entry| {
+            let relative = Path::new(entry).strip_prefix(&apps_path).ok()?;
+
+            let mut components = relative
+                .components()
+                .filter_map(|component| match component {
+                    Component::Normal(name) => Some(name.to_string_lossy()),
+                    _ => None,
+                });
+
+            let app = components.next()?.to_string();
+            let in_current = components
+                .next()
+                .is_some_and(|version| version == "current");
+
+            if in_current && Path::new(entry).exists() {
+                return None;
+            }
+
+            Some(Change {
+                app,
+                kind: Kind::Path,
+                name: None,
+                value: entry.clone(),
+                status: Status::Stale,
+            })
+        })
+        .collect()
+}
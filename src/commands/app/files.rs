@@ -0,0 +1,122 @@
+use std::path::Path;
+
+use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use schemars::JsonSchema;
+use serde::Serialize;
+use sprinkles::{contexts::ScoopContext, packages::reference::package};
+
+use crate::{
+    abandon,
+    commands::completions,
+    output::{
+        colours::eprintln_bright_yellow,
+        format::Format,
+        structured::{Priority, Structured},
+    },
+    wrappers::sizes::Size,
+};
+
+#[derive(Debug, Clone, Parser)]
+/// List the files an installed app put on disk, and their sizes
+///
+/// Walks the app's current version directory, and its persist directory with `--persist`
+pub struct Args {
+    #[clap(
+        help = "The app to list the files of",
+        add = ArgValueCandidates::new(completions::installed_apps)
+    )]
+    app: package::Reference,
+
+    #[clap(short, long, help = "Include the files in the app's persist directory")]
+    persist: bool,
+
+    #[clap(from_global)]
+    format: Format,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+/// A file owned by an installed app
+pub(crate) struct File {
+    /// The path of the file, relative to the version or persist directory
+    path: String,
+    #[schemars(with = "String")]
+    size: Size,
+    /// Whether the file is in the app's persist directory
+    persisted: bool,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let name = super::reference_name(&self.app);
+
+        let app_dir = ctx.apps_path().join(&name);
+
+        if !app_dir.exists() {
+            abandon!("{name} is not installed");
+        }
+
+        let current_dir = app_dir.join("current");
+        let version_dir = std::fs::read_link(&current_dir).unwrap_or(current_dir);
+
+        let mut files = vec![];
+        walk(&version_dir, &version_dir, false, &mut files)?;
+
+        if self.persist {
+            let persist_dir = ctx.persist_path().join(&name);
+
+            if persist_dir.exists() {
+                walk(&persist_dir, &persist_dir, true, &mut files)?;
+            }
+        }
+
+        files.sort_by(|a, b| (a.persisted, &a.path).cmp(&(b.persisted, &b.path)));
+
+        if self.format.is_structured() {
+            self.format.print(&files)?;
+            return Ok(());
+        }
+
+        let total_size = files.iter().fold(Size::new(0), |acc, file| acc + file.size);
+
+        let outputs = Structured::new(&files)
+            .with_priority("path", Priority::Required)
+            .with_priority("size", Priority::High)
+            .with_priority("persisted", Priority::Low);
+
+        print!("{outputs}");
+
+        eprintln_bright_yellow!("Total: {} files, {total_size}", files.len());
+
+        Ok(())
+    }
+}
+
+/// Recursively collect the files in a directory
+///
+/// Symlinks and junctions are not followed, as persisted data is linked into the version directory
+fn walk(root: &Path, dir: &Path, persisted: bool, files: &mut Vec<File>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let path = entry.path();
+
+        if file_type.is_symlink() {
+            continue;
+        }
+
+        if file_type.is_dir() {
+            walk(root, &path, persisted, files)?;
+        } else {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+
+            files.push(File {
+                path: relative.display().to_string(),
+                size: Size::new(entry.metadata()?.len()),
+                persisted,
+            });
+        }
+    }
+
+    Ok(())
+}
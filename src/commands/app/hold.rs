@@ -0,0 +1,81 @@
+use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use itertools::Itertools;
+use sprinkles::{contexts::ScoopContext, packages::reference::package};
+
+use crate::{
+    abandon,
+    commands::completions,
+    install,
+    output::colours::{eprintln_green, eprintln_yellow},
+};
+
+#[derive(Debug, Clone, Parser)]
+/// Hold the specified app(s), so that they are not updated
+pub struct Args {
+    #[clap(
+        help = "The app(s) to hold",
+        required_unless_present = "all",
+        add = ArgValueCandidates::new(completions::installed_apps)
+    )]
+    apps: Vec<package::Reference>,
+
+    #[clap(short, long, help = "Hold all installed apps", conflicts_with = "apps")]
+    all: bool,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        set_held(ctx, &self.apps, self.all, true)
+    }
+}
+
+/// Hold or unhold the given apps, or every installed app if `all` is true, and report each app's new state
+///
+/// # Errors
+/// - Listing the installed apps failed
+/// - Any of the apps could not be held or unheld
+pub fn set_held(
+    ctx: &impl ScoopContext,
+    apps: &[package::Reference],
+    all: bool,
+    held: bool,
+) -> anyhow::Result<()> {
+    let names = if all {
+        super::installed_names(ctx)?
+    } else {
+        super::reference_names(apps)
+    };
+
+    let mut failed = vec![];
+
+    for name in names.into_iter().unique() {
+        let app_dir = ctx.apps_path().join(&name);
+
+        if !app_dir.exists() {
+            eprintln_yellow!("{name} is not installed");
+            continue;
+        }
+
+        match install::set_held(&app_dir, held) {
+            Ok(true) if held => eprintln_green!("{name} is now held, and will not be updated"),
+            Ok(true) => eprintln_green!("{name} is no longer held, and will be updated"),
+            Ok(false) if held => eprintln!("{name} is already held"),
+            Ok(false) => eprintln!("{name} is not held"),
+            Err(e) => {
+                eprintln_yellow!("Failed to update the hold state of {name}: {e}");
+                failed.push(name);
+            }
+        }
+    }
+
+    if !failed.is_empty() {
+        abandon!(
+            "Failed to {} {}",
+            if held { "hold" } else { "unhold" },
+            failed.join(", ")
+        );
+    }
+
+    Ok(())
+}
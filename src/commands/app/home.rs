@@ -1,27 +1,51 @@
 use clap::Parser;
 use sprinkles::{contexts::ScoopContext, packages::reference::package};
 
-use crate::abandon;
+use crate::{abandon, output::colours::eprintln_yellow};
 
 #[derive(Debug, Clone, Parser)]
 /// Opens the app homepage
 pub struct Args {
-    #[clap(help = "The package to open the homepage for")]
-    package: package::Reference,
+    #[clap(help = "The package(s) to open the homepage for", required = true)]
+    packages: Vec<package::Reference>,
+
+    #[clap(
+        short,
+        long,
+        help = "Print the homepage URL(s) instead of opening them"
+    )]
+    print: bool,
 }
 
 impl super::Command for Args {
     async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
-        let manifest = self
-            .package
-            .first(ctx)
-            .ok_or(anyhow::anyhow!("Package not found"))?;
+        let mut homepages = vec![];
+
+        for package in &self.packages {
+            let Some(manifest) = package.first(ctx) else {
+                eprintln_yellow!("Package {package} not found");
+                continue;
+            };
+
+            let Some(homepage) = manifest.homepage else {
+                eprintln_yellow!("No homepage found for {package}");
+                continue;
+            };
+
+            homepages.push(homepage);
+        }
 
-        let Some(homepage) = manifest.homepage else {
-            abandon!("No homepage found for package");
-        };
+        if homepages.is_empty() {
+            abandon!("No homepages found");
+        }
 
-        open::that_detached(homepage)?;
+        for homepage in homepages {
+            if self.print {
+                println!("{homepage}");
+            } else {
+                open::that_detached(homepage)?;
+            }
+        }
 
         Ok(())
     }
@@ -1,4 +1,5 @@
 use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
 use itertools::Itertools;
 
 use sprinkles::{
@@ -13,8 +14,9 @@ use sprinkles::{
 
 use crate::{
     abandon,
+    commands::completions,
     models::info::Package,
-    output::structured::vertical::VTable,
+    output::{format::Format, structured::vertical::VTable},
     wrappers::{bool::NicerBool, time::NicerTime},
 };
 
@@ -23,7 +25,10 @@ use crate::{
 // TODO: Pass architecture
 /// Display information about a package
 pub struct Args {
-    #[clap(help = "The package to get info from")]
+    #[clap(
+        help = "The package to get info from",
+        add = ArgValueCandidates::new(completions::installed_apps)
+    )]
     package: package::Reference,
 
     #[cfg(not(feature = "v2"))]
@@ -41,7 +46,7 @@ pub struct Args {
     hide_emails: bool,
 
     #[clap(from_global)]
-    json: bool,
+    format: Format,
 
     #[clap(from_global)]
     verbose: bool,
@@ -172,9 +177,8 @@ impl Args {
         };
 
         let value = serde_json::to_value(pkg_info)?;
-        if self.json {
-            let output = serde_json::to_string_pretty(&value)?;
-            println!("{output}");
+        if self.format.is_structured() {
+            self.format.print(&value)?;
         } else {
             let table = VTable::new(&value);
             println!("{table}");
@@ -0,0 +1,709 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    time::Duration,
+};
+
+use clap::Parser;
+use itertools::Itertools;
+
+use sprinkles::{
+    Architecture,
+    cache::{DownloadHandle, Handle},
+    contexts::ScoopContext,
+    packages::{
+        Manifest,
+        downloading::Downloader,
+        reference::{manifest, package},
+    },
+    progress::indicatif::{MultiProgress, ProgressBar},
+    requests::AsyncClient,
+};
+
+use crate::{
+    abandon,
+    commands::verify::CachedHash,
+    install::{
+        self,
+        config::InstallConfig,
+        env, extract, installer, persist, psmodule,
+        scripts::{self, ScriptVariables},
+        shims::{self, Shim},
+        shortcuts,
+    },
+    output::colours::{eprintln_green, eprintln_yellow},
+};
+
+#[derive(Debug, Clone, Parser)]
+/// Install the specified app(s)
+pub struct Args {
+    #[clap(short = 'H', long, help = "Disable hash validation")]
+    no_hash_check: bool,
+
+    #[clap(long, help = "Don't run any of the scripts in the manifest")]
+    no_scripts: bool,
+
+    #[clap(
+        short = 'k',
+        long,
+        help = "Don't use the download cache, downloading fresh copies and removing them once installed"
+    )]
+    no_cache: bool,
+
+    #[clap(short, long, help = "Don't install dependencies automatically")]
+    independent: bool,
+
+    #[clap(help = "The packages to install", required = true)]
+    apps: Vec<package::Reference>,
+
+    #[clap(from_global)]
+    arch: Architecture,
+}
+
+impl super::Command for Args {
+    const BETA: bool = true;
+    const NEEDS_NETWORK: bool = true;
+
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        if self.no_hash_check {
+            eprintln_yellow!(
+                "Hash check has been disabled! This may allow modified files to be installed"
+            );
+        }
+
+        if self.no_scripts {
+            eprintln_yellow!(
+                "Manifest scripts have been disabled! Apps that rely on them may not work correctly"
+            );
+        }
+
+        let mut plan = vec![];
+        let mut seen = HashSet::new();
+        // The urls of manifests installed directly from a url, keyed by app name
+        let mut urls = HashMap::new();
+
+        for reference in &self.apps {
+            if reference.installed(ctx)? {
+                eprintln_yellow!("{reference} is already installed");
+                continue;
+            }
+
+            let manifest = match reference.manifest(ctx).await {
+                Ok(manifest) => manifest,
+                Err(e) => abandon!("Failed to find manifest for {reference}: {e}"),
+            };
+
+            let name = unsafe { manifest.name() }.to_string();
+
+            if !seen.insert(name.clone()) {
+                continue;
+            }
+
+            if let manifest::Reference::Url(url) = &reference.manifest {
+                urls.insert(name, url.to_string());
+            }
+
+            if self.independent {
+                warn_missing_dependencies(ctx, &manifest)?;
+            } else {
+                resolve_dependencies(ctx, &manifest, &mut plan, &mut seen)?;
+            }
+
+            plan.push(manifest);
+        }
+
+        if plan.len() > 1 {
+            eprintln!(
+                "Installing {} apps: {}",
+                plan.len(),
+                plan.iter()
+                    .map(|manifest| unsafe { manifest.name() })
+                    .join(", ")
+            );
+        }
+
+        for manifest in &plan {
+            let url = urls.get(unsafe { manifest.name() }).map(String::as_str);
+
+            self.install(ctx, manifest, url).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The version used by manifests that always install the latest build
+const NIGHTLY: &str = "nightly";
+
+/// Get the name of the directory to install the given version to
+///
+/// Nightly builds are installed to a directory suffixed with the current date, as Scoop does,
+/// so that each update gets a fresh directory
+fn version_dir_name(version: &str) -> String {
+    if version == NIGHTLY {
+        format!("{NIGHTLY}-{}", chrono::Local::now().format("%Y%m%d"))
+    } else {
+        version.to_string()
+    }
+}
+
+/// Resolve the uninstalled dependencies of a manifest, in the order they should be installed
+///
+/// Dependencies are added to `plan` before the apps that depend on them.
+/// Apps in `seen` are skipped, which also prevents cyclic dependencies from recursing infinitely
+fn resolve_dependencies(
+    ctx: &impl ScoopContext,
+    manifest: &Manifest,
+    plan: &mut Vec<Manifest>,
+    seen: &mut HashSet<String>,
+) -> anyhow::Result<()> {
+    for dependency in manifest.depends() {
+        let reference = dependency.into_package_ref();
+
+        let Some(name) = reference.name() else {
+            continue;
+        };
+
+        if !seen.insert(name) || reference.installed(ctx)? {
+            continue;
+        }
+
+        let Some(dependency) = reference.first(ctx) else {
+            abandon!(
+                "Could not find {reference}, which is required by {}",
+                unsafe { manifest.name() }
+            );
+        };
+
+        resolve_dependencies(ctx, &dependency, plan, seen)?;
+        plan.push(dependency);
+    }
+
+    Ok(())
+}
+
+/// Warn about any dependencies of a manifest that are not installed
+fn warn_missing_dependencies(ctx: &impl ScoopContext, manifest: &Manifest) -> anyhow::Result<()> {
+    for dependency in manifest.depends() {
+        let reference = dependency.into_package_ref();
+
+        if !reference.installed(ctx)? {
+            eprintln_yellow!(
+                "{} depends on {reference}, which is not installed",
+                unsafe { manifest.name() }
+            );
+        }
+    }
+
+    Ok(())
+}
+
+impl Args {
+    /// Create the arguments to install the given apps
+    pub fn new(apps: Vec<package::Reference>, arch: Architecture) -> Self {
+        Self {
+            no_hash_check: false,
+            no_scripts: false,
+            no_cache: false,
+            independent: false,
+            apps,
+            arch,
+        }
+    }
+
+    /// Skip validating the hashes of downloaded files
+    pub fn no_hash_check(mut self, no_hash_check: bool) -> Self {
+        self.no_hash_check = no_hash_check;
+        self
+    }
+
+    /// Skip running the scripts in the manifest
+    pub fn no_scripts(mut self, no_scripts: bool) -> Self {
+        self.no_scripts = no_scripts;
+        self
+    }
+
+    async fn install(
+        &self,
+        ctx: &impl ScoopContext,
+        manifest: &Manifest,
+        url: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let name = unsafe { manifest.name() }.to_string();
+        let version = manifest.version.to_string();
+
+        eprintln!("Installing {name} ({version}) [{}]", self.arch);
+
+        // Nightly builds change constantly, so their manifests don't provide hashes
+        if !self.no_hash_check && version != NIGHTLY {
+            validate_hashes(&name, &InstallConfig::from_manifest(manifest, self.arch)?)?;
+        }
+
+        if self.no_cache {
+            remove_cached(ctx, manifest, self.arch)?;
+        }
+
+        let installed = match download(ctx, &[(manifest, self.arch)], self.no_hash_check).await {
+            Ok(()) => self.install_downloaded(ctx, manifest, url),
+            Err(e) => Err(e),
+        };
+
+        if self.no_cache {
+            remove_cached(ctx, manifest, self.arch)?;
+        }
+
+        let config = installed?;
+
+        eprintln_green!("{name} ({version}) was installed successfully!");
+
+        show_notes(&config);
+
+        Ok(())
+    }
+
+    /// Install an app whose files have already been downloaded to the cache
+    ///
+    /// If the manifest was downloaded from a url, it is recorded in place of the bucket, as Scoop does.
+    /// Changes are rolled back if any step of the install fails.
+    /// Returns the install config, so the caller can show the manifest's notes
+    ///
+    /// # Errors
+    /// - The app does not support the selected architecture
+    /// - Any step of the install failed
+    pub fn install_downloaded(
+        &self,
+        ctx: &impl ScoopContext,
+        manifest: &Manifest,
+        url: Option<&str>,
+    ) -> anyhow::Result<InstallConfig> {
+        let name = unsafe { manifest.name() }.to_string();
+        let version = manifest.version.to_string();
+
+        let config = InstallConfig::from_manifest(manifest, self.arch)?;
+
+        if config.url.clone().into_vec().is_empty() {
+            anyhow::bail!("{name} does not support {}", self.arch);
+        }
+
+        let pb = ProgressBar::new_spinner();
+        pb.enable_steady_tick(Duration::from_millis(100));
+
+        let app_dir = ctx.apps_path().join(&name);
+        let version_dir = app_dir.join(version_dir_name(&version));
+
+        if version_dir.exists() {
+            // Left over from a previous install that was interrupted or removed without Scoop
+            debug!("Removing stale version directory {}", version_dir.display());
+            std::fs::remove_dir_all(&version_dir)?;
+        }
+
+        // Read before the new version is linked, so updates keep the app held or pinned
+        let user_info = install::user_install_info(&app_dir);
+
+        let installed = self
+            .install_files(ctx, manifest, &config, &app_dir, &version_dir, &pb)
+            .and_then(|()| {
+                pb.set_message("Writing install info");
+                write_install_info(&version_dir, manifest, self.arch, url, user_info)
+            });
+
+        if let Err(e) = installed {
+            pb.finish_and_clear();
+
+            eprintln_yellow!("Failed to install {name}, rolling back changes");
+            if let Err(rollback_error) =
+                rollback(ctx, &config, &app_dir, &version_dir, crate::is_global())
+            {
+                error!("Failed to roll back {name}: {rollback_error}");
+            }
+
+            return Err(e);
+        }
+
+        pb.finish_and_clear();
+
+        Ok(config)
+    }
+
+    /// Extract the downloaded files and link the app into place
+    fn install_files(
+        &self,
+        ctx: &impl ScoopContext,
+        manifest: &Manifest,
+        config: &InstallConfig,
+        app_dir: &Path,
+        version_dir: &Path,
+        pb: &ProgressBar,
+    ) -> anyhow::Result<()> {
+        let name = unsafe { manifest.name() }.to_string();
+        let version = manifest.version.to_string();
+        // Differs from the manifest version for nightly builds
+        let installed_version = version_dir.file_name().map_or_else(
+            || version.clone(),
+            |name| name.to_string_lossy().to_string(),
+        );
+
+        pb.set_message("Extracting files");
+        for (index, url) in config.url.clone().into_vec().iter().enumerate() {
+            let file = install::cache_file(ctx, &name, &version, url);
+
+            if !file.exists() {
+                anyhow::bail!("Could not find downloaded file for {url}");
+            }
+
+            extract::extract(
+                &file,
+                url,
+                &config.extract_to(version_dir, index),
+                config.extract_dir.get(index).map(String::as_str),
+                config.innosetup,
+            )?;
+        }
+
+        let persist_dir = ctx.persist_path().join(&name);
+        let mut variables = ScriptVariables {
+            app: &name,
+            version: &installed_version,
+            dir: version_dir,
+            original_dir: version_dir,
+            persist_dir: &persist_dir,
+            architecture: self.arch.to_string(),
+            global: crate::is_global(),
+            cmd: "install",
+        };
+
+        if let Some(script) = config.pre_install.to_script().filter(|_| !self.no_scripts) {
+            pb.set_message("Running pre-install script");
+            scripts::run(ctx, "pre_install", &script, &variables)?;
+        }
+
+        if let Some(manifest_installer) = &config.installer {
+            if manifest_installer.has_program() {
+                pb.set_message("Running installer");
+                // Scoop runs the first download if the installer doesn't name a file
+                let default_file = config.url.get(0).map(|url| extract::url_file_name(url));
+                installer::run(
+                    "installer",
+                    manifest_installer,
+                    default_file.as_deref(),
+                    &variables,
+                )?;
+            }
+
+            if let Some(script) = manifest_installer
+                .script
+                .to_script()
+                .filter(|_| !self.no_scripts)
+            {
+                pb.set_message("Running installer script");
+                scripts::run(ctx, "installer", &script, &variables)?;
+            }
+        }
+
+        pb.set_message("Linking current version");
+        let current_dir = install::link_current(app_dir, version_dir)?;
+
+        pb.set_message("Creating shims");
+        for bin in config.bins() {
+            Shim::new(&bin, &current_dir).create(ctx)?;
+        }
+
+        if let Some(psmodule) = &config.psmodule {
+            pb.set_message("Linking PowerShell module");
+            psmodule::link(ctx, &psmodule.name, &current_dir)?;
+        }
+
+        variables.dir = &current_dir;
+
+        if !config.shortcuts.is_empty() {
+            pb.set_message("Creating shortcuts");
+            for shortcut in &config.shortcuts {
+                shortcuts::create(shortcut, &variables)?;
+            }
+        }
+
+        let paths = env::resolve_paths(&config.env_add_path.clone().into_vec(), &current_dir);
+        if !paths.is_empty() || !config.env_set.is_empty() {
+            pb.set_message("Setting environment variables");
+            env::add_path(&paths, crate::is_global())?;
+
+            for (name, value) in &config.env_set {
+                env::set_var(name, &variables.substitute(value), crate::is_global())?;
+            }
+        }
+
+        let persist = config.persist();
+        if !persist.is_empty() {
+            pb.set_message("Persisting data");
+            persist::link(&persist, version_dir, &persist_dir)?;
+        }
+
+        if let Some(script) = config.post_install.to_script().filter(|_| !self.no_scripts) {
+            pb.set_message("Running post-install script");
+            scripts::run(ctx, "post_install", &script, &variables)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Download the files for each manifest concurrently, and validate their hashes
+///
+/// Each manifest is downloaded for the architecture it is paired with
+///
+/// # Errors
+/// - The downloads could not be started or failed
+/// - A downloaded file's hash did not match the manifest's hash
+pub async fn download(
+    ctx: &impl ScoopContext,
+    manifests: &[(&Manifest, Architecture)],
+    no_hash_check: bool,
+) -> anyhow::Result<()> {
+    let mp = MultiProgress::new();
+
+    let mut downloads = vec![];
+
+    for (manifest, arch) in manifests {
+        let name = unsafe { manifest.name() }.to_string();
+        let version = manifest.version.to_string();
+        // Nightly builds change constantly, so their manifests don't provide hashes
+        let skip_hash_check = no_hash_check || version == NIGHTLY;
+
+        let config = InstallConfig::from_manifest(manifest, *arch)?;
+        let urls = config.url.into_vec();
+        let hashes = config.hash.into_vec();
+
+        for (index, handle) in Handle::open_manifest(ctx.cache_path(), manifest, *arch)?
+            .into_iter()
+            .enumerate()
+        {
+            let cached_hash = urls
+                .get(index)
+                .zip(hashes.get(index))
+                .and_then(|(url, hash)| CachedHash::new(ctx, &name, &version, url, hash));
+
+            downloads.push((name.clone(), skip_hash_check, cached_hash, handle));
+        }
+    }
+
+    let downloaders = futures::future::try_join_all(downloads.into_iter().map(
+        |(name, skip_hash_check, cached_hash, handle)| {
+            let mp = mp.clone();
+            async move {
+                match DownloadHandle::new::<AsyncClient>(handle, Some(&mp), Some(name.clone()))
+                    .await
+                {
+                    Ok(dl) => anyhow::Ok((name, skip_hash_check, cached_hash, dl)),
+                    Err(sprinkles::cache::Error::ErrorCode(status)) => {
+                        anyhow::bail!("Found {status} error while downloading")
+                    }
+                    Err(e) => Err(e.into()),
+                }
+            }
+        },
+    ))
+    .await?;
+
+    let threads = downloaders
+        .into_iter()
+        .map(|(name, skip_hash_check, cached_hash, dl)| {
+            tokio::spawn(async move { (name, skip_hash_check, cached_hash, dl.download().await) })
+        });
+
+    for (name, skip_hash_check, cached_hash, result) in
+        futures::future::try_join_all(threads).await?
+    {
+        let result = result?;
+
+        if skip_hash_check {
+            continue;
+        }
+
+        if let Some(cached_hash) = cached_hash {
+            if let Some(actual) = cached_hash.mismatch()? {
+                anyhow::bail!(
+                    "🔓 Hash mismatch for {name}: expected {}, found {actual}",
+                    cached_hash.expected
+                );
+            }
+        } else if result.actual_hash != result.computed_hash {
+            anyhow::bail!(
+                "🔓 Hash mismatch for {name}: expected {}, found {}",
+                result.actual_hash.no_prefix(),
+                result.computed_hash.no_prefix()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove the cached downloads of a manifest, if they exist
+///
+/// # Errors
+/// - The manifest's install config could not be parsed
+/// - Removing a cached file failed
+fn remove_cached(
+    ctx: &impl ScoopContext,
+    manifest: &Manifest,
+    arch: Architecture,
+) -> anyhow::Result<()> {
+    let name = unsafe { manifest.name() };
+    let version = manifest.version.to_string();
+
+    for url in InstallConfig::from_manifest(manifest, arch)?.url.into_vec() {
+        let path = install::cache_file(ctx, name, &version, &url);
+
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Undo a partially completed install
+///
+/// Removes any shims pointing into the app directory, the app's shortcuts and PowerShell module,
+/// the environment variables set by the app, and the version directory.
+/// The app directory itself is only removed if nothing else is left in it
+///
+/// # Errors
+/// - Removing the shims, `current` junction or version directory failed
+fn rollback(
+    ctx: &impl ScoopContext,
+    config: &InstallConfig,
+    app_dir: &Path,
+    version_dir: &Path,
+    global: bool,
+) -> anyhow::Result<()> {
+    for bin in config.bins() {
+        shims::remove(ctx, &bin.name, app_dir)?;
+    }
+
+    for shortcut in config.shortcut_names() {
+        shortcuts::remove(shortcut, global)?;
+    }
+
+    if let Some(psmodule) = &config.psmodule {
+        psmodule::remove(ctx, &psmodule.name)?;
+    }
+
+    let paths = env::resolve_paths(
+        &config.env_add_path.clone().into_vec(),
+        &app_dir.join("current"),
+    );
+    env::remove_path(&paths, global)?;
+
+    for name in config.env_set.keys() {
+        env::remove_var(name, global)?;
+    }
+
+    let current = app_dir.join("current");
+    let links_to_version = install::read_link(&current).is_ok_and(|target| {
+        target == version_dir
+            || matches!(
+                (std::fs::canonicalize(&target), std::fs::canonicalize(version_dir)),
+                (Ok(target), Ok(version_dir)) if target == version_dir
+            )
+    });
+
+    if links_to_version {
+        std::fs::remove_dir(&current)?;
+    }
+
+    if version_dir.exists() {
+        std::fs::remove_dir_all(version_dir)?;
+    }
+
+    if std::fs::read_dir(app_dir).is_ok_and(|mut entries| entries.next().is_none()) {
+        std::fs::remove_dir(app_dir)?;
+    }
+
+    Ok(())
+}
+
+/// Print the manifest's notes and suggested apps after installing
+pub fn show_notes(config: &InstallConfig) {
+    if let Some(notes) = config.notes.to_script() {
+        eprintln!("Notes");
+        eprintln!("-----");
+        eprintln!("{notes}");
+    }
+
+    for (feature, apps) in &config.suggest {
+        let apps = apps.clone().into_vec();
+
+        if !apps.is_empty() {
+            eprintln_yellow!("{feature} is suggested, provided by: {}", apps.join(", "));
+        }
+    }
+}
+
+/// Check that the manifest provides a valid hash for each download
+///
+/// Manifests installed from a url or file have not been reviewed like bucket manifests,
+/// so a missing or malformed hash would otherwise let any file be installed
+///
+/// # Errors
+/// - The number of hashes does not match the number of downloads
+/// - A hash is malformed
+fn validate_hashes(name: &str, config: &InstallConfig) -> anyhow::Result<()> {
+    let urls = config.url.clone().into_vec();
+    let hashes = config.hash.clone().into_vec();
+
+    if hashes.len() != urls.len() {
+        anyhow::bail!(
+            "{name} provides {} hashes for {} downloads. Use --no-hash-check to install it anyway",
+            hashes.len(),
+            urls.len()
+        );
+    }
+
+    if let Some(hash) = hashes.iter().find(|hash| !install::is_valid_hash(hash)) {
+        anyhow::bail!(
+            "{name} has an invalid hash: {hash}. Use --no-hash-check to install it anyway"
+        );
+    }
+
+    Ok(())
+}
+
+/// Write the `manifest.json` and `install.json` files Scoop uses to track installed apps
+///
+/// Apps installed from a url record the url instead of a bucket.
+/// The fields set with `app hold` and `app pin` are carried over from the previous version
+fn write_install_info(
+    version_dir: &Path,
+    manifest: &Manifest,
+    arch: Architecture,
+    url: Option<&str>,
+    user_info: serde_json::Map<String, serde_json::Value>,
+) -> anyhow::Result<()> {
+    std::fs::write(
+        version_dir.join("manifest.json"),
+        serde_json::to_string_pretty(manifest)?,
+    )?;
+
+    let mut install_info = match url {
+        Some(url) => serde_json::json!({
+            "url": url,
+            "architecture": arch.to_string(),
+        }),
+        None => serde_json::json!({
+            "bucket": unsafe { manifest.bucket() },
+            "architecture": arch.to_string(),
+        }),
+    };
+
+    if let Some(install_info) = install_info.as_object_mut() {
+        install_info.extend(user_info);
+    }
+
+    std::fs::write(
+        version_dir.join("install.json"),
+        serde_json::to_string_pretty(&install_info)?,
+    )?;
+
+    Ok(())
+}
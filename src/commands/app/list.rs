@@ -1,9 +1,23 @@
 use clap::{Parser, ValueEnum};
+use clap_complete::engine::ArgValueCandidates;
 use rayon::prelude::*;
+#[cfg(not(feature = "v2"))]
+use regex::Regex;
 
 use sprinkles::contexts::ScoopContext;
 
-use crate::{models::min::Info, output::structured::Structured};
+#[cfg(feature = "contexts")]
+use crate::scope::Scope;
+#[cfg(not(feature = "v2"))]
+use crate::{calm_panic::CalmUnwrap, pattern};
+use crate::{
+    commands::completions,
+    models::min::Info,
+    output::{
+        format::Format,
+        structured::{self, Priority, Structured},
+    },
+};
 
 #[derive(Debug, Clone, Parser)]
 /// List all installed packages
@@ -14,7 +28,20 @@ pub struct Args {
     )]
     pattern: Option<String>,
 
-    #[clap(short, long, help = "The bucket to exclusively list packages in")]
+    #[cfg(not(feature = "v2"))]
+    #[clap(
+        long,
+        help = "Treat the pattern as a glob, where `*` matches any characters and `?` matches one character, rather than a regex",
+        requires = "pattern"
+    )]
+    glob: bool,
+
+    #[clap(
+        short,
+        long,
+        help = "The bucket to exclusively list packages in",
+        add = ArgValueCandidates::new(completions::buckets)
+    )]
     bucket: Option<String>,
 
     #[clap(long, help = "Sort by the given field", default_value = "name")]
@@ -23,8 +50,18 @@ pub struct Args {
     #[clap(long, help = "Sort in descending order")]
     descending: bool,
 
+    #[cfg(feature = "contexts")]
+    #[clap(
+        long,
+        help = "List apps from the user install, the global install, or both. Each app is annotated with its install"
+    )]
+    scope: Option<Scope>,
+
+    #[clap(flatten)]
+    table: structured::Options,
+
     #[clap(from_global)]
-    json: bool,
+    format: Format,
 }
 
 #[derive(Debug, Copy, Clone, ValueEnum)]
@@ -38,7 +75,12 @@ pub enum SortBy {
 
 impl super::Command for Args {
     async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
-        let mut outputs = Info::list_installed(ctx, self.bucket.as_ref())?;
+        let mut outputs = self.list_installed(ctx)?;
+
+        #[cfg(not(feature = "v2"))]
+        if let Some(pattern) = self.name_pattern() {
+            outputs.retain(|info| pattern.is_match(&info.name));
+        }
 
         outputs.par_sort_by(|a, b| match self.sort_by {
             SortBy::Name => a.name.cmp(&b.name),
@@ -52,10 +94,8 @@ impl super::Command for Args {
             outputs.reverse();
         }
 
-        if self.json {
-            let output_json = serde_json::to_string_pretty(&outputs)?;
-
-            println!("{output_json}");
+        if self.format.is_structured() {
+            self.format.print(&outputs)?;
         } else {
             if outputs.is_empty() {
                 println!("No packages found.");
@@ -67,7 +107,12 @@ impl super::Command for Args {
                 .map(serde_json::to_value)
                 .collect::<Result<Vec<_>, _>>()?;
 
-            let outputs = Structured::new(&values);
+            let outputs = Structured::new(&values)
+                .with_priority("version", Priority::High)
+                .with_priority("source", Priority::Low)
+                .with_priority("notes", Priority::Low)
+                .with_priority("scope", Priority::High)
+                .with_options(&self.table);
 
             print!("{outputs}");
         }
@@ -75,3 +120,40 @@ impl super::Command for Args {
         Ok(())
     }
 }
+
+impl Args {
+    #[cfg(not(feature = "v2"))]
+    fn name_pattern(&self) -> Option<Regex> {
+        let pattern = self.pattern.as_ref()?;
+
+        let pattern = if self.glob {
+            pattern::glob_to_regex(pattern)
+        } else {
+            pattern.clone()
+        };
+
+        Some(Regex::new(&format!("(?i){pattern}")).calm_expect(
+            "Invalid Regex provided. See https://docs.rs/regex/latest/regex/ for more info",
+        ))
+    }
+
+    fn list_installed(&self, ctx: &impl ScoopContext) -> anyhow::Result<Vec<Info>> {
+        #[cfg(feature = "contexts")]
+        if let Some(scope) = self.scope {
+            let mut outputs = vec![];
+
+            for (scope, ctx) in scope.contexts()? {
+                let infos = Info::list_installed(&ctx, self.bucket.as_ref())?;
+
+                outputs.extend(infos.into_iter().map(|info| Info {
+                    scope: Some(scope),
+                    ..info
+                }));
+            }
+
+            return Ok(outputs);
+        }
+
+        Info::list_installed(ctx, self.bucket.as_ref())
+    }
+}
@@ -0,0 +1,160 @@
+use chrono::{Local, NaiveDate};
+use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use itertools::Itertools;
+use rayon::prelude::*;
+use schemars::JsonSchema;
+use serde::Serialize;
+use sprinkles::{Architecture, contexts::ScoopContext, packages::reference::package};
+
+use crate::{
+    commands::completions,
+    install::{self, config::InstallConfig, scripts::ScriptVariables},
+    output::{
+        colours::eprintln_yellow,
+        format::Format,
+        sectioned::{Children, Section, Sections, Text},
+    },
+    wrappers::time::NicerTime,
+};
+
+#[derive(Debug, Clone, Parser)]
+/// Show the notes of installed apps, which are otherwise only shown once after installing
+pub struct Args {
+    #[clap(
+        help = "The apps to show the notes of. Defaults to all installed apps",
+        add = ArgValueCandidates::new(completions::installed_apps)
+    )]
+    apps: Vec<package::Reference>,
+
+    #[clap(
+        long,
+        help = "Only show the notes of apps installed or updated on or after the given date (YYYY-MM-DD)"
+    )]
+    changed_since: Option<NaiveDate>,
+
+    #[clap(from_global)]
+    arch: Architecture,
+
+    #[clap(from_global)]
+    format: Format,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+/// The notes of an installed app
+pub(crate) struct Notes {
+    name: String,
+    version: String,
+    /// The last time the app was installed or updated
+    #[schemars(with = "String")]
+    updated: NicerTime<Local>,
+    /// The notes, with script variables such as `$dir` substituted
+    notes: Vec<String>,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let names = super::names_or_installed(ctx, &self.apps)?;
+
+        let mut notes = names
+            .into_par_iter()
+            .filter_map(|name| self.app_notes(ctx, name))
+            .filter(|notes| {
+                self.changed_since
+                    .is_none_or(|since| notes.updated.date_naive() >= since)
+            })
+            .collect::<Vec<_>>();
+
+        notes.sort_by(|a, b| a.name.cmp(&b.name));
+
+        if self.format.is_structured() {
+            self.format.print(&notes)?;
+            return Ok(());
+        }
+
+        if notes.is_empty() {
+            println!("No notes found");
+            return Ok(());
+        }
+
+        let sections: Sections<_> = notes
+            .into_iter()
+            .map(|notes| {
+                let lines = notes.notes.into_iter().map(Text::new).collect_vec();
+
+                Section::new(Children::from(lines)).with_title(format!(
+                    "{} ({}, updated {}):",
+                    notes.name, notes.version, notes.updated
+                ))
+            })
+            .collect();
+
+        print!("{sections}");
+
+        Ok(())
+    }
+}
+
+impl Args {
+    /// Read the notes from an installed app's manifest, if it has any
+    fn app_notes(&self, ctx: &impl ScoopContext, name: String) -> Option<Notes> {
+        let app_dir = ctx.apps_path().join(&name);
+
+        if !app_dir.exists() {
+            eprintln_yellow!("{name} is not installed");
+            return None;
+        }
+
+        let Ok((manifest, arch)) = install::installed_manifest(&app_dir) else {
+            debug!("Skipping {name}, as its manifest could not be read");
+            return None;
+        };
+
+        let arch = arch.unwrap_or(self.arch);
+
+        let Ok(config) = InstallConfig::from_manifest(&manifest, arch) else {
+            debug!("Skipping {name}, as its manifest could not be parsed");
+            return None;
+        };
+
+        let notes = config.notes.into_vec();
+
+        if notes.is_empty() {
+            return None;
+        }
+
+        let current_dir = app_dir.join("current");
+
+        // The install info is rewritten on every install and update
+        let updated = current_dir
+            .join("install.json")
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()?;
+
+        let version = manifest.version.to_string();
+        let persist_dir = ctx.persist_path().join(&name);
+        let variables = ScriptVariables {
+            app: &name,
+            version: &version,
+            dir: &current_dir,
+            original_dir: &current_dir,
+            persist_dir: &persist_dir,
+            architecture: arch.to_string(),
+            global: crate::is_global(),
+            cmd: "notes",
+        };
+
+        let notes = notes
+            .iter()
+            .map(|line| variables.substitute(line))
+            .collect();
+
+        Some(Notes {
+            name,
+            version,
+            updated: updated.into(),
+            notes,
+        })
+    }
+}
@@ -0,0 +1,65 @@
+use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use sprinkles::{contexts::ScoopContext, packages::reference::package};
+
+use crate::{
+    abandon,
+    commands::completions,
+    install,
+    output::colours::{eprintln_green, eprintln_yellow},
+};
+
+#[derive(Debug, Clone, Parser)]
+/// Pin the specified app(s) to a version, so that they are not updated past it
+pub struct Args {
+    #[clap(
+        help = "The app(s) to pin. Use `app@version` to pin to a specific version, otherwise the installed version is used",
+        required = true,
+        add = ArgValueCandidates::new(completions::installed_apps)
+    )]
+    apps: Vec<package::Reference>,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let mut failed = vec![];
+
+        for reference in &self.apps {
+            let name = super::reference_name(reference);
+
+            let app_dir = ctx.apps_path().join(&name);
+
+            if !app_dir.exists() {
+                eprintln_yellow!("{name} is not installed");
+                continue;
+            }
+
+            let version = match &reference.version {
+                Some(version) => version.clone(),
+                None => match install::installed_manifest(&app_dir) {
+                    Ok((manifest, _)) => manifest.version.to_string(),
+                    Err(e) => {
+                        eprintln_yellow!("Could not read the installed version of {name}: {e}");
+                        failed.push(name);
+                        continue;
+                    }
+                },
+            };
+
+            match install::set_pinned(&app_dir, Some(&version)) {
+                Ok(true) => eprintln_green!("{name} is now pinned to {version}"),
+                Ok(false) => eprintln!("{name} is already pinned to {version}"),
+                Err(e) => {
+                    eprintln_yellow!("Failed to pin {name}: {e}");
+                    failed.push(name);
+                }
+            }
+        }
+
+        if !failed.is_empty() {
+            abandon!("Failed to pin {}", failed.join(", "));
+        }
+
+        Ok(())
+    }
+}
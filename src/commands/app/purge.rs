@@ -1,6 +1,12 @@
-use std::{collections::HashMap, ops::Deref};
+use std::{
+    collections::HashMap,
+    ops::Deref,
+    path::{Path, PathBuf},
+};
 
+use chrono::Local;
 use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
 use dialoguer::Confirm;
 use rayon::prelude::*;
 use sprinkles::{
@@ -12,14 +18,25 @@ use sprinkles::{
     progress::{indicatif::ProgressBar, style},
 };
 
-use crate::output::colours::{eprintln_yellow, yellow};
+use crate::{
+    commands::completions,
+    output::colours::{eprintln_yellow, yellow},
+};
 
 #[derive(Debug, Clone, Parser)]
 /// Purge package's persist folder
 pub struct Args {
-    #[clap(help = "The package to purge")]
+    #[clap(help = "The package to purge", add = ArgValueCandidates::new(completions::installed_apps))]
     apps: Vec<package::Reference>,
 
+    #[clap(
+        long,
+        help = "Move the persist folders to a timestamped folder in the given directory, rather than deleting them. Defaults to `persist_backups` in the Scoop directory",
+        value_name = "DIR",
+        num_args = 0..=1
+    )]
+    backup: Option<Option<PathBuf>>,
+
     #[clap(from_global)]
     assume_yes: bool,
 
@@ -107,6 +124,12 @@ impl super::Command for Args {
             return Ok(())
         }
 
+        let backup_dir = self.backup.as_ref().map(|backup_dir| {
+            backup_dir
+                .clone()
+                .unwrap_or_else(|| ctx.persist_path().with_file_name("persist_backups"))
+        });
+
         // if app_paths.len() == 1
         //     && let Some((app, path)) = app_paths.values().next()
 
@@ -114,9 +137,12 @@ impl super::Command for Args {
         if let (1, Some((app, path))) = (app_paths.len(), app_paths.values().next()) {
             eprintln_yellow!("Purging persist folder for {}", unsafe { app.name() });
 
-            if !self.dry_run {
-                std::fs::remove_dir_all(path)?;
+            if let Some(backup_dir) = &backup_dir {
+                let backup_path = backup(path, backup_dir, self.dry_run)?;
+                eprintln!("{} to {}", backed_up(self.dry_run), backup_path.display());
             }
+
+            purge(path, self.dry_run)?;
         } else {
             let pb = ProgressBar::new(app_paths.len() as u64).with_style(style(None, None));
 
@@ -128,9 +154,17 @@ impl super::Command for Args {
 
                 if self.dry_run {
                     std::thread::sleep(std::time::Duration::from_millis(100));
-                } else {
-                    std::fs::remove_dir_all(persist_path)?;
                 }
+
+                if let Some(backup_dir) = &backup_dir {
+                    backup(persist_path, backup_dir, self.dry_run)?;
+                }
+
+                purge(persist_path, self.dry_run)?;
+            }
+
+            if let Some(backup_dir) = &backup_dir {
+                eprintln!("{} to {}", backed_up(self.dry_run), backup_dir.display());
             }
         }
 
@@ -138,6 +172,96 @@ impl super::Command for Args {
     }
 }
 
+/// Remove an app's persist folder
+///
+/// Does nothing if `dry_run` is true or the folder does not exist
+///
+/// # Errors
+/// - Removing the folder failed
+pub fn purge(persist_path: &std::path::Path, dry_run: bool) -> std::io::Result<()> {
+    if dry_run || !persist_path.exists() {
+        return Ok(());
+    }
+
+    std::fs::remove_dir_all(persist_path)
+}
+
+/// Describe a backup, which only happens if `dry_run` is false
+fn backed_up(dry_run: bool) -> &'static str {
+    if dry_run {
+        "Would back up"
+    } else {
+        "Backed up"
+    }
+}
+
+/// Move an app's persist folder into a timestamped folder in the backup directory
+///
+/// Returns the path of the backup. Nothing is moved if `dry_run` is true.
+/// If the folder has to be copied and the copy fails, the partial backup is removed and the folder is left in place
+///
+/// # Errors
+/// - Creating the backup directory failed
+/// - Moving or copying the folder failed
+pub fn backup(persist_path: &Path, backup_dir: &Path, dry_run: bool) -> std::io::Result<PathBuf> {
+    let name = persist_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let backup_path = backup_dir.join(format!("{name}-{}", Local::now().format("%Y%m%d-%H%M%S")));
+
+    if dry_run {
+        return Ok(backup_path);
+    }
+
+    std::fs::create_dir_all(backup_dir)?;
+
+    // Renaming fails if the backup directory is on another drive, so fall back to copying
+    if std::fs::rename(persist_path, &backup_path).is_err() {
+        if let Err(e) = copy_dir(persist_path, &backup_path) {
+            if let Err(e) = std::fs::remove_dir_all(&backup_path) {
+                warn!(
+                    "Failed to remove partial backup {}: {e}",
+                    backup_path.display()
+                );
+            }
+
+            return Err(e);
+        }
+    }
+
+    Ok(backup_path)
+}
+
+/// Recursively copy a directory
+///
+/// Fails on symlinks and junctions, as their targets would not be backed up
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = to.join(entry.file_name());
+
+        if file_type.is_symlink() {
+            return Err(std::io::Error::other(format!(
+                "cannot back up {}, as it is a symlink or junction",
+                entry.path().display()
+            )));
+        }
+
+        if file_type.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+
+    Ok(())
+}
+
 struct AppPaths(HashMap<manifest::Reference, (Manifest, std::path::PathBuf)>);
 
 impl AppPaths {
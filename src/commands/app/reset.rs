@@ -0,0 +1,220 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use itertools::Itertools;
+use sprinkles::{
+    Architecture,
+    contexts::ScoopContext,
+    packages::{Manifest, reference::package},
+};
+
+use crate::{
+    abandon,
+    commands::completions,
+    install::{
+        self, config::InstallConfig, env, psmodule, scripts::ScriptVariables, shims::Shim,
+        shortcuts,
+    },
+    output::colours::{eprintln_green, eprintln_red, eprintln_yellow},
+};
+
+#[derive(Debug, Clone, Parser)]
+/// Repair the specified app(s), by relinking the current version and recreating its shims, shortcuts and environment variables
+pub struct Args {
+    #[clap(
+        help = "The app(s) to reset",
+        required_unless_present = "all",
+        add = ArgValueCandidates::new(completions::installed_apps)
+    )]
+    apps: Vec<package::Reference>,
+
+    #[clap(
+        short,
+        long,
+        help = "Reset all installed apps",
+        conflicts_with = "apps"
+    )]
+    all: bool,
+
+    #[clap(
+        short = 'n',
+        long,
+        help = "Print what would be done, without changing anything"
+    )]
+    dry_run: bool,
+
+    #[clap(from_global)]
+    arch: Architecture,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        // Global apps link into system directories and modify the system environment
+        if crate::is_global() && !self.dry_run && !quork::root::is_root()? {
+            abandon!("Resetting global apps requires elevation. Please run as an administrator.");
+        }
+
+        let names = if self.all {
+            super::installed_names(ctx)?
+        } else {
+            super::reference_names(&self.apps)
+        };
+
+        let mut failed = vec![];
+
+        for name in names.into_iter().unique() {
+            if let Err(e) = self.reset(ctx, &name) {
+                eprintln_red!("Failed to reset {name}: {e}");
+                failed.push(name);
+            }
+        }
+
+        if !failed.is_empty() {
+            abandon!("Failed to reset {}", failed.join(", "));
+        }
+
+        Ok(())
+    }
+}
+
+impl Args {
+    fn reset(&self, ctx: &impl ScoopContext, name: &str) -> anyhow::Result<()> {
+        let app_dir = ctx.apps_path().join(name);
+
+        if !app_dir.exists() {
+            eprintln_yellow!("{name} is not installed");
+            return Ok(());
+        }
+
+        let Some(version_dir) = version_dir(&app_dir) else {
+            anyhow::bail!("Could not find an installed version of {name}");
+        };
+
+        let manifest = Manifest::from_path(version_dir.join("manifest.json"))?;
+        let version = manifest.version.to_string();
+        let arch = install::version_architecture(&version_dir).unwrap_or(self.arch);
+        let config = InstallConfig::from_manifest(&manifest, arch)?;
+
+        eprintln!(
+            "{} {name} ({version})",
+            if self.dry_run {
+                "Would reset"
+            } else {
+                "Resetting"
+            }
+        );
+
+        let current_dir = app_dir.join("current");
+
+        self.step(
+            format_args!(
+                "link {} to {}",
+                current_dir.display(),
+                version_dir.display()
+            ),
+            || {
+                install::link_current(&app_dir, &version_dir)?;
+                Ok(())
+            },
+        )?;
+
+        for bin in config.bins() {
+            self.step(format_args!("create shim {}", bin.name), || {
+                Shim::new(&bin, &current_dir).create(ctx)
+            })?;
+        }
+
+        if let Some(psmodule) = &config.psmodule {
+            self.step(
+                format_args!("link PowerShell module {}", psmodule.name),
+                || Ok(psmodule::link(ctx, &psmodule.name, &current_dir)?),
+            )?;
+        }
+
+        let persist_dir = ctx.persist_path().join(name);
+        let variables = ScriptVariables {
+            app: name,
+            version: &version,
+            dir: &current_dir,
+            original_dir: &version_dir,
+            persist_dir: &persist_dir,
+            architecture: arch.to_string(),
+            global: crate::is_global(),
+            cmd: "reset",
+        };
+
+        for shortcut in &config.shortcuts {
+            let shortcut_name = shortcut.get(1).map_or("", String::as_str);
+
+            self.step(format_args!("create shortcut {shortcut_name}"), || {
+                shortcuts::create(shortcut, &variables)
+            })?;
+        }
+
+        let paths = env::resolve_paths(&config.env_add_path.clone().into_vec(), &current_dir);
+        for path in &paths {
+            self.step(format_args!("add {} to PATH", path.display()), || {
+                Ok(env::add_path(
+                    std::slice::from_ref(path),
+                    crate::is_global(),
+                )?)
+            })?;
+        }
+
+        for (var, value) in &config.env_set {
+            let value = variables.substitute(value);
+
+            self.step(format_args!("set {var} to {value}"), || {
+                Ok(env::set_var(var, &value, crate::is_global())?)
+            })?;
+        }
+
+        if !self.dry_run {
+            eprintln_green!("{name} ({version}) was reset!");
+        }
+
+        Ok(())
+    }
+
+    /// Run the action, or print a description of it in a dry run
+    fn step(
+        &self,
+        description: impl Display,
+        action: impl FnOnce() -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        if self.dry_run {
+            eprintln!("  Would {description}");
+            Ok(())
+        } else {
+            action()
+        }
+    }
+}
+
+/// Find the version directory an app's `current` junction should point to
+///
+/// This is the junction's target if it is still intact, otherwise the most recently installed version
+fn version_dir(app_dir: &Path) -> Option<PathBuf> {
+    if let Some(target) = install::read_link(&app_dir.join("current"))
+        .ok()
+        .filter(|target| target.join("manifest.json").exists())
+    {
+        return Some(target);
+    }
+
+    std::fs::read_dir(app_dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.file_name().is_some_and(|name| name != "current"))
+        .filter(|path| path.join("manifest.json").exists())
+        .max_by_key(|path| {
+            path.metadata()
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+}
@@ -0,0 +1,119 @@
+use std::sync::atomic::Ordering;
+
+use clap::Parser;
+use schemars::JsonSchema;
+use serde::Serialize;
+use sprinkles::{Architecture, contexts::ScoopContext, packages::reference::package};
+
+use crate::{
+    COLOR_ENABLED, abandon,
+    install::config::{InstallConfig, Installer},
+    output::{
+        colours::{eprintln_green, eprintln_yellow},
+        format::Format,
+    },
+};
+
+#[derive(Debug, Clone, Parser)]
+/// Show the scripts an app's manifest runs when it is installed or uninstalled
+///
+/// Useful for reviewing what an app will run before installing it
+pub struct Args {
+    #[clap(help = "The app to show the scripts of")]
+    app: package::Reference,
+
+    #[clap(from_global)]
+    arch: Architecture,
+
+    #[clap(from_global)]
+    format: Format,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+/// The scripts in an app's manifest, merged for the selected architecture
+pub(crate) struct Scripts {
+    name: String,
+    version: String,
+    /// Script run before the installer
+    pre_install: Option<String>,
+    /// The installer's script
+    installer: Option<String>,
+    /// Script run after the installer
+    post_install: Option<String>,
+    /// The uninstaller's script
+    uninstaller: Option<String>,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let manifest = match self.app.manifest(ctx).await {
+            Ok(manifest) => manifest,
+            Err(e) => abandon!("Failed to find manifest for {}: {e}", self.app),
+        };
+
+        let config = InstallConfig::from_manifest(&manifest, self.arch)?;
+
+        let script = |installer: Option<&Installer>| {
+            installer.and_then(|installer| installer.script.to_script())
+        };
+
+        let scripts = Scripts {
+            name: unsafe { manifest.name() }.to_string(),
+            version: manifest.version.to_string(),
+            pre_install: config.pre_install.to_script(),
+            installer: script(config.installer.as_ref()),
+            post_install: config.post_install.to_script(),
+            uninstaller: script(config.uninstaller.as_ref()),
+        };
+
+        if self.format.is_structured() {
+            self.format.print(&scripts)?;
+            return Ok(());
+        }
+
+        let sections = [
+            ("pre_install", &scripts.pre_install),
+            ("installer.script", &scripts.installer),
+            ("post_install", &scripts.post_install),
+            ("uninstaller.script", &scripts.uninstaller),
+        ];
+
+        if sections.iter().all(|(_, script)| script.is_none()) {
+            eprintln_green!(
+                "{} ({}) does not run any scripts",
+                scripts.name,
+                scripts.version
+            );
+            return Ok(());
+        }
+
+        for (field, script) in sections {
+            let Some(script) = script else {
+                continue;
+            };
+
+            eprintln_yellow!("{field}:");
+            print_script(script)?;
+            println!();
+        }
+
+        Ok(())
+    }
+}
+
+/// Print a PowerShell script, highlighted if colours are enabled
+fn print_script(script: &str) -> anyhow::Result<()> {
+    if COLOR_ENABLED.load(Ordering::Relaxed) {
+        use bat::PrettyPrinter;
+
+        PrettyPrinter::new()
+            .input_from_bytes(script.as_bytes())
+            .language("powershell")
+            .print()?;
+        println!();
+    } else {
+        println!("{script}");
+    }
+
+    Ok(())
+}
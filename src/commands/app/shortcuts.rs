@@ -0,0 +1,152 @@
+use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use schemars::JsonSchema;
+use serde::Serialize;
+use sprinkles::{Architecture, contexts::ScoopContext, packages::reference::package};
+
+use crate::{
+    abandon,
+    commands::completions,
+    install::{self, config::InstallConfig, scripts::ScriptVariables, shortcuts},
+    output::{
+        colours::{eprintln_green, eprintln_yellow},
+        format::Format,
+        structured::{Priority, Structured},
+    },
+};
+
+#[derive(Debug, Clone, Parser)]
+/// List, create or remove the start menu shortcuts of an app
+///
+/// Useful for restoring shortcuts that were deleted by cleanup tools, without resetting the whole app
+pub struct Args {
+    #[clap(
+        help = "The app to manage the shortcuts of",
+        add = ArgValueCandidates::new(completions::installed_apps)
+    )]
+    app: package::Reference,
+
+    #[clap(
+        short,
+        long,
+        help = "List the app's shortcuts, and whether they exist. This is the default"
+    )]
+    list: bool,
+
+    #[clap(
+        short,
+        long,
+        help = "Create the app's shortcuts, replacing any that exist",
+        conflicts_with_all = ["list", "remove"]
+    )]
+    create: bool,
+
+    #[clap(
+        short,
+        long,
+        help = "Remove the app's shortcuts",
+        conflicts_with_all = ["list", "create"]
+    )]
+    remove: bool,
+
+    #[clap(from_global)]
+    arch: Architecture,
+
+    #[clap(from_global)]
+    format: Format,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+/// A start menu shortcut declared in an app's manifest
+pub(crate) struct Shortcut {
+    /// The name of the shortcut
+    name: String,
+    /// The path the shortcut points to
+    target: String,
+    /// The path of the shortcut itself
+    path: String,
+    /// Whether the shortcut exists
+    exists: bool,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let name = super::reference_name(&self.app);
+
+        let app_dir = ctx.apps_path().join(&name);
+
+        if !app_dir.exists() {
+            abandon!("{name} is not installed");
+        }
+
+        let (manifest, arch) = install::installed_manifest(&app_dir)?;
+        let arch = arch.unwrap_or(self.arch);
+        let config = InstallConfig::from_manifest(&manifest, arch)?;
+
+        if config.shortcuts.is_empty() {
+            eprintln_yellow!("{name} does not have any shortcuts");
+            return Ok(());
+        }
+
+        let version = manifest.version.to_string();
+        let current_dir = app_dir.join("current");
+        let persist_dir = ctx.persist_path().join(&name);
+        let variables = ScriptVariables {
+            app: &name,
+            version: &version,
+            dir: &current_dir,
+            original_dir: &current_dir,
+            persist_dir: &persist_dir,
+            architecture: arch.to_string(),
+            global: crate::is_global(),
+            cmd: "shortcuts",
+        };
+
+        if self.create {
+            for shortcut in &config.shortcuts {
+                shortcuts::create(shortcut, &variables)?;
+            }
+
+            eprintln_green!("Created {} shortcuts for {name}", config.shortcuts.len());
+        } else if self.remove {
+            for shortcut_name in config.shortcut_names() {
+                shortcuts::remove(shortcut_name, crate::is_global())?;
+            }
+
+            eprintln_green!("Removed {} shortcuts for {name}", config.shortcuts.len());
+        } else {
+            let mut listed = vec![];
+
+            for shortcut in &config.shortcuts {
+                let [target, shortcut_name, ..] = shortcut.as_slice() else {
+                    continue;
+                };
+
+                let path = shortcuts::path(shortcut_name, crate::is_global())?;
+
+                listed.push(Shortcut {
+                    name: shortcut_name.clone(),
+                    target: current_dir
+                        .join(variables.substitute(target))
+                        .display()
+                        .to_string(),
+                    exists: path.exists(),
+                    path: path.display().to_string(),
+                });
+            }
+
+            if self.format.is_structured() {
+                self.format.print(&listed)?;
+            } else {
+                let outputs = Structured::new(&listed)
+                    .with_priority("exists", Priority::Required)
+                    .with_priority("target", Priority::High)
+                    .with_priority("path", Priority::Low);
+
+                print!("{outputs}");
+            }
+        }
+
+        Ok(())
+    }
+}
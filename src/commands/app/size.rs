@@ -0,0 +1,183 @@
+use std::{collections::HashMap, path::Path};
+
+use clap::{Parser, ValueEnum};
+use clap_complete::engine::ArgValueCandidates;
+use rayon::prelude::*;
+use schemars::JsonSchema;
+use serde::Serialize;
+use sprinkles::{contexts::ScoopContext, packages::reference::package};
+
+use crate::{
+    commands::completions,
+    output::{
+        colours::eprintln_bright_yellow,
+        format::Format,
+        structured::{Priority, Structured},
+    },
+    wrappers::sizes::Size,
+};
+
+#[derive(Debug, Clone, Parser)]
+/// Show how much disk space each installed app uses
+///
+/// Counts every installed version of the app, its cache entries and its persist directory
+pub struct Args {
+    #[clap(
+        help = "The apps to show. Defaults to all installed apps",
+        add = ArgValueCandidates::new(completions::installed_apps)
+    )]
+    apps: Vec<package::Reference>,
+
+    #[clap(
+        long,
+        help = "Sort by the given field. Sizes are sorted largest first",
+        default_value = "total"
+    )]
+    sort_by: SortBy,
+
+    #[clap(long, help = "Reverse the sort order")]
+    reverse: bool,
+
+    #[clap(from_global)]
+    format: Format,
+}
+
+#[derive(Debug, Copy, Clone, ValueEnum)]
+pub enum SortBy {
+    Name,
+    Versions,
+    Cache,
+    Persist,
+    Total,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+/// The disk space used by an installed app
+pub(crate) struct AppSize {
+    name: String,
+    /// The combined size of every installed version
+    #[schemars(with = "String")]
+    versions: Size,
+    /// The size of the app's cache entries
+    #[schemars(with = "String")]
+    cache: Size,
+    /// The size of the app's persist directory
+    #[schemars(with = "String")]
+    persist: Size,
+    #[schemars(with = "String")]
+    total: Size,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let names = super::names_or_installed(ctx, &self.apps)?;
+
+        let cache_sizes = cache_sizes(&ctx.cache_path());
+
+        let mut sizes = names
+            .into_par_iter()
+            .filter_map(|name| {
+                let app_dir = ctx.apps_path().join(&name);
+
+                if !app_dir.exists() {
+                    eprintln_bright_yellow!("{name} is not installed");
+                    return None;
+                }
+
+                // The `current` junction is skipped, so each version is only counted once
+                let versions = dir_size(&app_dir);
+                let persist = dir_size(&ctx.persist_path().join(&name));
+                let cache = cache_sizes.get(&name).copied().unwrap_or(Size::new(0));
+
+                Some(AppSize {
+                    total: versions + cache + persist,
+                    name,
+                    versions,
+                    cache,
+                    persist,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        sizes.par_sort_by(|a, b| match self.sort_by {
+            SortBy::Name => a.name.cmp(&b.name),
+            SortBy::Versions => b.versions.cmp(&a.versions),
+            SortBy::Cache => b.cache.cmp(&a.cache),
+            SortBy::Persist => b.persist.cmp(&a.persist),
+            SortBy::Total => b.total.cmp(&a.total),
+        });
+
+        if self.reverse {
+            sizes.reverse();
+        }
+
+        if self.format.is_structured() {
+            self.format.print(&sizes)?;
+            return Ok(());
+        }
+
+        let total_size = sizes
+            .iter()
+            .fold(Size::new(0), |acc, size| acc + size.total);
+
+        let outputs = Structured::new(&sizes)
+            .with_priority("total", Priority::Required)
+            .with_priority("versions", Priority::High)
+            .with_priority("persist", Priority::High)
+            .with_priority("cache", Priority::Low);
+
+        print!("{outputs}");
+
+        eprintln_bright_yellow!("Total: {} apps, {total_size}", sizes.len());
+
+        Ok(())
+    }
+}
+
+/// Sum the sizes of the cache entries of each app, keyed by app name
+fn cache_sizes(cache_path: &Path) -> HashMap<String, Size> {
+    let mut sizes = HashMap::new();
+
+    let Ok(entries) = std::fs::read_dir(cache_path) else {
+        return sizes;
+    };
+
+    for entry in entries.flatten() {
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+        let Some((name, _)) = file_name.split_once('#') else {
+            continue;
+        };
+
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+
+        let size = sizes.entry(name.to_string()).or_insert(Size::new(0));
+        *size = *size + Size::new(metadata.len());
+    }
+
+    sizes
+}
+
+/// Recursively sum the sizes of the files in a directory
+///
+/// Symlinks and junctions are not followed, so persisted directories linked into a version are not counted twice
+fn dir_size(dir: &Path) -> Size {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Size::new(0);
+    };
+
+    entries
+        .par_bridge()
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_symlink() => Size::new(0),
+            Ok(file_type) if file_type.is_dir() => dir_size(&entry.path()),
+            Ok(_) => entry
+                .metadata()
+                .map_or(Size::new(0), |metadata| Size::new(metadata.len())),
+            Err(_) => Size::new(0),
+        })
+        .reduce(|| Size::new(0), |acc, size| acc + size)
+}
@@ -0,0 +1,221 @@
+use std::path::Path;
+
+use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use itertools::Itertools;
+use serde_json::{Map, Value};
+use sprinkles::{
+    Architecture,
+    contexts::ScoopContext,
+    packages::{Manifest, reference::package},
+};
+
+use crate::{
+    abandon,
+    commands::completions,
+    install::{
+        self, config::InstallConfig, env, persist, psmodule, scripts::ScriptVariables, shims,
+        shims::Shim, shortcuts,
+    },
+    output::colours::{eprintln_green, eprintln_yellow},
+};
+
+#[derive(Debug, Clone, Parser)]
+/// Switch an app to another installed version, without downloading anything
+///
+/// Only versions that are still installed can be switched to, so this will not work after `app cleanup`
+pub struct Args {
+    #[clap(
+        help = "The app to switch",
+        add = ArgValueCandidates::new(completions::installed_apps)
+    )]
+    app: package::Reference,
+
+    #[clap(help = "The installed version to switch to")]
+    version: String,
+
+    #[clap(from_global)]
+    arch: Architecture,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        // Global apps link into system directories
+        if crate::is_global() && !quork::root::is_root()? {
+            abandon!("Switching global apps requires elevation. Please run as an administrator.");
+        }
+
+        let name = super::reference_name(&self.app);
+
+        let Ok(app_handle) = self.app.clone().open_handle(ctx).await else {
+            abandon!("{name} is not installed");
+        };
+
+        let current_version = app_handle.local_version()?;
+
+        if current_version.as_str() == self.version {
+            eprintln!("{name} is already using {current_version}");
+            return Ok(());
+        }
+
+        let versions = app_handle.list_versions()?;
+
+        let Some(target) = versions
+            .iter()
+            .find(|version| version.version() == self.version)
+        else {
+            abandon!(
+                "{name} {} is not installed. Installed versions: {}",
+                self.version,
+                versions.iter().map(|version| version.version()).join(", ")
+            );
+        };
+
+        let app_dir = ctx.apps_path().join(&name);
+        let old_version_dir = install::read_link(&app_dir.join("current"))
+            .unwrap_or_else(|_| app_dir.join(current_version.as_str()));
+        let persist_dir = ctx.persist_path().join(&name);
+
+        let (old_manifest, old_arch) = install::installed_manifest(&app_dir)?;
+        let old_arch = old_arch.unwrap_or(self.arch);
+        let old = Version {
+            version: current_version.as_str(),
+            dir: &old_version_dir,
+            arch: old_arch,
+            config: InstallConfig::from_manifest(&old_manifest, old_arch)?,
+        };
+
+        let new_manifest = Manifest::from_path(target.path().join("manifest.json"))?;
+        let new_arch = install::version_architecture(target.path()).unwrap_or(self.arch);
+        let new = Version {
+            version: &self.version,
+            dir: &target.path(),
+            arch: new_arch,
+            config: InstallConfig::from_manifest(&new_manifest, new_arch)?,
+        };
+
+        // The persisted data is linked into the new version once it is current
+        persist::unlink(&old.config.persist(), &old_version_dir)?;
+
+        // The target version's install info is from when it was current, so it may not be held or pinned
+        let user_info = install::user_install_info(&app_dir);
+
+        let switch = |from: &Version<'_>, to: &Version<'_>| {
+            link_version(ctx, &name, &app_dir, &persist_dir, &user_info, from, to)
+        };
+
+        if let Err(e) = switch(&old, &new) {
+            eprintln_yellow!("Restoring {name} ({current_version})");
+
+            if let Err(restore_error) = switch(&new, &old) {
+                error!("Failed to restore {name}: {restore_error}");
+            }
+
+            return Err(e);
+        }
+
+        eprintln_green!(
+            "{name} was switched from {current_version} to {}!",
+            self.version
+        );
+        eprintln!("Use `sfsu app hold {name}` to stop it from being updated");
+
+        Ok(())
+    }
+}
+
+/// An installed version of an app
+struct Version<'a> {
+    version: &'a str,
+    dir: &'a Path,
+    arch: Architecture,
+    config: InstallConfig,
+}
+
+/// Point an app at another installed version, and bring its shims, shortcuts and environment in line with it
+///
+/// Anything only the version being switched from provides is removed, so nothing is left pointing at the wrong version.
+/// This is also used to switch back to the previous version if switching fails
+fn link_version(
+    ctx: &impl ScoopContext,
+    name: &str,
+    app_dir: &Path,
+    persist_dir: &Path,
+    user_info: &Map<String, Value>,
+    from: &Version<'_>,
+    to: &Version<'_>,
+) -> anyhow::Result<()> {
+    let current_dir = app_dir.join("current");
+
+    let to_bins = to.config.bins();
+    for bin in from.config.bins() {
+        if !to_bins.iter().any(|to_bin| to_bin.name == bin.name) {
+            shims::remove(ctx, &bin.name, app_dir)?;
+        }
+    }
+
+    if let Some(psmodule) = from.config.psmodule.as_ref().filter(|psmodule| {
+        to.config
+            .psmodule
+            .as_ref()
+            .is_none_or(|to| to.name != psmodule.name)
+    }) {
+        psmodule::remove(ctx, &psmodule.name)?;
+    }
+
+    let to_shortcuts = to.config.shortcut_names();
+    for shortcut in from.config.shortcut_names() {
+        if !to_shortcuts.contains(&shortcut) {
+            shortcuts::remove(shortcut, crate::is_global())?;
+        }
+    }
+
+    let to_paths = env::resolve_paths(&to.config.env_add_path.clone().into_vec(), &current_dir);
+    let from_paths = env::resolve_paths(&from.config.env_add_path.clone().into_vec(), &current_dir)
+        .into_iter()
+        .filter(|path| !to_paths.contains(path))
+        .collect_vec();
+    env::remove_path(&from_paths, crate::is_global())?;
+
+    for var in from.config.env_set.keys() {
+        if !to.config.env_set.contains_key(var) {
+            env::remove_var(var, crate::is_global())?;
+        }
+    }
+
+    let current_dir = install::link_current(app_dir, to.dir)?;
+    install::set_user_install_info(app_dir, user_info)?;
+
+    for bin in &to_bins {
+        Shim::new(bin, &current_dir).create(ctx)?;
+    }
+
+    if let Some(psmodule) = &to.config.psmodule {
+        psmodule::link(ctx, &psmodule.name, &current_dir)?;
+    }
+
+    let variables = ScriptVariables {
+        app: name,
+        version: to.version,
+        dir: &current_dir,
+        original_dir: to.dir,
+        persist_dir,
+        architecture: to.arch.to_string(),
+        global: crate::is_global(),
+        cmd: "switch",
+    };
+
+    for shortcut in &to.config.shortcuts {
+        shortcuts::create(shortcut, &variables)?;
+    }
+
+    env::add_path(&to_paths, crate::is_global())?;
+
+    for (var, value) in &to.config.env_set {
+        env::set_var(var, &variables.substitute(value), crate::is_global())?;
+    }
+
+    persist::link(&to.config.persist(), to.dir, persist_dir)?;
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use sprinkles::{contexts::ScoopContext, packages::reference::package};
+
+use crate::commands::completions;
+
+#[derive(Debug, Clone, Parser)]
+/// Unhold the specified app(s), so that they are updated again
+pub struct Args {
+    #[clap(
+        help = "The app(s) to unhold",
+        required_unless_present = "all",
+        add = ArgValueCandidates::new(completions::installed_apps)
+    )]
+    apps: Vec<package::Reference>,
+
+    #[clap(
+        short,
+        long,
+        help = "Unhold all installed apps",
+        conflicts_with = "apps"
+    )]
+    all: bool,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        super::hold::set_held(ctx, &self.apps, self.all, false)
+    }
+}
@@ -0,0 +1,447 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use dialoguer::Confirm;
+use sprinkles::{
+    Architecture,
+    contexts::ScoopContext,
+    packages::reference::{manifest, package},
+};
+
+use crate::{
+    abandon,
+    commands::{completions, depends},
+    handlers::{AppsDecider, ListApps},
+    install::{
+        self,
+        config::InstallConfig,
+        env, installer, psmodule,
+        scripts::{self, ScriptVariables},
+        shims, shortcuts,
+    },
+    output::colours::{eprintln_green, eprintln_yellow, yellow},
+};
+
+use super::purge;
+
+#[derive(Debug, Clone, Parser)]
+/// Uninstall the specified app(s)
+pub struct Args {
+    #[clap(
+        help = "The app(s) to uninstall",
+        add = ArgValueCandidates::new(completions::installed_apps)
+    )]
+    apps: Vec<package::Reference>,
+
+    #[clap(
+        short,
+        long,
+        help = "Uninstall all installed apps, except Scoop itself"
+    )]
+    all: bool,
+
+    #[clap(short, long, help = "Remove the app's persist data as well")]
+    purge: bool,
+
+    #[clap(short = 'k', long, help = "Remove the app's cached downloads as well")]
+    cache: bool,
+
+    #[clap(short, long, help = "Uninstall held apps as well")]
+    force: bool,
+
+    #[clap(from_global)]
+    arch: Architecture,
+
+    #[clap(from_global)]
+    assume_yes: bool,
+
+    #[clap(
+        long,
+        help = "Print what would be done, but don't actually do anything"
+    )]
+    dry_run: bool,
+}
+
+/// An installed app to be uninstalled
+struct App {
+    name: String,
+    app_dir: PathBuf,
+    persist_dir: PathBuf,
+}
+
+impl super::Command for Args {
+    const BETA: bool = true;
+
+    async fn runner(mut self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        // Global apps live in a system directory and modify the system environment
+        if crate::is_global() && !self.dry_run && !quork::root::is_root()? {
+            abandon!(
+                "Uninstalling global apps requires elevation. Please run as an administrator."
+            );
+        }
+
+        let provided_apps = std::mem::take(&mut self.apps);
+
+        let references = match AppsDecider::new(ctx, self.list_apps(), provided_apps).decide()? {
+            Some(apps) if apps.is_empty() => abandon!("No apps selected"),
+            None => abandon!("No apps selected"),
+            Some(apps) => apps,
+        };
+
+        let mut apps = vec![];
+
+        for reference in &references {
+            let name = super::reference_name(reference);
+
+            let app_dir = ctx.apps_path().join(&name);
+
+            if !app_dir.exists() {
+                eprintln_yellow!("{name} is not installed");
+                continue;
+            }
+
+            if !self.force && install::is_held(&app_dir) {
+                eprintln_yellow!(
+                    "{name} is held, so it will not be uninstalled. Use --force to uninstall it anyway"
+                );
+                continue;
+            }
+
+            let persist_dir = ctx.persist_path().join(&name);
+
+            apps.push(App {
+                name,
+                app_dir,
+                persist_dir,
+            });
+        }
+
+        if apps.is_empty() {
+            eprintln_yellow!("No apps to uninstall");
+            return Ok(());
+        }
+
+        eprintln!(
+            "{} the following {}:",
+            if self.dry_run {
+                "Would uninstall"
+            } else {
+                "Uninstalling"
+            },
+            if apps.len() == 1 { "app" } else { "apps" }
+        );
+        for app in &apps {
+            eprintln!("- {} ({})", app.name, app.app_dir.display());
+        }
+        eprintln!();
+
+        Self::warn_dependents(ctx, &apps)?;
+
+        if !self.assume_yes
+            && !self.dry_run
+            && !Confirm::new()
+                .with_prompt(
+                    yellow!(
+                        "Are you sure you want to uninstall {}{}?",
+                        if apps.len() == 1 {
+                            "this app".to_string()
+                        } else {
+                            format!("{} apps", apps.len())
+                        },
+                        if self.purge {
+                            " and purge its persist data"
+                        } else {
+                            ""
+                        }
+                    )
+                    .to_string(),
+                )
+                .default(false)
+                .interact()?
+        {
+            return Ok(());
+        }
+
+        for app in &apps {
+            self.uninstall(ctx, app)?;
+        }
+
+        if self.dry_run {
+            eprintln_green!("Dry run complete, nothing was removed");
+        }
+
+        Ok(())
+    }
+}
+
+impl Args {
+    fn list_apps<C: ScoopContext>(&self) -> ListApps<C> {
+        let all = self.all;
+        std::rc::Rc::new(move |ctx: &C| {
+            if all {
+                let installed_apps = ctx
+                    .installed_apps()?
+                    .into_iter()
+                    .filter_map(|path| {
+                        path.file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                    })
+                    // Uninstalling Scoop itself would break every other app
+                    .filter(|name| name != "scoop")
+                    .map(|name| manifest::Reference::Name(name).into_package_ref())
+                    .collect();
+
+                anyhow::Ok(Some(installed_apps))
+            } else {
+                anyhow::Ok(None)
+            }
+        })
+    }
+
+    /// Warn about installed apps that depend on the apps being uninstalled
+    ///
+    /// Apps that are being uninstalled together are left out of the warnings
+    fn warn_dependents(ctx: &impl ScoopContext, apps: &[App]) -> anyhow::Result<()> {
+        let dependents = depends::dependents_map(depends::installed_manifests(ctx)?);
+
+        for app in apps {
+            let remaining = dependents
+                .get(&app.name)
+                .into_iter()
+                .flatten()
+                .map(|(dependent, _)| dependent.as_str())
+                .filter(|dependent| {
+                    !apps
+                        .iter()
+                        .any(|app| app.name.eq_ignore_ascii_case(dependent))
+                })
+                .collect::<Vec<_>>();
+
+            if !remaining.is_empty() {
+                eprintln_yellow!(
+                    "{} is required by {}, which may stop working once it is uninstalled",
+                    app.name,
+                    remaining.join(", ")
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn uninstall(&self, ctx: &impl ScoopContext, app: &App) -> anyhow::Result<()> {
+        if self.dry_run {
+            eprintln!("\n{}:", app.name);
+        } else {
+            eprintln!("Uninstalling {}", app.name);
+        }
+
+        let (manifest, config) = match install::installed_manifest(&app.app_dir) {
+            Ok((manifest, arch)) => {
+                let arch = arch.unwrap_or(self.arch);
+                let config = InstallConfig::from_manifest(&manifest, arch)?;
+
+                (Some((manifest, arch)), config)
+            }
+            Err(e) => {
+                eprintln_yellow!(
+                    "Could not read the manifest for {}, so only its files will be removed: {e}",
+                    app.name
+                );
+
+                (None, InstallConfig::default())
+            }
+        };
+
+        let version = manifest
+            .as_ref()
+            .map(|(manifest, _)| manifest.version.to_string())
+            .unwrap_or_default();
+        // Nightly builds are installed into dated directories, rather than one named after the version
+        let (version_dir, version) = match install::read_link(&app.app_dir.join("current")) {
+            Ok(version_dir) => {
+                let version = version_dir
+                    .file_name()
+                    .map_or(version, |name| name.to_string_lossy().to_string());
+
+                (version_dir, version)
+            }
+            Err(_) => (app.app_dir.join(&version), version),
+        };
+        let variables = ScriptVariables {
+            app: &app.name,
+            version: &version,
+            dir: &version_dir,
+            original_dir: &version_dir,
+            persist_dir: &app.persist_dir,
+            architecture: manifest
+                .as_ref()
+                .map(|(_, arch)| arch.to_string())
+                .unwrap_or_default(),
+            global: crate::is_global(),
+            cmd: "uninstall",
+        };
+
+        self.run_script(
+            ctx,
+            "pre_uninstall",
+            config.pre_uninstall.to_script(),
+            &variables,
+        )?;
+        self.run_script(
+            ctx,
+            "uninstaller",
+            config
+                .uninstaller
+                .as_ref()
+                .and_then(|uninstaller| uninstaller.script.to_script()),
+            &variables,
+        )?;
+
+        if let Some(uninstaller) = config
+            .uninstaller
+            .as_ref()
+            .filter(|uninstaller| uninstaller.has_program())
+        {
+            if self.dry_run {
+                eprintln!("Would run uninstaller");
+            } else {
+                installer::run("uninstaller", uninstaller, None, &variables)?;
+            }
+        }
+
+        for bin in config.bins() {
+            if self.dry_run {
+                if shims::belongs_to(ctx, &bin.name, &app.app_dir) {
+                    eprintln!("Would remove shim {}", bin.name);
+                } else {
+                    eprintln!(
+                        "Would skip shim {}, as it does not belong to {}",
+                        bin.name, app.name
+                    );
+                }
+            } else if !shims::remove(ctx, &bin.name, &app.app_dir)? {
+                eprintln_yellow!(
+                    "Skipping shim {}, as it does not belong to {}",
+                    bin.name,
+                    app.name
+                );
+            }
+        }
+
+        for shortcut in config.shortcut_names() {
+            if self.dry_run {
+                eprintln!("Would remove shortcut {shortcut}");
+            } else {
+                shortcuts::remove(shortcut, crate::is_global())?;
+            }
+        }
+
+        let paths = env::resolve_paths(
+            &config.env_add_path.clone().into_vec(),
+            &app.app_dir.join("current"),
+        );
+        if self.dry_run {
+            for path in &paths {
+                eprintln!("Would remove {} from PATH", path.display());
+            }
+        } else {
+            env::remove_path(&paths, crate::is_global())?;
+        }
+
+        for name in config.env_set.keys() {
+            if self.dry_run {
+                eprintln!("Would remove environment variable {name}");
+            } else {
+                env::remove_var(name, crate::is_global())?;
+            }
+        }
+
+        if let Some(psmodule) = &config.psmodule {
+            if self.dry_run {
+                eprintln!("Would remove PowerShell module {}", psmodule.name);
+            } else {
+                psmodule::remove(ctx, &psmodule.name)?;
+            }
+        }
+
+        if self.dry_run {
+            eprintln!("Would remove {}", app.app_dir.display());
+        } else {
+            std::fs::remove_dir_all(&app.app_dir)?;
+        }
+
+        if self.purge && app.persist_dir.exists() {
+            if self.dry_run {
+                eprintln!("Would remove {}", app.persist_dir.display());
+            }
+
+            purge::purge(&app.persist_dir, self.dry_run)?;
+        } else if self.dry_run && app.persist_dir.exists() {
+            eprintln!("Would keep {}", app.persist_dir.display());
+        }
+
+        if self.cache {
+            self.remove_cache(ctx, app)?;
+        }
+
+        self.run_script(
+            ctx,
+            "post_uninstall",
+            config.post_uninstall.to_script(),
+            &variables,
+        )?;
+
+        if !self.dry_run {
+            eprintln_green!("{} was uninstalled successfully!", app.name);
+        }
+
+        Ok(())
+    }
+
+    /// Remove every cached download of the app, across all of its versions
+    fn remove_cache(&self, ctx: &impl ScoopContext, app: &App) -> std::io::Result<()> {
+        let cache_path = ctx.cache_path();
+
+        if !cache_path.exists() {
+            return Ok(());
+        }
+
+        // Cache files are named `app#version#url`
+        let prefix = format!("{}#", app.name);
+
+        for entry in std::fs::read_dir(cache_path)?.filter_map(Result::ok) {
+            if !entry.file_name().to_string_lossy().starts_with(&prefix) {
+                continue;
+            }
+
+            if self.dry_run {
+                eprintln!("Would remove {}", entry.path().display());
+            } else {
+                std::fs::remove_file(entry.path())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn run_script(
+        &self,
+        ctx: &impl ScoopContext,
+        name: &str,
+        script: Option<String>,
+        variables: &ScriptVariables<'_>,
+    ) -> anyhow::Result<()> {
+        let Some(script) = script else {
+            return Ok(());
+        };
+
+        if self.dry_run {
+            eprintln!("Would run {name} script");
+            Ok(())
+        } else {
+            scripts::run(ctx, name, &script, variables)
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use itertools::Itertools;
+use sprinkles::{contexts::ScoopContext, packages::reference::package};
+
+use crate::{
+    abandon,
+    commands::completions,
+    install,
+    output::colours::{eprintln_green, eprintln_yellow},
+};
+
+#[derive(Debug, Clone, Parser)]
+/// Unpin the specified app(s), so that they are updated to the latest version again
+pub struct Args {
+    #[clap(
+        help = "The app(s) to unpin",
+        required_unless_present = "all",
+        add = ArgValueCandidates::new(completions::installed_apps)
+    )]
+    apps: Vec<package::Reference>,
+
+    #[clap(
+        short,
+        long,
+        help = "Unpin all installed apps",
+        conflicts_with = "apps"
+    )]
+    all: bool,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let names = if self.all {
+            super::installed_names(ctx)?
+                .into_iter()
+                // Only report the apps that were actually pinned
+                .filter(|name| install::pinned_version(&ctx.apps_path().join(name)).is_some())
+                .collect_vec()
+        } else {
+            super::reference_names(&self.apps)
+        };
+
+        let mut failed = vec![];
+
+        for name in names.into_iter().unique() {
+            let app_dir = ctx.apps_path().join(&name);
+
+            if !app_dir.exists() {
+                eprintln_yellow!("{name} is not installed");
+                continue;
+            }
+
+            match install::set_pinned(&app_dir, None) {
+                Ok(true) => eprintln_green!("{name} is no longer pinned"),
+                Ok(false) => eprintln!("{name} is not pinned"),
+                Err(e) => {
+                    eprintln_yellow!("Failed to unpin {name}: {e}");
+                    failed.push(name);
+                }
+            }
+        }
+
+        if !failed.is_empty() {
+            abandon!("Failed to unpin {}", failed.join(", "));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,397 @@
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use itertools::Itertools;
+use sprinkles::{
+    Architecture,
+    buckets::Bucket,
+    contexts::ScoopContext,
+    packages::{InstallManifest, Manifest, reference::package},
+};
+
+use crate::{
+    abandon,
+    commands::completions,
+    install::{
+        self,
+        config::InstallConfig,
+        env, installer, persist, psmodule,
+        scripts::{self, ScriptVariables},
+        shims::{self, Shim},
+        shortcuts,
+    },
+    output::colours::{eprintln_green, eprintln_red, eprintln_yellow},
+};
+
+use super::install as app_install;
+
+#[derive(Debug, Clone, Parser)]
+/// Update the specified app(s), or all outdated apps with `--all`
+pub struct Args {
+    #[clap(
+        help = "The app(s) to update",
+        required_unless_present = "all",
+        add = ArgValueCandidates::new(completions::installed_apps)
+    )]
+    apps: Vec<package::Reference>,
+
+    #[clap(
+        short,
+        long,
+        help = "Update all outdated apps",
+        conflicts_with = "apps"
+    )]
+    all: bool,
+
+    #[clap(short = 'H', long, help = "Disable hash validation")]
+    no_hash_check: bool,
+
+    #[clap(long, help = "Don't run any of the scripts in the manifests")]
+    no_scripts: bool,
+
+    #[clap(short, long, help = "Update held and pinned apps as well")]
+    force: bool,
+
+    #[clap(from_global)]
+    arch: Architecture,
+}
+
+/// An installed app with a newer version available
+struct Outdated {
+    name: String,
+    app_dir: PathBuf,
+    arch: Architecture,
+    local: Manifest,
+    remote: Manifest,
+}
+
+impl super::Command for Args {
+    const BETA: bool = true;
+    const NEEDS_NETWORK: bool = true;
+
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        // Global apps live in a system directory and modify the system environment
+        if crate::is_global() && !quork::root::is_root()? {
+            abandon!("Updating global apps requires elevation. Please run as an administrator.");
+        }
+
+        if self.no_hash_check {
+            eprintln_yellow!(
+                "Hash check has been disabled! This may allow modified files to be installed"
+            );
+        }
+
+        let explicit = !self.apps.is_empty();
+
+        let names = if explicit {
+            super::reference_names(&self.apps)
+        } else {
+            super::installed_names(ctx)?
+                .into_iter()
+                // Scoop itself is updated alongside the buckets
+                .filter(|name| name != "scoop")
+                .collect()
+        };
+
+        let mut outdated = vec![];
+
+        for name in names.into_iter().unique() {
+            if let Some(app) = self.outdated(ctx, name, explicit)? {
+                outdated.push(app);
+            }
+        }
+
+        if outdated.is_empty() {
+            eprintln_green!("All apps are up to date");
+            return Ok(());
+        }
+
+        eprintln!(
+            "Updating {} {}:",
+            outdated.len(),
+            if outdated.len() == 1 { "app" } else { "apps" }
+        );
+        for app in &outdated {
+            eprintln!(
+                "- {} ({} -> {})",
+                app.name, app.local.version, app.remote.version
+            );
+        }
+        eprintln!();
+
+        // Downloading every app at once is much faster than downloading them one by one
+        let downloads = outdated
+            .iter()
+            .map(|app| (&app.remote, app.arch))
+            .collect_vec();
+        app_install::download(ctx, &downloads, self.no_hash_check).await?;
+
+        let mut failed = vec![];
+
+        for app in &outdated {
+            if let Err(e) = self.update(ctx, app) {
+                eprintln_red!("Failed to update {}: {e}", app.name);
+                failed.push(app.name.as_str());
+            }
+        }
+
+        if !failed.is_empty() {
+            abandon!("Failed to update {}", failed.join(", "));
+        }
+
+        Ok(())
+    }
+}
+
+impl Args {
+    /// Create the arguments to update all outdated apps
+    pub fn all(arch: Architecture) -> Self {
+        Self {
+            apps: vec![],
+            all: true,
+            no_hash_check: false,
+            no_scripts: false,
+            force: false,
+            arch,
+        }
+    }
+
+    /// Find the latest version of an installed app
+    ///
+    /// Returns [`None`] if the app is up to date, or cannot be updated.
+    /// Apps that were explicitly requested print why they were skipped
+    fn outdated(
+        &self,
+        ctx: &impl ScoopContext,
+        name: String,
+        explicit: bool,
+    ) -> anyhow::Result<Option<Outdated>> {
+        let app_dir = ctx.apps_path().join(&name);
+
+        if !app_dir.exists() {
+            eprintln_yellow!("{name} is not installed");
+            return Ok(None);
+        }
+
+        if !self.force && install::is_held(&app_dir) {
+            if explicit {
+                eprintln_yellow!(
+                    "{name} is held, so it will not be updated. Use --force to update it anyway"
+                );
+            }
+            return Ok(None);
+        }
+
+        let (local, arch) = match install::installed_manifest(&app_dir) {
+            Ok(installed) => installed,
+            Err(e) => {
+                eprintln_yellow!("Could not read the manifest for {name}, skipping: {e}");
+                return Ok(None);
+            }
+        };
+
+        let Some(bucket) = InstallManifest::from_path(app_dir.join("current").join("install.json"))
+            .ok()
+            .and_then(|install_manifest| install_manifest.bucket)
+        else {
+            if explicit {
+                eprintln_yellow!("{name} was not installed from a bucket, so it cannot be updated");
+            }
+            return Ok(None);
+        };
+
+        let remote =
+            match Bucket::from_name(ctx, &bucket).and_then(|bucket| bucket.get_manifest(&name)) {
+                Ok(remote) => remote,
+                Err(e) => {
+                    eprintln_yellow!("Could not find the manifest for {name} in {bucket}: {e}");
+                    return Ok(None);
+                }
+            };
+
+        let pinned = install::pinned_version(&app_dir).filter(|_| !self.force);
+        if let Some(pinned) = pinned.filter(|pinned| *pinned != remote.version.to_string()) {
+            if explicit {
+                eprintln_yellow!(
+                    "{name} is pinned to {pinned}, so it will not be updated to {}. Use --force to update it anyway",
+                    remote.version
+                );
+            }
+            return Ok(None);
+        }
+
+        if local.version == remote.version {
+            if explicit {
+                eprintln!("{name} ({}) is already up to date", local.version);
+            }
+            return Ok(None);
+        }
+
+        Ok(Some(Outdated {
+            name,
+            app_dir,
+            arch: arch.unwrap_or(self.arch),
+            local,
+            remote,
+        }))
+    }
+
+    /// Replace the installed version of an app with the downloaded new version
+    ///
+    /// The old version directory is kept, so it can be removed with `app cleanup`
+    fn update(&self, ctx: &impl ScoopContext, app: &Outdated) -> anyhow::Result<()> {
+        eprintln!(
+            "Updating {} ({} -> {})",
+            app.name, app.local.version, app.remote.version
+        );
+
+        let config = InstallConfig::from_manifest(&app.local, app.arch)?;
+
+        let current_dir = app.app_dir.join("current");
+        let version = app.local.version.to_string();
+        let old_version_dir =
+            install::read_link(&current_dir).unwrap_or_else(|_| app.app_dir.join(&version));
+        let persist_dir = ctx.persist_path().join(&app.name);
+
+        let variables = ScriptVariables {
+            app: &app.name,
+            version: &version,
+            dir: &current_dir,
+            original_dir: &old_version_dir,
+            persist_dir: &persist_dir,
+            architecture: app.arch.to_string(),
+            global: crate::is_global(),
+            cmd: "update",
+        };
+
+        if !self.no_scripts {
+            if let Some(script) = config.pre_uninstall.to_script() {
+                scripts::run(ctx, "pre_uninstall", &script, &variables)?;
+            }
+
+            if let Some(script) = config
+                .uninstaller
+                .as_ref()
+                .and_then(|uninstaller| uninstaller.script.to_script())
+            {
+                scripts::run(ctx, "uninstaller", &script, &variables)?;
+            }
+        }
+
+        for bin in config.bins() {
+            shims::remove(ctx, &bin.name, &app.app_dir)?;
+        }
+
+        let paths = env::resolve_paths(&config.env_add_path.clone().into_vec(), &current_dir);
+        env::remove_path(&paths, crate::is_global())?;
+
+        // The persisted data is linked into the new version once it is installed
+        persist::unlink(&config.persist(), &old_version_dir)?;
+
+        match self
+            .installer(app.arch)
+            .install_downloaded(ctx, &app.remote, None)
+        {
+            Ok(new_config) => {
+                eprintln_green!(
+                    "{} was updated from {} to {}!",
+                    app.name,
+                    app.local.version,
+                    app.remote.version
+                );
+
+                // The old version's uninstaller program only runs once the new version is in place,
+                // so a failed update leaves the old version working
+                if let Some(uninstaller) = config
+                    .uninstaller
+                    .as_ref()
+                    .filter(|uninstaller| !self.no_scripts && uninstaller.has_program())
+                {
+                    let variables = ScriptVariables {
+                        dir: &old_version_dir,
+                        ..variables
+                    };
+
+                    if let Err(e) = installer::run("uninstaller", uninstaller, None, &variables) {
+                        eprintln_yellow!(
+                            "The uninstaller for {} ({}) failed: {e}",
+                            app.name,
+                            app.local.version
+                        );
+                    }
+                }
+
+                app_install::show_notes(&new_config);
+
+                Ok(())
+            }
+            Err(e) => {
+                eprintln_yellow!("Restoring {} ({})", app.name, app.local.version);
+
+                if let Err(restore_error) =
+                    self.restore(ctx, app, &config, &old_version_dir, &persist_dir)
+                {
+                    error!("Failed to restore {}: {restore_error}", app.name);
+                }
+
+                Err(e)
+            }
+        }
+    }
+
+    /// Link the previous version of an app back into place after a failed update
+    fn restore(
+        &self,
+        ctx: &impl ScoopContext,
+        app: &Outdated,
+        config: &InstallConfig,
+        old_version_dir: &Path,
+        persist_dir: &Path,
+    ) -> anyhow::Result<()> {
+        let current_dir = install::link_current(&app.app_dir, old_version_dir)?;
+
+        for bin in config.bins() {
+            Shim::new(&bin, &current_dir).create(ctx)?;
+        }
+
+        if let Some(psmodule) = &config.psmodule {
+            psmodule::link(ctx, &psmodule.name, &current_dir)?;
+        }
+
+        // Rolling back the new version removed the shortcuts, which both versions share
+        let version = app.local.version.to_string();
+        let variables = ScriptVariables {
+            app: &app.name,
+            version: &version,
+            dir: &current_dir,
+            original_dir: old_version_dir,
+            persist_dir,
+            architecture: app.arch.to_string(),
+            global: crate::is_global(),
+            cmd: "update",
+        };
+
+        for shortcut in &config.shortcuts {
+            shortcuts::create(shortcut, &variables)?;
+        }
+
+        let paths = env::resolve_paths(&config.env_add_path.clone().into_vec(), &current_dir);
+        env::add_path(&paths, crate::is_global())?;
+
+        // Rolling back the new version removed the variables, which both versions set
+        for (name, value) in &config.env_set {
+            env::set_var(name, &variables.substitute(value), crate::is_global())?;
+        }
+
+        persist::link(&config.persist(), old_version_dir, persist_dir)?;
+
+        Ok(())
+    }
+
+    fn installer(&self, arch: Architecture) -> app_install::Args {
+        app_install::Args::new(vec![], arch)
+            .no_hash_check(self.no_hash_check)
+            .no_scripts(self.no_scripts)
+    }
+}
@@ -0,0 +1,266 @@
+use std::path::Path;
+
+use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use rayon::prelude::*;
+use schemars::JsonSchema;
+use serde::Serialize;
+use sprinkles::{Architecture, contexts::ScoopContext, packages::reference::package};
+
+use crate::{
+    commands::{
+        completions,
+        verify::{Status, hash_file},
+    },
+    install::{self, config::InstallConfig, extract, scripts::ScriptVariables, shortcuts},
+    output::{
+        colours::{eprintln_green, eprintln_yellow},
+        format::Format,
+        structured::{Priority, Structured},
+    },
+};
+
+#[derive(Debug, Clone, Parser)]
+/// Check that installed apps have not been corrupted or tampered with
+///
+/// Files that were installed as is are re-hashed against the manifest's hashes,
+/// and the binaries and shortcuts the manifest declares are checked to exist
+pub struct Args {
+    #[clap(
+        help = "The apps to verify. Defaults to all installed apps",
+        add = ArgValueCandidates::new(completions::installed_apps)
+    )]
+    apps: Vec<package::Reference>,
+
+    #[clap(from_global)]
+    arch: Architecture,
+
+    #[clap(from_global)]
+    format: Format,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// The kind of file that was checked
+enum Kind {
+    /// A downloaded file that was installed as is
+    Payload,
+    /// A binary declared in the manifest's `bin` field
+    Binary,
+    /// A shortcut declared in the manifest's `shortcuts` field
+    Shortcut,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+/// The result of checking a single file of an installed app
+pub(crate) struct Check {
+    name: String,
+    version: String,
+    kind: Kind,
+    status: Status,
+    path: String,
+    /// The hash from the manifest, for payloads
+    expected: Option<String>,
+    /// The hash of the installed file, for payloads
+    actual: Option<String>,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let names = super::names_or_installed(ctx, &self.apps)?;
+
+        let mut checks = names
+            .into_par_iter()
+            .flat_map(|name| self.check_app(ctx, name))
+            .collect::<Vec<_>>();
+
+        checks.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let failed = checks
+            .iter()
+            .filter(|check| check.status != Status::Ok)
+            .collect::<Vec<_>>();
+
+        if self.format.is_structured() {
+            self.format.print(&checks)?;
+        } else if failed.is_empty() {
+            eprintln_green!("All {} checked files are intact", checks.len());
+        } else {
+            let outputs = Structured::new(&failed)
+                .with_priority("status", Priority::Required)
+                .with_priority("kind", Priority::High)
+                .with_priority("path", Priority::High)
+                .with_priority("expected", Priority::Low)
+                .with_priority("actual", Priority::Low);
+
+            print!("{outputs}");
+
+            eprintln_yellow!("Use `sfsu app reset` or reinstall the affected apps to repair them");
+        }
+
+        // Fail so that scripts can tell when apps need repairing
+        if !failed.is_empty() {
+            anyhow::bail!(
+                "{} of {} checked files are corrupted or missing",
+                failed.len(),
+                checks.len()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Args {
+    /// Check the payloads, binaries and shortcuts of an installed app
+    fn check_app(&self, ctx: &impl ScoopContext, name: String) -> Vec<Check> {
+        let app_dir = ctx.apps_path().join(&name);
+
+        if !app_dir.exists() {
+            eprintln_yellow!("{name} is not installed");
+            return vec![];
+        }
+
+        let (manifest, arch) = match install::installed_manifest(&app_dir) {
+            Ok(installed) => installed,
+            Err(e) => {
+                eprintln_yellow!("Skipping {name}, as its manifest could not be read: {e}");
+                return vec![];
+            }
+        };
+
+        let arch = arch.unwrap_or(self.arch);
+
+        let Ok(config) = InstallConfig::from_manifest(&manifest, arch) else {
+            eprintln_yellow!("Skipping {name}, as its manifest could not be parsed");
+            return vec![];
+        };
+
+        let version = manifest.version.to_string();
+        let current_dir = app_dir.join("current");
+        let check = |kind, status, path: &Path| Check {
+            name: name.clone(),
+            version: version.clone(),
+            kind,
+            status,
+            path: path.display().to_string(),
+            expected: None,
+            actual: None,
+        };
+
+        let mut checks = vec![];
+
+        for (index, url) in config.url.clone().into_vec().into_iter().enumerate() {
+            // Archives and installers are unpacked, so there is nothing left to hash
+            if !extract::is_copied(&url, config.innosetup) {
+                continue;
+            }
+
+            let file_name = extract::url_file_name(&url);
+
+            // Scoop runs the first download if the installer doesn't name a file, and removes it unless asked to keep it
+            let removed_by_installer = config.installer.as_ref().is_some_and(|installer| {
+                !installer.keep
+                    && match &installer.file {
+                        Some(file) => *file == file_name,
+                        None => index == 0 && installer.has_program(),
+                    }
+            });
+            if removed_by_installer {
+                continue;
+            }
+
+            let Some(hash) = config.hash.get(index) else {
+                continue;
+            };
+
+            let path = config.extract_to(&current_dir, index).join(&file_name);
+            checks.push(check_payload(
+                check(Kind::Payload, Status::Missing, &path),
+                &path,
+                hash,
+            ));
+        }
+
+        for bin in config.bins() {
+            let path = current_dir.join(&bin.path);
+            let status = if path.exists() {
+                Status::Ok
+            } else {
+                Status::Missing
+            };
+
+            checks.push(check(Kind::Binary, status, &path));
+        }
+
+        if !config.shortcuts.is_empty() {
+            let persist_dir = ctx.persist_path().join(&name);
+            let variables = ScriptVariables {
+                app: &name,
+                version: &version,
+                dir: &current_dir,
+                original_dir: &current_dir,
+                persist_dir: &persist_dir,
+                architecture: arch.to_string(),
+                global: crate::is_global(),
+                cmd: "verify",
+            };
+
+            let Ok(shortcuts_dir) = shortcuts::shortcuts_dir(crate::is_global()) else {
+                eprintln_yellow!(
+                    "Skipping the shortcuts of {name}, as the shortcuts directory could not be found"
+                );
+                return checks;
+            };
+
+            for shortcut in &config.shortcuts {
+                let [target, shortcut_name, ..] = shortcut.as_slice() else {
+                    continue;
+                };
+
+                let link = shortcuts_dir.join(format!("{shortcut_name}.lnk"));
+                let target = current_dir.join(variables.substitute(target));
+
+                let path = if link.exists() { target } else { link };
+                let status = if path.exists() {
+                    Status::Ok
+                } else {
+                    Status::Missing
+                };
+
+                checks.push(check(Kind::Shortcut, status, &path));
+            }
+        }
+
+        checks
+    }
+}
+
+/// Re-hash an installed payload and compare it to the manifest hash
+fn check_payload(check: Check, path: &Path, hash: &str) -> Check {
+    let (algorithm, expected) = match hash.split_once(':') {
+        Some((algorithm, hash)) => (algorithm.to_lowercase(), hash.to_lowercase()),
+        None => ("sha256".to_string(), hash.to_lowercase()),
+    };
+
+    let (status, actual) = if path.exists() {
+        match hash_file(path, &algorithm) {
+            Ok(Some(actual)) if actual == expected => (Status::Ok, Some(actual)),
+            Ok(Some(actual)) => (Status::Mismatch, Some(actual)),
+            Ok(None) => (Status::Unsupported, None),
+            Err(e) => {
+                warn!("Failed to hash {}: {e}", path.display());
+                (Status::Error, None)
+            }
+        }
+    } else {
+        (Status::Missing, None)
+    };
+
+    Check {
+        status,
+        expected: Some(expected),
+        actual,
+        ..check
+    }
+}
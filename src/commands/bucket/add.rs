@@ -14,6 +14,8 @@ pub struct Args {
 }
 
 impl super::Command for Args {
+    const NEEDS_NETWORK: bool = true;
+
     async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
         let repo_url = self.repo.clone().unwrap_or_else(|| {
             let known_buckets = ctx.known_buckets();
@@ -2,29 +2,44 @@ use anyhow::Context;
 use chrono::FixedOffset;
 use clap::Parser;
 use rayon::prelude::*;
+use schemars::JsonSchema;
 use serde::Serialize;
 use sprinkles::{buckets::Bucket, contexts::ScoopContext};
 
-use crate::{output, wrappers::time::NicerTime};
+use crate::{
+    index::BucketIndex,
+    output::{
+        format::Format,
+        structured::{self, Structured},
+    },
+    wrappers::time::NicerTime,
+};
 
 #[derive(Debug, Clone, Parser)]
 /// List all installed buckets
 pub struct Args {
+    #[clap(flatten)]
+    table: structured::Options,
+
     #[clap(from_global)]
-    json: bool,
+    format: Format,
 }
 
-#[derive(Debug, Clone, Serialize)]
-struct BucketInfo {
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub(crate) struct BucketInfo {
     name: String,
     source: String,
+    #[schemars(with = "String")]
     updated: NicerTime<FixedOffset>,
     manifests: usize,
 }
 
 impl BucketInfo {
-    fn collect(bucket: &Bucket) -> anyhow::Result<Self> {
-        let manifests = bucket.manifests()?;
+    fn collect(ctx: &impl ScoopContext, bucket: &Bucket) -> anyhow::Result<Self> {
+        let manifests = match BucketIndex::open(ctx, bucket) {
+            Ok(index) => index.entries.len(),
+            Err(_) => bucket.manifests()?,
+        };
 
         let updated_time = {
             let repo = bucket.open_repo()?;
@@ -50,7 +65,7 @@ impl super::Command for Args {
         let buckets = {
             let mut buckets = buckets
                 .par_iter()
-                .map(BucketInfo::collect)
+                .map(|bucket| BucketInfo::collect(ctx, bucket))
                 .collect::<Result<Vec<_>, _>>()?;
 
             buckets.sort_by(|a, b| a.name.cmp(&b.name));
@@ -58,11 +73,10 @@ impl super::Command for Args {
             buckets
         };
 
-        if self.json {
-            let output = serde_json::to_string_pretty(&buckets)?;
-            println!("{output}");
+        if self.format.is_structured() {
+            self.format.print(&buckets)?;
         } else {
-            let structured = output::structured::Structured::new(&buckets);
+            let structured = Structured::new(&buckets).with_options(&self.table);
 
             println!("{structured}");
         }
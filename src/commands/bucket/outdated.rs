@@ -19,6 +19,7 @@ impl commands::Command for Args {
         message: DeprecationMessage::Replacement("sfsu status"),
         version: Some(2.0),
     });
+    const NEEDS_NETWORK: bool = true;
 
     async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
         let buckets = Bucket::list_all(ctx)?;
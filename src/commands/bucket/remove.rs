@@ -1,13 +1,14 @@
 use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
 use dialoguer::Confirm;
 use sprinkles::contexts::ScoopContext;
 
-use crate::{abandon, output::colours::yellow};
+use crate::{abandon, commands::completions, output::colours::yellow};
 
 #[derive(Debug, Clone, Parser)]
 /// Remove a bucket
 pub struct Args {
-    #[clap(help = "The name of the bucket to delete")]
+    #[clap(help = "The name of the bucket to delete", add = ArgValueCandidates::new(completions::buckets))]
     name: String,
 
     #[clap(from_global)]
@@ -17,16 +17,30 @@ use sprinkles::{
     },
 };
 
-use crate::output::sectioned::{Children, Section};
+use crate::{
+    freshness,
+    index::BucketIndex,
+    output::sectioned::{Children, Section},
+};
 
 #[derive(Debug, Clone, Parser)]
 /// Update Scoop and Scoop buckets
 pub struct Args {
     #[clap(short, long, help = "Show commit messages for each update")]
     changelog: bool,
+
+    #[clap(
+        long,
+        visible_alias = "max-concurrency",
+        help = "The maximum number of buckets to update at once. Defaults to the number of CPU cores",
+        env = "SFSU_MAX_CONCURRENT_UPDATES"
+    )]
+    max_concurrent: Option<std::num::NonZeroUsize>,
 }
 
 impl super::Command for Args {
+    const NEEDS_NETWORK: bool = true;
+
     async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
         let progress_style = style(Some(ProgressOptions::Hide), Some(Message::suffix()));
 
@@ -52,7 +66,11 @@ impl super::Command for Args {
                 let pb = mp.add(
                     ProgressBar::new(1)
                         .with_style(progress_style.clone())
-                        .with_message("Checking updates")
+                        .with_message(if self.max_concurrent.is_some() {
+                            "Waiting"
+                        } else {
+                            "Checking updates"
+                        })
                         .with_prefix(format!("🪣 {:<longest_bucket_name$}", bucket.name()))
                         .with_finish(ProgressFinish::WithMessage(Self::FINISH_MESSAGE.into())),
                 );
@@ -123,16 +141,37 @@ impl Args {
         ctx: &impl ScoopContext,
         outdated_buckets: &'a [(Bucket, ProgressBar)],
     ) -> anyhow::Result<Vec<(Cow<'a, str>, Vec<String>)>> {
-        let bucket_changelogs = outdated_buckets
-            .par_iter()
-            .map(|(bucket, pb)| -> buckets::Result<_> {
-                let repo = bucket.open_repo()?;
-
-                let changelog = self.update(ctx, &repo, pb)?;
+        let update_all = || {
+            outdated_buckets
+                .par_iter()
+                .map(|(bucket, pb)| -> buckets::Result<_> {
+                    pb.set_message("Checking updates");
+
+                    let repo = bucket.open_repo()?;
+
+                    let changelog = self.update(ctx, &repo, pb)?;
+
+                    if changelog.is_some() {
+                        // Rebuild the index now, rather than on the next search
+                        if let Err(e) = BucketIndex::open(ctx, bucket) {
+                            warn!("Failed to update search index for {}: {e}", bucket.name());
+                        }
+                    }
+
+                    Ok((bucket.name(), changelog.unwrap_or_default()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
 
-                Ok((bucket.name(), changelog.unwrap_or_default()))
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+        let bucket_changelogs = if let Some(max_concurrent) = self.max_concurrent {
+            // A dedicated pool limits how many buckets are fetched at once, without limiting the rest of sfsu
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(max_concurrent.get())
+                .build()?
+                .install(update_all)?
+        } else {
+            update_all()?
+        };
 
         Ok(bucket_changelogs)
     }
@@ -149,9 +188,9 @@ impl Args {
         }
 
         let changelog = if self.changelog {
-            repo.pull_with_changelog(ctx, Some(&Self::gen_stats_callback(pb)))?
+            repo.pull_with_changelog(ctx, Some(&freshness::stats_callback(pb)))?
         } else {
-            repo.pull(ctx, Some(&Self::gen_stats_callback(pb)))?;
+            repo.pull(ctx, Some(&freshness::stats_callback(pb)))?;
 
             vec![]
         };
@@ -160,25 +199,4 @@ impl Args {
 
         Ok(Some(changelog))
     }
-
-    fn gen_stats_callback(
-        pb: &ProgressBar,
-    ) -> impl Fn(sprinkles::git::implementations::git2::Progress<'_>, bool) -> bool + '_ {
-        |stats, thin| {
-            if thin {
-                pb.set_position(stats.indexed_objects() as u64);
-                pb.set_length(stats.total_objects() as u64);
-            } else if stats.received_objects() == stats.total_objects() {
-                pb.set_position(stats.indexed_deltas() as u64);
-                pb.set_length(stats.total_deltas() as u64);
-                pb.set_message("Resolving deltas");
-            } else if stats.total_objects() > 0 {
-                pb.set_position(stats.received_objects() as u64);
-                pb.set_length(stats.total_objects() as u64);
-                pb.set_message("Receiving objects");
-            }
-
-            true
-        }
-    }
 }
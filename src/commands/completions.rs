@@ -0,0 +1,127 @@
+use clap::{CommandFactory, Parser, ValueEnum};
+use clap_complete::{engine::CompletionCandidate, env::Shells};
+
+use sprinkles::{
+    buckets::Bucket,
+    contexts::{ScoopContext, User},
+    packages::models::install,
+};
+
+use crate::{abandon, install::shims};
+
+/// The environment variable used to request dynamic completions from sfsu
+const COMPLETE_VAR: &str = "COMPLETE";
+
+#[derive(Debug, Copy, Clone, ValueEnum)]
+/// The shells completions can be generated for
+pub enum Shell {
+    Bash,
+    Elvish,
+    Fish,
+    Nushell,
+    Powershell,
+    Zsh,
+}
+
+#[derive(Debug, Clone, Parser)]
+/// Generate shell completions
+///
+/// Completions for all shells other than Nushell also complete installed app and bucket names
+pub struct Args {
+    #[clap(help = "The shell to generate completions for")]
+    shell: Shell,
+}
+
+impl super::Command for Args {
+    async fn runner(self, _: &impl ScoopContext) -> anyhow::Result<()> {
+        let mut command = crate::Args::command();
+        let name = command.get_name().to_string();
+        let mut stdout = std::io::stdout();
+
+        let shell_name = match self.shell {
+            Shell::Nushell => {
+                // Nushell is not supported by clap's dynamic completions, so only static completions are generated
+                clap_complete::generate(
+                    clap_complete_nushell::Nushell,
+                    &mut command,
+                    name,
+                    &mut stdout,
+                );
+
+                return Ok(());
+            }
+            Shell::Bash => "bash",
+            Shell::Elvish => "elvish",
+            Shell::Fish => "fish",
+            Shell::Powershell => "powershell",
+            Shell::Zsh => "zsh",
+        };
+
+        let Some(completer) = Shells::builtins().completer(shell_name) else {
+            abandon!("Completions are not supported for {shell_name}");
+        };
+
+        let exe = std::env::current_exe()?;
+
+        completer.write_registration(
+            COMPLETE_VAR,
+            &name,
+            &name,
+            &exe.display().to_string(),
+            &mut stdout,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Handle dynamic completion requests from the registered shell completions
+///
+/// Exits the process if sfsu was invoked to provide completions, otherwise does nothing
+pub fn handle_requests() {
+    clap_complete::CompleteEnv::with_factory(crate::Args::command)
+        .var(COMPLETE_VAR)
+        .complete();
+}
+
+/// List the names of installed apps, for completing app arguments
+pub fn installed_apps() -> Vec<CompletionCandidate> {
+    let Ok(ctx) = User::new() else {
+        return vec![];
+    };
+
+    install::Manifest::list_all_unchecked(&ctx)
+        .map(|apps| {
+            apps.iter()
+                .map(|app| CompletionCandidate::new(unsafe { app.name() }))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// List the names of added buckets, for completing bucket arguments
+pub fn buckets() -> Vec<CompletionCandidate> {
+    let Ok(ctx) = User::new() else {
+        return vec![];
+    };
+
+    Bucket::list_all(&ctx)
+        .map(|buckets| {
+            buckets
+                .iter()
+                .map(|bucket| CompletionCandidate::new(bucket.name().to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// List the names of existing shims, for completing shim arguments
+pub fn shims() -> Vec<CompletionCandidate> {
+    let Ok(ctx) = User::new() else {
+        return vec![];
+    };
+
+    shims::list(&ctx)
+        .map(|names| names.into_iter().map(CompletionCandidate::new).collect())
+        .unwrap_or_default()
+}
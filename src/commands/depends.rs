@@ -1,36 +1,148 @@
+use std::collections::{HashMap, HashSet};
+
 use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use rayon::prelude::*;
+use regex::Regex;
+use serde::Serialize;
 use sprinkles::{
+    buckets::Bucket,
     contexts::ScoopContext,
-    packages::reference::{manifest, package},
+    packages::{
+        SearchMode,
+        models::install,
+        reference::{manifest, package},
+    },
 };
 
 use crate::{
     abandon,
-    output::sectioned::{Children, Section, Sections},
+    commands::completions,
+    index,
+    output::{
+        WHITESPACE,
+        sectioned::{Children, Section, Sections},
+    },
 };
 
 #[derive(Debug, Clone, Parser)]
 /// List the dependencies of a given package, in the order that they will be installed
 pub struct Args {
-    #[clap(help = "The package to list dependencies for")]
+    #[clap(
+        help = "The package to list dependencies for",
+        add = ArgValueCandidates::new(completions::installed_apps)
+    )]
     package: package::Reference,
 
-    #[clap(help = "The bucket of the given package")]
+    #[clap(
+        help = "The bucket of the given package",
+        add = ArgValueCandidates::new(completions::buckets)
+    )]
     bucket: Option<String>,
 
+    #[clap(
+        short,
+        long,
+        help = "List the apps that depend on the given package, instead of its dependencies"
+    )]
+    reverse: bool,
+
+    #[clap(
+        short,
+        long,
+        requires = "reverse",
+        help = "Search all bucket manifests for dependents, rather than only installed apps"
+    )]
+    all: bool,
+
     // TODO: Implement recursion?
     // recursive: bool,
     #[clap(from_global)]
     json: bool,
 }
 
+#[derive(Debug, Clone, Serialize)]
+/// An app that depends on another app, along with its own dependents
+struct Dependent {
+    name: String,
+    bucket: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    dependents: Vec<Dependent>,
+}
+
+impl Dependent {
+    /// Build the tree of apps that depend on the given app
+    ///
+    /// Apps that have already been visited are not expanded again, to avoid infinite recursion on cyclic dependencies
+    fn tree(
+        name: &str,
+        dependents: &HashMap<String, Vec<(String, String)>>,
+        visited: &mut HashSet<String>,
+    ) -> Vec<Self> {
+        let Some(direct) = dependents.get(name) else {
+            return vec![];
+        };
+
+        direct
+            .iter()
+            .map(|(name, bucket)| Self {
+                name: name.clone(),
+                bucket: bucket.clone(),
+                dependents: if visited.insert(name.clone()) {
+                    Self::tree(name, dependents, visited)
+                } else {
+                    vec![]
+                },
+            })
+            .collect()
+    }
+
+    fn write_tree(&self, f: &mut std::fmt::Formatter<'_>, depth: usize) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{}{} ({})",
+            WHITESPACE.repeat(depth),
+            self.name,
+            self.bucket
+        )?;
+
+        for dependent in &self.dependents {
+            dependent.write_tree(f, depth + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Dependent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_tree(f, 1)
+    }
+}
+
 impl super::Command for Args {
     async fn runner(mut self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
-        if let Some(bucket) = self.bucket {
+        if let Some(bucket) = self.bucket.take() {
             self.package.set_bucket(bucket)?;
         }
 
-        let manifests = self.package.list_manifests(ctx).await?;
+        if self.reverse {
+            return self.list_dependents(ctx);
+        }
+
+        let manifests = match &self.package.manifest {
+            manifest::Reference::Name(name) => match index::buckets_containing(ctx, name) {
+                Ok(buckets) => buckets
+                    .iter()
+                    .filter_map(|bucket| bucket.get_manifest(name).ok())
+                    .collect(),
+                Err(e) => {
+                    debug!("Failed to use search index: {e}");
+                    self.package.list_manifests(ctx).await?
+                }
+            },
+            _ => self.package.list_manifests(ctx).await?,
+        };
 
         if manifests.is_empty() {
             abandon!("Could not find any packages matching: {}", self.package);
@@ -62,4 +174,105 @@ impl super::Command for Args {
     }
 }
 
+impl Args {
+    fn list_dependents(&self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let name = super::app::reference_name(&self.package);
+
+        let dependents = dependents_map(self.list_manifests(ctx)?);
+
+        let tree = Dependent::tree(&name, &dependents, &mut HashSet::from([name.clone()]));
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&tree)?);
+            return Ok(());
+        }
+
+        if tree.is_empty() {
+            println!(
+                "No {} depend on '{name}'",
+                if self.all { "apps" } else { "installed apps" }
+            );
+            return Ok(());
+        }
+
+        println!("Apps that depend on '{name}':");
+        for dependent in tree {
+            print!("{dependent}");
+        }
+
+        Ok(())
+    }
+
+    /// List the name, bucket and dependencies of each manifest to check for dependents
+    fn list_manifests(
+        &self,
+        ctx: &impl ScoopContext,
+    ) -> anyhow::Result<Vec<(String, String, Vec<manifest::Reference>)>> {
+        if self.all {
+            let pattern = Regex::new("")?;
+
+            let manifests = Bucket::list_all(ctx)?
+                .par_iter()
+                .filter_map(|bucket| {
+                    bucket
+                        .matches(ctx, false, &pattern, SearchMode::default())
+                        .ok()
+                })
+                .flatten()
+                .map(|manifest| unsafe {
+                    (
+                        manifest.name().to_string(),
+                        manifest.bucket().to_string(),
+                        manifest.depends(),
+                    )
+                })
+                .collect();
+
+            Ok(manifests)
+        } else {
+            installed_manifests(ctx)
+        }
+    }
+}
+
+/// List the name, bucket and dependencies of each installed app
+pub(crate) fn installed_manifests(
+    ctx: &impl ScoopContext,
+) -> anyhow::Result<Vec<(String, String, Vec<manifest::Reference>)>> {
+    let manifests = install::Manifest::list_all_unchecked(ctx)?
+        .par_iter()
+        .filter_map(|app| {
+            let manifest = app.get_manifest(ctx).ok()?;
+
+            Some((
+                unsafe { app.name() }.to_string(),
+                app.bucket.clone().unwrap_or_default(),
+                manifest.depends(),
+            ))
+        })
+        .collect();
+
+    Ok(manifests)
+}
+
+/// Map each dependency name to the (name, bucket) pairs of the apps that depend on it
+pub(crate) fn dependents_map(
+    manifests: Vec<(String, String, Vec<manifest::Reference>)>,
+) -> HashMap<String, Vec<(String, String)>> {
+    let mut dependents: HashMap<String, Vec<(String, String)>> = HashMap::new();
+
+    for (dependent, bucket, dependencies) in manifests {
+        for dependency in dependencies {
+            if let Some(dependency) = dependency.into_package_ref().name() {
+                dependents
+                    .entry(dependency)
+                    .or_default()
+                    .push((dependent.clone(), bucket.clone()));
+            }
+        }
+    }
+
+    dependents
+}
+
 // note to self, use `phpstudy-lagecy-scoop` to test this command
@@ -2,13 +2,22 @@ use clap::Parser;
 
 use sprinkles::{config, contexts::ScoopContext};
 
-use crate::models::export::Export;
+use crate::{models::export::Export, output::format::Format};
 
 #[derive(Debug, Clone, Parser)]
-/// Exports installed apps, buckets (and optionally configs) in JSON format
+/// Exports installed apps, buckets (and optionally configs) in JSON format, or the format passed with `--format`
 pub struct Args {
     #[clap(short, long, help = "Export the scoop config as well")]
     config: bool,
+
+    #[clap(
+        long,
+        help = "Match the output of `scoop export`, so it can be imported on another machine with `scoop import`"
+    )]
+    scoop: bool,
+
+    #[clap(from_global)]
+    format: Format,
 }
 
 impl super::Command for Args {
@@ -23,9 +32,12 @@ impl super::Command for Args {
             export
         };
 
-        let output = serde_json::to_string_pretty(&export)?;
-
-        println!("{output}");
+        // The default table format falls back to JSON, which keeps the existing output
+        if self.scoop {
+            self.format.print(&export.into_scoop(ctx)?)?;
+        } else {
+            self.format.print(&export)?;
+        }
 
         Ok(())
     }
@@ -1,9 +1,45 @@
-use clap::Parser;
+use std::fmt::Display;
+
+use clap::{Parser, ValueEnum};
 use quork::traits::list::ListVariants;
 use sprinkles::{contexts::ScoopContext, shell::Shell};
 
 use super::CommandHooks as CommandsHooks;
 
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, ValueEnum)]
+/// The shells hooks can be generated for
+///
+/// Fish is handled here, as it isn't one of Sprinkles' shells
+pub enum HookShell {
+    #[default]
+    Powershell,
+    Bash,
+    Zsh,
+    Nu,
+    Fish,
+}
+
+impl HookShell {
+    /// Get the shell's config file
+    fn config(self) -> String {
+        match self {
+            HookShell::Powershell => Shell::Powershell.config().to_string(),
+            HookShell::Bash => Shell::Bash.config().to_string(),
+            HookShell::Zsh => Shell::Zsh.config().to_string(),
+            HookShell::Nu => Shell::Nu.config().to_string(),
+            HookShell::Fish => "~/.config/fish/config.fish".to_string(),
+        }
+    }
+}
+
+impl Display for HookShell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = self.to_possible_value().expect("no variants are skipped");
+
+        f.write_str(value.get_name())
+    }
+}
+
 #[derive(Debug, Clone, Parser)]
 /// Generate hooks for the given shell
 pub struct Args {
@@ -13,8 +49,14 @@ pub struct Args {
     #[clap(short = 'E', long, help = "The commands to exclusively enable")]
     enabled: Vec<CommandsHooks>,
 
-    #[clap(short, long, help = "Print hooks for the given shell", default_value_t = Shell::Powershell)]
-    shell: Shell,
+    #[clap(
+        short,
+        long,
+        help = "Print hooks for the given shell",
+        value_enum,
+        default_value_t
+    )]
+    shell: HookShell,
 }
 
 impl super::Command for Args {
@@ -37,7 +79,7 @@ impl super::Command for Args {
         .collect();
 
         match shell {
-            Shell::Powershell => {
+            HookShell::Powershell => {
                 print!("function scoop {{ switch ($args[0]) {{ ");
 
                 // I would love to make this all one condition, but Powershell doesn't seem to support that elegantly
@@ -55,7 +97,7 @@ impl super::Command for Args {
                 // println!("# To add this to your config, add the following line to the end of your PowerShell profile:");
                 // println!("#     Invoke-Expression (&sfsu hook)");
             }
-            Shell::Bash | Shell::Zsh => {
+            HookShell::Bash | HookShell::Zsh => {
                 println!(
                     "SCOOP_EXEC=$(which scoop) \n\
                     scoop () {{ \n\
@@ -78,7 +120,7 @@ impl super::Command for Args {
                     #   source <(sfsu.exe hook --shell {shell})"
                 );
             }
-            Shell::Nu => {
+            HookShell::Nu => {
                 for command in enabled_hooks {
                     println!(
                         "def --wrapped \"scoop {hook}\" [...rest] {{ sfsu {command} ...$rest }}",
@@ -93,6 +135,23 @@ impl super::Command for Args {
                         #   source ~/.cache/sfsu.nu"
                 );
             }
+            HookShell::Fish => {
+                println!("function scoop\n    switch $argv[1]");
+
+                for command in enabled_hooks {
+                    println!(
+                        "        case {hook}\n            sfsu.exe {command} $argv[2..-1]",
+                        hook = command.hook(),
+                        command = command.command()
+                    );
+                }
+
+                println!(
+                    "        case '*'\n            command scoop $argv\n    end\nend\n\n\
+                    # Add the following to the end of your {shell_config}\n\
+                    #   sfsu.exe hook --shell {shell} | source"
+                );
+            }
         }
 
         Ok(())
@@ -0,0 +1,191 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use itertools::Itertools;
+use sprinkles::{Architecture, config, contexts::ScoopContext, packages::reference::manifest};
+
+use crate::{
+    abandon,
+    models::export::{Export, MACHINE_CONFIG_KEYS},
+    output::colours::{eprintln_green, eprintln_yellow},
+};
+
+use super::{Command, app::install};
+
+/// The note `sfsu export` adds to held apps
+const HELD_PACKAGE: &str = "Held package";
+
+#[derive(Debug, Clone, Parser)]
+/// Imports apps, buckets and configs from a file created with `sfsu export`
+///
+/// Buckets are added before any apps are installed. Held apps are installed at their exported version and held again,
+/// and apps exported with `--scoop` are installed for the architecture noted in their info.
+/// The exported config is merged into the existing config, leaving out machine specific values
+pub struct Args {
+    #[clap(
+        help = "The exported file to import. YAML and TOML files are detected by their extension"
+    )]
+    file: PathBuf,
+
+    #[clap(
+        long,
+        help = "Don't apply the Scoop config from the export, if it has one"
+    )]
+    no_config: bool,
+
+    #[clap(
+        long,
+        help = "Print what would be done, but don't actually do anything"
+    )]
+    dry_run: bool,
+
+    #[clap(from_global)]
+    arch: Architecture,
+}
+
+impl Command for Args {
+    const BETA: bool = true;
+    const NEEDS_NETWORK: bool = true;
+
+    async fn runner(self, ctx: &impl ScoopContext<Config = config::Scoop>) -> anyhow::Result<()> {
+        let export: Export = match std::fs::read_to_string(&self.file) {
+            Ok(contents) => match self.file.extension().and_then(|ext| ext.to_str()) {
+                Some("yaml" | "yml") => serde_yaml::from_str(&contents)?,
+                Some("toml") => toml::from_str(&contents)?,
+                _ => serde_json::from_str(&contents)?,
+            },
+            Err(e) => abandon!("Failed to read {}: {e}", self.file.display()),
+        };
+
+        for bucket in &export.buckets {
+            let dest_path = ctx.buckets_path().join(&bucket.name);
+
+            if dest_path.exists() {
+                continue;
+            }
+
+            if self.dry_run {
+                eprintln!("Would add bucket {} ({})", bucket.name, bucket.source);
+                continue;
+            }
+
+            eprintln!("Adding bucket {}", bucket.name);
+
+            let root = prodash::tree::Root::new();
+            let handle = crate::progress::render::LineRenderer::run(root.clone(), true);
+
+            let clone_progress = root.add_child_with_id("Cloning repository", *b"REPO");
+
+            sprinkles::git::clone::clone(&bucket.source, dest_path, clone_progress)?;
+
+            handle.await?;
+        }
+
+        if let Some(config) = export.config.filter(|_| !self.no_config) {
+            if self.dry_run {
+                eprintln!("Would apply the exported Scoop config");
+            } else {
+                merge_config(ctx.config(), &config)?.save()?;
+            }
+        }
+
+        let mut apps = vec![];
+        let mut held = vec![];
+
+        for app in export.apps {
+            let mut reference = if ctx.buckets_path().join(&app.source).exists() {
+                manifest::Reference::BucketNamePair {
+                    bucket: app.source.clone(),
+                    name: app.name.clone(),
+                }
+            } else {
+                manifest::Reference::Name(app.name.clone())
+            }
+            .into_package_ref();
+
+            if reference.installed(ctx)? {
+                continue;
+            }
+
+            // Held apps are pinned to their exported version, as Scoop's import does
+            if app.info.contains(HELD_PACKAGE) {
+                reference.set_version(app.version.clone());
+                held.push(app.name.clone());
+            } else if let Some(manifest) = reference.first(ctx) {
+                if manifest.version.to_string() != app.version {
+                    eprintln_yellow!(
+                        "{} {} is no longer available, {} will be installed instead",
+                        app.name,
+                        app.version,
+                        manifest.version
+                    );
+                }
+            }
+
+            let arch = app.architecture().unwrap_or(self.arch);
+
+            if self.dry_run {
+                eprintln!("Would install {reference} ({arch})");
+
+                if held.contains(&app.name) {
+                    eprintln!("Would hold {}", app.name);
+                }
+            } else {
+                apps.push((reference, arch));
+            }
+        }
+
+        if self.dry_run {
+            return Ok(());
+        }
+
+        if apps.is_empty() {
+            eprintln_green!("All apps are already installed");
+            return Ok(());
+        }
+
+        // Install the apps for each architecture separately, as the architecture applies to the whole install
+        let by_arch = apps
+            .into_iter()
+            .into_group_map_by(|(_, arch)| arch.to_string());
+
+        for (_, apps) in by_arch {
+            let arch = apps[0].1;
+            let apps = apps.into_iter().map(|(reference, _)| reference).collect();
+
+            install::Args::new(apps, arch).runner(ctx).await?;
+        }
+
+        for name in held {
+            if let Err(e) = crate::install::set_held(&ctx.apps_path().join(&name), true) {
+                eprintln_yellow!("Failed to hold {name}: {e}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Merge the exported config into the existing config
+///
+/// Keys that are missing from the export, or are specific to the machine it was exported from, keep their existing values
+fn merge_config(
+    existing: &config::Scoop,
+    exported: &config::Scoop,
+) -> serde_json::Result<config::Scoop> {
+    let mut merged = serde_json::to_value(existing)?;
+
+    if let (Some(merged), serde_json::Value::Object(exported)) =
+        (merged.as_object_mut(), serde_json::to_value(exported)?)
+    {
+        for (key, value) in exported {
+            if value.is_null() || MACHINE_CONFIG_KEYS.contains(&key.as_str()) {
+                continue;
+            }
+
+            merged.insert(key, value);
+        }
+    }
+
+    serde_json::from_value(merged)
+}
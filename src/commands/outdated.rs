@@ -2,6 +2,10 @@ use clap::{Parser, Subcommand};
 use serde_json::Map;
 use sprinkles::{config, contexts::ScoopContext};
 
+use crate::output::{format::Format, structured};
+#[cfg(feature = "contexts")]
+use crate::scope::Scope;
+
 use super::{Command, CommandRunner, DeprecationMessage, DeprecationWarning, Runnable};
 
 pub mod apps;
@@ -31,8 +35,25 @@ pub struct Args {
     #[command(subcommand)]
     command: Option<Commands>,
 
+    #[clap(
+        short,
+        long,
+        help = "Fetch the latest bucket changes before checking for outdated apps"
+    )]
+    remote: bool,
+
+    #[cfg(feature = "contexts")]
+    #[clap(
+        long,
+        help = "Check apps from the user install, the global install, or both. Each app is annotated with its install"
+    )]
+    scope: Option<Scope>,
+
+    #[clap(flatten)]
+    table: structured::Options,
+
     #[clap(from_global)]
-    json: bool,
+    format: Format,
 }
 
 impl Command for Args {
@@ -45,32 +66,55 @@ impl Command for Args {
         if let Some(command) = self.command {
             command.run(ctx).await
         } else {
-            if self.json {
+            if self.format.is_structured() {
                 let mut map = Map::new();
 
-                let apps = apps::Args { json: self.json }
-                    .run_direct(ctx, false)?
-                    .unwrap_or_default();
+                let apps = apps::Args {
+                    remote: self.remote,
+                    #[cfg(feature = "contexts")]
+                    scope: self.scope,
+                    table: self.table,
+                    format: self.format,
+                }
+                .run_direct(ctx, false)?
+                .unwrap_or_default();
 
-                let buckets = buckets::Args { json: self.json }
+                // Checking buckets requires network access, so they are left out when offline
+                let buckets = if crate::is_offline() {
+                    vec![]
+                } else {
+                    buckets::Args {
+                        format: self.format,
+                    }
                     .run_direct(ctx, false)?
-                    .unwrap_or_default();
+                    .unwrap_or_default()
+                };
 
                 map.insert("outdated_apps".into(), apps.into());
                 map.insert("outdated_buckets".into(), buckets.into());
 
-                let output = serde_json::to_string_pretty(&map)?;
-
-                println!("{output}");
+                self.format.print(&map)?;
             } else {
                 println!("Outdated Apps:");
-                Commands::Apps(apps::Args { json: self.json })
-                    .run(ctx)
-                    .await?;
-                println!("\nOutdated Buckets:");
-                Commands::Buckets(buckets::Args { json: self.json })
+                Commands::Apps(apps::Args {
+                    remote: self.remote,
+                    #[cfg(feature = "contexts")]
+                    scope: self.scope,
+                    table: self.table,
+                    format: self.format,
+                })
+                .run(ctx)
+                .await?;
+                if crate::is_offline() {
+                    println!("\nSkipping outdated buckets in offline mode.");
+                } else {
+                    println!("\nOutdated Buckets:");
+                    Commands::Buckets(buckets::Args {
+                        format: self.format,
+                    })
                     .run(ctx)
                     .await?;
+                }
             }
 
             Ok(())
@@ -3,13 +3,39 @@ use rayon::prelude::*;
 use serde_json::Value;
 use sprinkles::{buckets::Bucket, contexts::ScoopContext, packages::models::install};
 
-use crate::{models::outdated::Info, output::structured::Structured};
+#[cfg(feature = "contexts")]
+use crate::scope::Scope;
+use crate::{
+    freshness,
+    models::outdated::Info,
+    output::{
+        format::Format,
+        structured::{self, Priority, Structured},
+    },
+};
 
 #[derive(Debug, Clone, Parser)]
 /// List outdated apps
 pub struct Args {
+    #[clap(
+        short,
+        long,
+        help = "Fetch the latest bucket changes before checking for outdated apps"
+    )]
+    pub(super) remote: bool,
+
+    #[cfg(feature = "contexts")]
+    #[clap(
+        long,
+        help = "Check apps from the user install, the global install, or both. Each app is annotated with its install"
+    )]
+    pub(super) scope: Option<Scope>,
+
+    #[clap(flatten)]
+    pub(super) table: structured::Options,
+
     #[clap(from_global)]
-    pub(super) json: bool,
+    pub(super) format: Format,
 }
 
 impl super::super::Command for Args {
@@ -30,27 +56,11 @@ impl Args {
         ctx: &impl ScoopContext,
         is_subcommand: bool,
     ) -> Result<Option<Vec<Value>>, anyhow::Error> {
-        let apps = install::Manifest::list_all_unchecked(ctx)?;
-
-        let mut outdated: Vec<Info> = apps
-            .par_iter()
-            .flat_map(|app| -> anyhow::Result<Info> {
-                if let Some(bucket) = &app.bucket {
-                    let local_manifest = app.get_manifest(ctx)?;
-                    // TODO: Add the option to check all buckets and find the highest version (will require semver to order versions)
-                    let bucket = Bucket::from_name(ctx, bucket)?;
-
-                    let remote_manifest = bucket.get_manifest(unsafe { app.name() })?;
+        if self.remote {
+            freshness::refresh_buckets(ctx)?;
+        }
 
-                    match Info::from_manifests(&local_manifest, &remote_manifest) {
-                        Some(info) => Ok(info),
-                        None => anyhow::bail!("no update available"),
-                    }
-                } else {
-                    anyhow::bail!("no bucket specified")
-                }
-            })
-            .collect();
+        let mut outdated = self.check_scopes(ctx)?;
 
         if outdated.is_empty() {
             println!("No outdated packages.");
@@ -63,16 +73,19 @@ impl Args {
                 .map(serde_json::to_value)
                 .collect::<Result<Vec<_>, _>>()?;
 
-            if self.json {
+            if self.format.is_structured() {
                 if !is_subcommand {
                     return Ok(Some(values));
                 }
 
-                let output = serde_json::to_string_pretty(&values)?;
-
-                println!("{output}");
+                self.format.print(&values)?;
             } else {
-                let outputs = Structured::new(&values);
+                let outputs = Structured::new(&values)
+                    .with_priority("current", Priority::High)
+                    .with_priority("available", Priority::High)
+                    .with_priority("pinned", Priority::High)
+                    .with_priority("scope", Priority::High)
+                    .with_options(&self.table);
 
                 print!("{outputs}");
             }
@@ -80,4 +93,57 @@ impl Args {
 
         Ok(None)
     }
+
+    /// Check the apps in each Scoop install in the selected scope, or only the current context if no scope was selected
+    fn check_scopes(&self, ctx: &impl ScoopContext) -> anyhow::Result<Vec<Info>> {
+        #[cfg(feature = "contexts")]
+        if let Some(scope) = self.scope {
+            let mut outdated = vec![];
+
+            for (scope, ctx) in scope.contexts()? {
+                let infos = Self::outdated_apps(&ctx)?;
+
+                outdated.extend(infos.into_iter().map(|info| Info {
+                    scope: Some(scope),
+                    ..info
+                }));
+            }
+
+            return Ok(outdated);
+        }
+
+        Self::outdated_apps(ctx)
+    }
+
+    /// Find the installed apps with a newer version available in their bucket
+    fn outdated_apps(ctx: &impl ScoopContext) -> anyhow::Result<Vec<Info>> {
+        let apps = install::Manifest::list_all_unchecked(ctx)?;
+
+        let outdated = apps
+            .par_iter()
+            .flat_map(|app| -> anyhow::Result<Info> {
+                if let Some(bucket) = &app.bucket {
+                    let local_manifest = app.get_manifest(ctx)?;
+                    // TODO: Add the option to check all buckets and find the highest version (will require semver to order versions)
+                    let bucket = Bucket::from_name(ctx, bucket)?;
+
+                    let remote_manifest = bucket.get_manifest(unsafe { app.name() })?;
+
+                    match Info::from_manifests(&local_manifest, &remote_manifest) {
+                        Some(info) => Ok(Info {
+                            pinned: crate::install::pinned_version(
+                                &ctx.apps_path().join(unsafe { app.name() }),
+                            ),
+                            ..info
+                        }),
+                        None => anyhow::bail!("no update available"),
+                    }
+                } else {
+                    anyhow::bail!("no bucket specified")
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Ok(outdated)
+    }
 }
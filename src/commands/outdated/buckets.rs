@@ -3,14 +3,18 @@ use itertools::Itertools;
 use rayon::prelude::*;
 use sprinkles::{buckets::Bucket, contexts::ScoopContext};
 
+use crate::output::format::Format;
+
 #[derive(Debug, Clone, Parser)]
 /// List outdated buckets
 pub struct Args {
     #[clap(from_global)]
-    pub(super) json: bool,
+    pub(super) format: Format,
 }
 
 impl super::super::Command for Args {
+    const NEEDS_NETWORK: bool = true;
+
     async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
         self.run_direct(ctx, true)?;
 
@@ -43,7 +47,7 @@ impl Args {
             })
             .collect::<Vec<_>>();
 
-        if self.json {
+        if self.format.is_structured() {
             let outdated_bucket_names = outdated_buckets
                 .into_iter()
                 .map(|bucket| bucket.name().to_string())
@@ -53,9 +57,7 @@ impl Args {
                 return Ok(Some(outdated_bucket_names));
             }
 
-            let output = serde_json::to_string_pretty(&outdated_bucket_names)?;
-
-            println!("{output}");
+            self.format.print(&outdated_bucket_names)?;
         } else if outdated_buckets.is_empty() {
             println!("All buckets are up to date!");
         } else {
@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+
+use clap::{Parser, ValueEnum};
+use schemars::{JsonSchema, schema::RootSchema, schema_for};
+use sprinkles::contexts::ScoopContext;
+
+use crate::{
+    history, known_buckets,
+    models::{export::Export, info::Package, min, outdated, status, virustotal},
+};
+
+#[derive(Debug, Copy, Clone, ValueEnum)]
+/// The commands that have structured output
+pub enum Output {
+    List,
+    Search,
+    SearchFlat,
+    SearchRemote,
+    SearchVersions,
+    Info,
+    Status,
+    Outdated,
+    OutdatedApps,
+    OutdatedBuckets,
+    BucketList,
+    Export,
+    #[cfg(feature = "download")]
+    Verify,
+    #[cfg(feature = "download")]
+    AppVerify,
+    AppShortcuts,
+    AppEnv,
+    AppFiles,
+    AppSize,
+    AppNotes,
+    AppScripts,
+    ShimList,
+    ShimInfo,
+    Scan,
+    Which,
+}
+
+#[derive(Debug, Clone, Parser)]
+/// Print the JSON Schema of a command's structured output
+///
+/// The schema describes the output of `--json` and `--format`, so scripts can validate against it
+pub struct Args {
+    #[clap(help = "The command to print the output schema for")]
+    command: Output,
+}
+
+#[derive(JsonSchema)]
+#[allow(dead_code)]
+/// The output of `sfsu status`. Sections that were not checked are left out
+struct StatusOutput {
+    /// Whether Scoop itself is out of date
+    scoop: Option<bool>,
+    /// The names of the outdated buckets
+    buckets: Option<Vec<String>>,
+    /// The outdated or otherwise broken apps
+    packages: Option<Vec<status::Info>>,
+    /// The buckets that have not been updated within `--threshold`
+    stale_buckets: Option<Vec<status::Stale>>,
+    /// The apps that have not been updated within `--threshold`
+    stale_apps: Option<Vec<status::Stale>>,
+}
+
+#[derive(JsonSchema)]
+#[allow(dead_code)]
+/// The output of `sfsu outdated`
+struct OutdatedOutput {
+    /// The outdated apps
+    outdated_apps: Vec<outdated::Info>,
+    /// The names of the outdated buckets
+    outdated_buckets: Vec<String>,
+}
+
+impl super::Command for Args {
+    async fn runner(self, _: &impl ScoopContext) -> anyhow::Result<()> {
+        let schema = self.command.schema();
+
+        println!("{}", serde_json::to_string_pretty(&schema)?);
+
+        Ok(())
+    }
+}
+
+impl Output {
+    fn schema(self) -> RootSchema {
+        match self {
+            Output::List => schema_for!(Vec<min::Info>),
+            Output::Search => schema_for!(BTreeMap<String, Vec<super::search::MatchedOutput>>),
+            Output::SearchFlat => schema_for!(Vec<super::search::MatchedOutput>),
+            Output::SearchRemote => schema_for!(Vec<known_buckets::Match>),
+            Output::SearchVersions => schema_for!(Vec<history::AppHistory>),
+            Output::Info => schema_for!(Package),
+            Output::Status => schema_for!(StatusOutput),
+            Output::Outdated => schema_for!(OutdatedOutput),
+            Output::OutdatedApps => schema_for!(Vec<outdated::Info>),
+            Output::OutdatedBuckets => schema_for!(Vec<String>),
+            Output::BucketList => schema_for!(Vec<super::bucket::list::BucketInfo>),
+            Output::Export => schema_for!(Export),
+            #[cfg(feature = "download")]
+            Output::Verify => schema_for!(Vec<super::verify::Verification>),
+            #[cfg(feature = "download")]
+            Output::AppVerify => schema_for!(Vec<super::app::verify::Check>),
+            Output::AppShortcuts => schema_for!(Vec<super::app::shortcuts::Shortcut>),
+            Output::AppEnv => schema_for!(Vec<super::app::env::Change>),
+            Output::AppFiles => schema_for!(Vec<super::app::files::File>),
+            Output::AppSize => schema_for!(Vec<super::app::size::AppSize>),
+            Output::AppNotes => schema_for!(Vec<super::app::notes::Notes>),
+            Output::AppScripts => schema_for!(super::app::scripts::Scripts),
+            Output::ShimList => schema_for!(Vec<super::shim::list::ShimInfo>),
+            Output::ShimInfo => schema_for!(super::shim::info::ShimDetails),
+            Output::Scan => schema_for!(Vec<virustotal::Report>),
+            Output::Which => schema_for!(super::which::Which),
+        }
+    }
+}
@@ -1,30 +1,212 @@
-use std::collections::HashMap;
+use std::{
+    cmp::Ordering,
+    collections::BTreeMap,
+    io::Write,
+    sync::atomic::{self, AtomicBool},
+};
 
 use itertools::Itertools;
 use rayon::prelude::*;
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use clap_complete::engine::ArgValueCandidates;
 use regex::Regex;
 
 use sprinkles::{
     Architecture,
     buckets::Bucket,
-    contexts::ScoopContext,
+    contexts::{AnyContext, ScoopContext},
     packages::{Manifest, MergeDefaults, SearchMode},
     version::Version,
 };
 
 use crate::{
+    abandon,
     calm_panic::CalmUnwrap,
-    output::sectioned::{Children, Section, Sections, Text},
+    commands::completions,
+    fuzzy, history,
+    index::{BucketIndex, Entry},
+    install::config::InstallConfig,
+    known_buckets,
+    output::{
+        format::Format,
+        powershell,
+        sectioned::{Children, Section, Sections, Text},
+        structured::{self, Priority, Structured},
+    },
+    pattern,
+    scope::Scope,
+    version_order,
 };
 
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, ValueEnum)]
+/// The fields of a manifest to match the pattern against
+pub enum Mode {
+    #[default]
+    /// Match app names
+    Name,
+    /// Match binaries
+    Binary,
+    /// Match app names and binaries
+    Both,
+    /// Match app descriptions
+    Description,
+    /// Match app homepages
+    Homepage,
+    /// Match the notes shown after installing apps
+    Notes,
+    /// Match the names of start menu shortcuts
+    Shortcuts,
+    /// Match the directories apps add to `PATH`
+    Env,
+    /// Match app names, binaries, descriptions, homepages, notes, shortcuts and `PATH` directories
+    All,
+    /// Match all of the above, as well as any other text within the manifest
+    Full,
+}
+
+impl Mode {
+    fn match_names(self) -> bool {
+        matches!(self, Mode::Name | Mode::Both | Mode::All | Mode::Full)
+    }
+
+    fn match_binaries(self) -> bool {
+        matches!(self, Mode::Binary | Mode::Both | Mode::All | Mode::Full)
+    }
+
+    fn match_descriptions(self) -> bool {
+        matches!(self, Mode::Description | Mode::All | Mode::Full)
+    }
+
+    fn match_homepages(self) -> bool {
+        matches!(self, Mode::Homepage | Mode::All | Mode::Full)
+    }
+
+    fn match_notes(self) -> bool {
+        matches!(self, Mode::Notes | Mode::All | Mode::Full)
+    }
+
+    fn match_shortcuts(self) -> bool {
+        matches!(self, Mode::Shortcuts | Mode::All | Mode::Full)
+    }
+
+    fn match_env(self) -> bool {
+        matches!(self, Mode::Env | Mode::All | Mode::Full)
+    }
+
+    fn match_full_text(self) -> bool {
+        matches!(self, Mode::Full)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+/// The fields of a manifest to match the pattern against, combined from each mode passed to `--mode`
+pub struct Fields {
+    names: bool,
+    binaries: bool,
+    descriptions: bool,
+    homepages: bool,
+    notes: bool,
+    shortcuts: bool,
+    env: bool,
+    full_text: bool,
+}
+
+impl Fields {
+    /// Match any of the fields matched by the given modes
+    pub fn new(modes: &[Mode]) -> Self {
+        let any = |matches: fn(Mode) -> bool| modes.iter().any(|mode| matches(*mode));
+
+        Self {
+            names: any(Mode::match_names),
+            binaries: any(Mode::match_binaries),
+            descriptions: any(Mode::match_descriptions),
+            homepages: any(Mode::match_homepages),
+            notes: any(Mode::match_notes),
+            shortcuts: any(Mode::match_shortcuts),
+            env: any(Mode::match_env),
+            full_text: any(Mode::match_full_text),
+        }
+    }
+
+    fn match_names(self) -> bool {
+        self.names
+    }
+
+    fn match_binaries(self) -> bool {
+        self.binaries
+    }
+
+    fn match_descriptions(self) -> bool {
+        self.descriptions
+    }
+
+    fn match_homepages(self) -> bool {
+        self.homepages
+    }
+
+    fn match_notes(self) -> bool {
+        self.notes
+    }
+
+    fn match_shortcuts(self) -> bool {
+        self.shortcuts
+    }
+
+    fn match_env(self) -> bool {
+        self.env
+    }
+
+    fn match_full_text(self) -> bool {
+        self.full_text
+    }
+
+    /// Check if an index entry may match the pattern
+    fn matches_entry(self, pattern: &Regex, name: &str, entry: &Entry) -> bool {
+        // The full text of each manifest isn't indexed, so every manifest has to be checked
+        if self.match_full_text() {
+            return true;
+        }
+
+        let matches_field = |field: &Option<String>| {
+            field
+                .as_deref()
+                .is_some_and(|field| pattern.is_match(field))
+        };
+        let matches_list = |list: &[String]| list.iter().any(|item| pattern.is_match(item));
+
+        (self.match_names() && pattern.is_match(name))
+            || (self.match_binaries() && entry.bins.iter().any(|bin| pattern.is_match(bin)))
+            || (self.match_descriptions() && matches_field(&entry.description))
+            || (self.match_homepages() && matches_field(&entry.homepage))
+            || (self.match_notes() && matches_field(&entry.notes))
+            || (self.match_shortcuts() && matches_list(&entry.shortcuts))
+            || (self.match_env() && matches_list(&entry.env_add_path))
+    }
+
+    /// Check if an index entry's name or binaries are a close match for the pattern
+    fn fuzzy_matches_entry(self, pattern: &str, name: &str, entry: &Entry) -> bool {
+        (self.match_names() && fuzzy::score(pattern, name).is_some())
+            || (self.match_binaries()
+                && entry
+                    .bins
+                    .iter()
+                    .any(|bin| fuzzy::score(pattern, bin).is_some()))
+    }
+}
+
 #[derive(Debug, Clone)]
 #[must_use = "MatchCriteria has no side effects"]
 /// The criteria for a match
 pub struct MatchCriteria {
     name: bool,
     bins: Vec<String>,
+    description: bool,
+    homepage: bool,
+    notes: Option<String>,
+    shortcuts: Vec<String>,
+    env_add_path: Vec<String>,
 }
 
 impl MatchCriteria {
@@ -33,29 +215,96 @@ impl MatchCriteria {
         Self {
             name: false,
             bins: vec![],
+            description: false,
+            homepage: false,
+            notes: None,
+            shortcuts: vec![],
+            env_add_path: vec![],
         }
     }
 
-    /// Check if the name matches
+    /// Check which of the manifest's fields match
     pub fn matches(
         file_name: &str,
         pattern: &Regex,
         list_binaries: impl FnOnce() -> Vec<String>,
-        mode: SearchMode,
+        (description, homepage): (Option<&str>, Option<&str>),
+        load_config: impl FnOnce() -> Option<InstallConfig>,
+        fields: Fields,
     ) -> Self {
         let mut output = MatchCriteria::new();
 
-        if mode.match_names() {
+        if fields.match_names() {
             output.match_names(pattern, file_name);
         }
 
-        if mode.match_binaries() {
+        if fields.match_binaries() {
             output.match_binaries(pattern, list_binaries());
         }
 
+        if fields.match_descriptions() {
+            output.description = description.is_some_and(|text| pattern.is_match(text));
+        }
+
+        if fields.match_homepages() {
+            output.homepage = homepage.is_some_and(|text| pattern.is_match(text));
+        }
+
+        if fields.match_notes() || fields.match_shortcuts() || fields.match_env() {
+            if let Some(config) = load_config() {
+                output.match_config(pattern, config, fields);
+            }
+        }
+
         output
     }
 
+    /// Check if none of the fields matched
+    fn is_empty(&self) -> bool {
+        !self.name
+            && self.bins.is_empty()
+            && !self.description
+            && !self.homepage
+            && self.notes.is_none()
+            && self.shortcuts.is_empty()
+            && self.env_add_path.is_empty()
+    }
+
+    /// Match the fields that are only available once the manifest's install config is parsed
+    fn match_config(
+        &mut self,
+        pattern: &Regex,
+        config: InstallConfig,
+        fields: Fields,
+    ) -> &mut Self {
+        if fields.match_notes() {
+            self.notes = config
+                .notes
+                .to_script()
+                .filter(|notes| pattern.is_match(notes));
+        }
+
+        if fields.match_shortcuts() {
+            self.shortcuts = config
+                .shortcut_names()
+                .into_iter()
+                .filter(|name| pattern.is_match(name))
+                .map(String::from)
+                .collect();
+        }
+
+        if fields.match_env() {
+            self.env_add_path = config
+                .env_add_path
+                .into_vec()
+                .into_iter()
+                .filter(|path| pattern.is_match(path))
+                .collect();
+        }
+
+        self
+    }
+
     fn match_names(&mut self, pattern: &Regex, file_name: &str) -> &mut Self {
         if pattern.is_match(file_name) {
             self.name = true;
@@ -90,55 +339,162 @@ impl Default for MatchCriteria {
 struct MatchedManifest {
     manifest: Manifest,
     installed: bool,
+    scopes: Vec<Scope>,
     name_matched: bool,
     bins: Vec<String>,
+    description_matched: bool,
+    homepage_matched: bool,
+    notes: Option<String>,
+    shortcuts: Vec<String>,
+    env_add_path: Vec<String>,
+    full_text: Option<String>,
     exact_match: bool,
+    /// Whether the pattern matched at the start of the name
+    prefix_match: bool,
+    /// How closely the manifest matched a fuzzy search, where lower is closer
+    score: Option<usize>,
+    pattern: Regex,
+    /// Whether to show which text matched in each field
+    explain: bool,
+    /// The patterns that matched, when searching for more than one
+    patterns: Vec<String>,
 }
 
 impl MatchedManifest {
     pub fn new(
         ctx: &impl ScoopContext,
         manifest: Manifest,
-        pattern: &Regex,
-        mode: SearchMode,
+        (pattern, raw_pattern): (&Regex, &str),
+        fields: Fields,
         arch: Architecture,
+        scope_contexts: &[(Scope, AnyContext)],
     ) -> MatchedManifest {
         // TODO: Better display of output
-        let bucket = unsafe { manifest.bucket() };
-
         let match_output = MatchCriteria::matches(
             unsafe { manifest.name() },
             pattern,
             // Function to list binaries from a manifest
             // Passed as a closure to avoid this parsing if bin matching isn't required
-            || {
-                manifest
-                    .architecture
-                    .merge_default(manifest.install_config.clone(), arch)
-                    .bin
-                    .map(|b| b.to_vec())
-                    .unwrap_or_default()
-            },
-            mode,
+            || list_binaries(&manifest, arch),
+            (
+                manifest.description.as_deref(),
+                manifest.homepage.as_deref(),
+            ),
+            // The install config (for notes, shortcuts and PATH directories) is parsed lazily for the same reason as binaries
+            || InstallConfig::from_manifest(&manifest, arch).ok(),
+            fields,
         );
 
-        let installed = manifest.is_installed(ctx, Some(bucket));
-        let exact_match = unsafe { manifest.name() } == pattern.to_string();
+        // Show the first line of the manifest that matched, as the whole manifest would be far too long
+        let full_text = if fields.match_full_text() && match_output.is_empty() {
+            serde_json::to_string_pretty(&manifest)
+                .ok()
+                .and_then(|json| {
+                    json.lines()
+                        .find(|line| pattern.is_match(line))
+                        .map(|line| line.trim().to_string())
+                })
+        } else {
+            None
+        };
+
+        let (installed, scopes) = installed_scopes(ctx, &manifest, scope_contexts);
+        let name = unsafe { manifest.name() };
+        let exact_match = name.eq_ignore_ascii_case(raw_pattern);
+        let prefix_match = match_output.name && starts_with_match(pattern, name);
 
         MatchedManifest {
             manifest,
             installed,
+            scopes,
             name_matched: match_output.name,
             bins: match_output.bins,
+            description_matched: match_output.description,
+            homepage_matched: match_output.homepage,
+            notes: match_output.notes,
+            shortcuts: match_output.shortcuts,
+            env_add_path: match_output.env_add_path,
+            full_text,
             exact_match,
+            prefix_match,
+            score: None,
+            pattern: pattern.clone(),
+            explain: false,
+            patterns: vec![],
         }
     }
 
+    /// Match the manifest's name and binaries against the pattern, allowing for typos
+    ///
+    /// Returns `None` if neither are a close enough match
+    pub fn fuzzy(
+        ctx: &impl ScoopContext,
+        manifest: Manifest,
+        pattern: &str,
+        fields: Fields,
+        arch: Architecture,
+        scope_contexts: &[(Scope, AnyContext)],
+    ) -> Option<MatchedManifest> {
+        let name = unsafe { manifest.name() };
+        let name_score = fields
+            .match_names()
+            .then(|| fuzzy::score(pattern, name))
+            .flatten();
+        let exact_match = name.eq_ignore_ascii_case(pattern);
+        let prefix_match =
+            name_score.is_some() && name.to_lowercase().starts_with(&pattern.to_lowercase());
+
+        let bins = if fields.match_binaries() {
+            list_binaries(&manifest, arch)
+                .into_iter()
+                .filter_map(|bin| fuzzy::score(pattern, &bin).map(|score| (bin, score)))
+                .collect_vec()
+        } else {
+            vec![]
+        };
+
+        let score = name_score
+            .into_iter()
+            .chain(bins.iter().map(|(_, score)| *score))
+            .min()?;
+
+        let (installed, scopes) = installed_scopes(ctx, &manifest, scope_contexts);
+
+        Some(MatchedManifest {
+            manifest,
+            installed,
+            scopes,
+            name_matched: name_score.is_some(),
+            bins: bins.into_iter().map(|(bin, _)| bin).collect(),
+            description_matched: false,
+            homepage_matched: false,
+            notes: None,
+            shortcuts: vec![],
+            env_add_path: vec![],
+            full_text: None,
+            exact_match,
+            prefix_match,
+            score: Some(score),
+            // Only used for highlighting, so an escaped pattern is fine
+            pattern: Regex::new(&format!("(?i){}", regex::escape(pattern))).ok()?,
+            explain: false,
+            patterns: vec![],
+        })
+    }
+
     pub fn should_match(&self, installed_only: bool) -> bool {
         if !self.installed && installed_only {
             return false;
         }
-        if !self.name_matched && self.bins.is_empty() {
+        if !self.name_matched
+            && self.bins.is_empty()
+            && !self.description_matched
+            && !self.homepage_matched
+            && self.notes.is_none()
+            && self.shortcuts.is_empty()
+            && self.env_add_path.is_empty()
+            && self.full_text.is_none()
+        {
             return false;
         }
 
@@ -154,64 +510,448 @@ impl MatchedManifest {
             unsafe { self.manifest.name() }.to_string()
         };
 
-        let installed_text = if self.installed { "[installed] " } else { "" };
+        let patterns_text = if self.patterns.is_empty() {
+            String::new()
+        } else {
+            format!("[matched: {}] ", self.patterns.join(", "))
+        };
+
+        let installed_text = if !self.scopes.is_empty() {
+            format!("[installed: {}] ", self.scopes.iter().join(", "))
+        } else if self.installed {
+            "[installed] ".to_string()
+        } else {
+            String::new()
+        };
 
         let title = format!(
-            "{styled_package_name} ({}) {installed_text}",
+            "{styled_package_name} ({}) {installed_text}{patterns_text}",
             self.manifest.version
         );
 
-        if self.bins.is_empty() {
+        let labelled = |label: &str, text: &str| {
+            Text::new(format!(
+                "{}{} {}",
+                crate::output::WHITESPACE,
+                console::style(format!("{label}:")).dim(),
+                highlight(&self.pattern, text)
+            ))
+        };
+
+        let mut lines = vec![];
+
+        if self.explain && self.name_matched {
+            lines.push(labelled("name", unsafe { self.manifest.name() }));
+        }
+
+        lines.extend(self.bins.iter().map(|output| {
+            if self.explain {
+                labelled("bin", output)
+            } else {
+                Text::new(format!(
+                    "{}{}",
+                    crate::output::WHITESPACE,
+                    console::style(output).bold()
+                ))
+            }
+        }));
+
+        let matched_fields = [
+            (
+                "description",
+                self.description_matched,
+                &self.manifest.description,
+            ),
+            ("homepage", self.homepage_matched, &self.manifest.homepage),
+        ];
+
+        // Only show the lines of the notes that matched, as they can be quite long
+        let matched_notes = self
+            .notes
+            .iter()
+            .flat_map(|notes| notes.lines())
+            .filter(|line| self.pattern.is_match(line))
+            .map(|line| ("notes", line));
+
+        for (label, field) in matched_fields
+            .into_iter()
+            .filter_map(|(label, matched, field)| {
+                field
+                    .as_deref()
+                    .filter(|_| matched)
+                    .map(|field| (label, field))
+            })
+            .chain(matched_notes)
+            .chain(
+                self.shortcuts
+                    .iter()
+                    .map(|name| ("shortcut", name.as_str())),
+            )
+            .chain(self.env_add_path.iter().map(|path| ("path", path.as_str())))
+            .chain(self.full_text.as_deref().map(|line| ("manifest", line)))
+        {
+            lines.push(labelled(label, field));
+        }
+
+        if lines.is_empty() {
             Section::new(Children::None)
         } else {
-            let bins = self
-                .bins
+            Section::new(Children::from(lines))
+        }
+        .with_title(title)
+    }
+
+    /// List the names of the fields that matched the pattern
+    fn matched_fields(&self) -> Vec<String> {
+        [
+            ("name", self.name_matched),
+            ("bin", !self.bins.is_empty()),
+            ("description", self.description_matched),
+            ("homepage", self.homepage_matched),
+            ("notes", self.notes.is_some()),
+            ("shortcuts", !self.shortcuts.is_empty()),
+            ("env_add_path", !self.env_add_path.is_empty()),
+            ("manifest", self.full_text.is_some()),
+        ]
+        .into_iter()
+        .filter(|(_, matched)| *matched)
+        .map(|(field, _)| field.to_string())
+        .collect()
+    }
+
+    /// Find the text the pattern matched within each of the matched fields
+    fn explanation(&self) -> BTreeMap<String, Vec<String>> {
+        let mut explanation: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+        for (field, text) in self.matched_texts() {
+            explanation.entry(field.to_string()).or_default().extend(
+                self.pattern
+                    .find_iter(text)
+                    .map(|found| found.as_str().to_string()),
+            );
+        }
+
+        // Fuzzy matches may not contain the pattern itself
+        explanation.retain(|_, found| !found.is_empty());
+
+        explanation
+    }
+
+    /// How relevant the match is to the pattern
+    fn relevance(&self) -> Relevance {
+        Relevance {
+            exact_match: self.exact_match,
+            score: self.score,
+            prefix_match: self.prefix_match,
+            name_matched: self.name_matched,
+        }
+    }
+
+    /// Record which of the patterns matched, when searching for more than one
+    fn with_patterns(mut self, patterns: &[(String, Regex)]) -> Self {
+        let matched_patterns = {
+            let texts = self.matched_texts();
+
+            patterns
                 .iter()
-                .map(|output| {
-                    Text::new(format!(
-                        "{}{}",
-                        crate::output::WHITESPACE,
-                        console::style(output).bold()
-                    ))
-                })
-                .collect_vec();
+                .filter(|(_, pattern)| texts.iter().any(|(_, text)| pattern.is_match(text)))
+                .map(|(raw_pattern, _)| raw_pattern.clone())
+                .collect_vec()
+        };
+
+        let name = unsafe { self.manifest.name() };
+        self.exact_match |= patterns
+            .iter()
+            .any(|(raw_pattern, _)| name.eq_ignore_ascii_case(raw_pattern));
+        self.patterns = matched_patterns;
+
+        self
+    }
 
-            Section::new(Children::from(bins))
+    /// List the text of each of the matched fields, labelled with the field's name
+    fn matched_texts(&self) -> Vec<(&'static str, &str)> {
+        let mut fields = vec![];
+
+        if self.name_matched {
+            fields.push(("name", unsafe { self.manifest.name() }));
         }
-        .with_title(title)
+        fields.extend(self.bins.iter().map(|bin| ("bin", bin.as_str())));
+        if self.description_matched {
+            fields.extend(
+                self.manifest
+                    .description
+                    .as_deref()
+                    .map(|description| ("description", description)),
+            );
+        }
+        if self.homepage_matched {
+            fields.extend(
+                self.manifest
+                    .homepage
+                    .as_deref()
+                    .map(|homepage| ("homepage", homepage)),
+            );
+        }
+        fields.extend(self.notes.as_deref().map(|notes| ("notes", notes)));
+        fields.extend(
+            self.shortcuts
+                .iter()
+                .map(|name| ("shortcuts", name.as_str())),
+        );
+        fields.extend(
+            self.env_add_path
+                .iter()
+                .map(|path| ("env_add_path", path.as_str())),
+        );
+        fields.extend(self.full_text.as_deref().map(|line| ("manifest", line)));
+
+        fields
     }
 
     pub fn into_output(self) -> MatchedOutput {
+        let matched = self.matched_fields();
+        let explanation = self.explain.then(|| self.explanation());
+
         MatchedOutput {
             name: unsafe { self.manifest.name() }.to_string(),
             bucket: unsafe { self.manifest.bucket() }.to_string(),
             version: self.manifest.version.clone(),
             installed: self.installed,
+            scopes: self.scopes,
             bins: self.bins,
+            shortcuts: self.shortcuts,
+            env_add_path: self.env_add_path,
+            matched,
+            explanation,
+            patterns: self.patterns,
         }
     }
 }
 
+/// List the binaries of a manifest for the given architecture
+/// Split the deprecated `bucket/package` syntax into the bucket and the pattern
+///
+/// Only a single pattern is split, and not when `--bucket` is passed,
+/// or when homepages are searched, as a slash is then more likely to be part of a url
+fn deprecated_bucket(
+    patterns: &[String],
+    has_bucket: bool,
+    modes: &[Mode],
+) -> Option<(String, String)> {
+    if has_bucket || modes.iter().any(|mode| mode.match_homepages()) {
+        return None;
+    }
+
+    match patterns {
+        [pattern] => pattern
+            .split_once('/')
+            .map(|(bucket, pattern)| (bucket.to_string(), pattern.to_string())),
+        _ => None,
+    }
+}
+
+/// Combine the patterns into a single pattern that matches any of them
+fn combine_patterns(patterns: &[(String, Regex)]) -> Regex {
+    match patterns {
+        [(_, pattern)] => pattern.clone(),
+        patterns => Regex::new(
+            &patterns
+                .iter()
+                .map(|(_, pattern)| format!("(?:{pattern})"))
+                .join("|"),
+        )
+        .expect("combined patterns to be valid"),
+    }
+}
+
+/// Check if the pattern matches at the start of the name
+fn starts_with_match(pattern: &Regex, name: &str) -> bool {
+    pattern.find(name).is_some_and(|found| found.start() == 0)
+}
+
+/// Keep the items after the offset, up to the limit
+///
+/// Returns the kept items, and the number of items that were left out after them
+fn page<T>(items: Vec<T>, offset: usize, limit: Option<usize>) -> (Vec<T>, usize) {
+    let remaining = items.len().saturating_sub(offset);
+    let shown = limit.map_or(remaining, |limit| limit.min(remaining));
+
+    let kept = items.into_iter().skip(offset).take(shown).collect();
+
+    (kept, remaining - shown)
+}
+
+fn list_binaries(manifest: &Manifest, arch: Architecture) -> Vec<String> {
+    manifest
+        .architecture
+        .merge_default(manifest.install_config.clone(), arch)
+        .bin
+        .map(|b| b.to_vec())
+        .unwrap_or_default()
+}
+
+/// Check if a manifest is installed, and in which scopes
+///
+/// When searching a scope, the app counts as installed if it is installed in any of its contexts
+fn installed_scopes(
+    ctx: &impl ScoopContext,
+    manifest: &Manifest,
+    scope_contexts: &[(Scope, AnyContext)],
+) -> (bool, Vec<Scope>) {
+    let bucket = unsafe { manifest.bucket() };
+
+    let scopes = scope_contexts
+        .iter()
+        .filter(|(_, ctx)| manifest.is_installed(ctx, Some(bucket)))
+        .map(|(scope, _)| *scope)
+        .collect_vec();
+
+    let installed = if scope_contexts.is_empty() {
+        manifest.is_installed(ctx, Some(bucket))
+    } else {
+        !scopes.is_empty()
+    };
+
+    (installed, scopes)
+}
+
+/// Create the output section listing the matches in a bucket
+fn bucket_section(bucket: &str, matches: &[MatchedManifest]) -> Section<Section<Text<String>>> {
+    let mut sections = vec![];
+
+    matches
+        .par_iter()
+        .map(MatchedManifest::to_section)
+        .collect_into_vec(&mut sections);
+
+    Section::new(Children::from(sections)).with_title(format!("'{bucket}' bucket:"))
+}
+
+/// Print each match as a line of JSON, so other tools can consume matches as they are found
+fn print_ndjson(matches: impl IntoIterator<Item = MatchedManifest>) -> std::io::Result<()> {
+    // Lock stdout for all of the matches, so lines from different buckets are never interleaved
+    let mut stdout = std::io::stdout().lock();
+
+    for matched in matches {
+        serde_json::to_writer(&mut stdout, &matched.into_output())?;
+        writeln!(stdout)?;
+    }
+
+    Ok(())
+}
+
+/// Highlight each match of the pattern within the text
+fn highlight(pattern: &Regex, text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut last_end = 0;
+
+    for found in pattern.find_iter(text) {
+        output.push_str(&text[last_end..found.start()]);
+        output.push_str(&console::style(found.as_str()).yellow().bold().to_string());
+        last_end = found.end();
+    }
+
+    output.push_str(&text[last_end..]);
+
+    output
+}
+
 impl std::fmt::Display for MatchedManifest {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         std::fmt::Display::fmt(&self.to_section(), f)
     }
 }
 
-#[derive(Debug, serde::Serialize)]
-struct MatchedOutput {
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub(crate) struct MatchedOutput {
     name: String,
     bucket: String,
+    #[schemars(with = "String")]
     version: Version,
     installed: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    scopes: Vec<Scope>,
     bins: Vec<String>,
+    /// The matching start menu shortcut names
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    shortcuts: Vec<String>,
+    /// The matching directories added to `PATH`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    env_add_path: Vec<String>,
+    /// The fields of the manifest that matched the pattern
+    matched: Vec<String>,
+    /// The text the pattern matched within each field, with `--explain`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    explanation: Option<BTreeMap<String, Vec<String>>>,
+    /// The patterns that matched, when searching for more than one
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    patterns: Vec<String>,
+}
+
+#[derive(Debug, Default, Copy, Clone, ValueEnum)]
+/// The order to list matches within each bucket in
+pub enum SortBy {
+    #[default]
+    /// Exact matches first, then the closest fuzzy matches, then names starting with the pattern,
+    /// then other name matches, then any other matches
+    Relevance,
+    /// Alphabetically by name
+    Name,
+    /// By version, oldest first
+    Version,
+}
+
+impl SortBy {
+    fn compare(self, a: &MatchedManifest, b: &MatchedManifest) -> Ordering {
+        let by_name = || unsafe { a.manifest.name().cmp(b.manifest.name()) };
+
+        match self {
+            SortBy::Relevance => a.relevance().cmp(&b.relevance()).then_with(by_name),
+            SortBy::Name => by_name(),
+            SortBy::Version => version_order::compare(
+                &a.manifest.version.to_string(),
+                &b.manifest.version.to_string(),
+            )
+            .then_with(by_name),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// The parts of a match that decide how relevant it is, where more relevant matches sort first
+struct Relevance {
+    exact_match: bool,
+    score: Option<usize>,
+    prefix_match: bool,
+    name_matched: bool,
+}
+
+impl Ord for Relevance {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .exact_match
+            .cmp(&self.exact_match)
+            .then_with(|| self.score.cmp(&other.score))
+            .then_with(|| other.prefix_match.cmp(&self.prefix_match))
+            .then_with(|| other.name_matched.cmp(&self.name_matched))
+    }
+}
+
+impl PartialOrd for Relevance {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 #[derive(Debug, Clone, Parser)]
 /// Search for a package
 pub struct Args {
-    #[clap(help = "The regex pattern to search for, using Rust Regex syntax")]
-    pattern: String,
+    #[clap(
+        help = "The regex patterns to search for, using Rust Regex syntax, or the approximate name to search for with --fuzzy. Apps matching any of the patterns are shown, annotated with the patterns they matched",
+        required = true,
+        num_args = 1..
+    )]
+    pattern: Vec<String>,
 
     #[clap(
         short,
@@ -220,86 +960,283 @@ pub struct Args {
     )]
     case_sensitive: bool,
 
-    #[clap(short, long, help = "The bucket to exclusively search in")]
+    #[clap(
+        short,
+        long,
+        help = "The bucket to exclusively search in",
+        add = ArgValueCandidates::new(completions::buckets)
+    )]
     bucket: Option<String>,
 
     #[clap(short, long, help = "Only search installed packages")]
     installed: bool,
 
-    #[clap(short, long, help = "Search mode to use", default_value_t)]
-    mode: SearchMode,
+    #[clap(
+        long,
+        help = "Search the names of apps in known buckets that haven't been added, to find out which bucket to add. Each bucket is downloaded the first time it is searched",
+        conflicts_with_all = ["installed", "fuzzy", "bucket", "mode"]
+    )]
+    remote: bool,
+
+    #[clap(
+        long,
+        help = "List the versions each matching app has been available at, and when each was added, by walking its bucket's git history. Useful for finding a version to downgrade to",
+        conflicts_with_all = ["remote", "stream", "ndjson", "flat", "table", "shovel"]
+    )]
+    versions: bool,
+
+    #[cfg(feature = "contexts")]
+    #[clap(
+        long,
+        help = "Check if apps are installed in the user install, the global install, or both. Installed apps are annotated with their install"
+    )]
+    scope: Option<Scope>,
+
+    #[clap(
+        short,
+        long,
+        help = "The fields of each manifest to search. Pass several, separated by commas, to match any of them",
+        visible_alias = "fields",
+        value_enum,
+        value_delimiter = ',',
+        default_value = "name"
+    )]
+    mode: Vec<Mode>,
+
+    #[clap(
+        long,
+        help = "The order to list matches in within each bucket",
+        value_enum,
+        default_value_t
+    )]
+    sort_by: SortBy,
+
+    #[clap(
+        long,
+        help = "List matches from every bucket in one list, ordered by --sort-by, rather than grouping them by bucket"
+    )]
+    flat: bool,
+
+    #[clap(
+        long,
+        help = "Print matches from every bucket in a table, ordered by --sort-by, with columns for each app's version, bucket and install status"
+    )]
+    table: bool,
+
+    #[clap(flatten)]
+    table_options: structured::Options,
+
+    #[clap(
+        long,
+        help = "Print matches in the same layout as Shovel's `search`, with Name, Version, Source and Binaries columns, for scripts that parse it",
+        conflicts_with = "table"
+    )]
+    shovel: bool,
+
+    #[clap(
+        long,
+        help = "Match app names and binaries approximately, allowing for typos, and show only the closest matches"
+    )]
+    fuzzy: bool,
+
+    #[clap(
+        long,
+        help = "Treat the pattern as a glob, where `*` matches any characters and `?` matches one character, rather than a regex",
+        conflicts_with = "fuzzy"
+    )]
+    glob: bool,
+
+    #[clap(
+        short = 'x',
+        long,
+        help = "Leave out apps whose names match the given pattern, e.g. `-nightly$`. Can be passed multiple times"
+    )]
+    exclude: Vec<String>,
+
+    #[clap(
+        long,
+        help = "The maximum number of matches to show, after sorting. Defaults to 10 with --fuzzy"
+    )]
+    limit: Option<usize>,
+
+    #[clap(
+        long,
+        help = "The number of matches to skip, after sorting",
+        default_value_t = 0
+    )]
+    offset: usize,
+
+    #[clap(
+        long,
+        help = "Print each bucket's matches as soon as they are found, rather than waiting to list buckets in order"
+    )]
+    stream: bool,
+
+    #[clap(
+        long,
+        help = "Print each match as a line of JSON as soon as its bucket has been searched, for piping into tools like jq or fzf",
+        conflicts_with_all = ["table", "shovel"]
+    )]
+    ndjson: bool,
+
+    #[clap(
+        long,
+        help = "Show which field each match came from, with the matched text highlighted. Structured output includes the matched text for each field"
+    )]
+    explain: bool,
 
     #[clap(from_global)]
     arch: Architecture,
 
     #[clap(from_global)]
-    json: bool,
+    format: Format,
 }
 
 impl super::Command for Args {
     async fn runner(self, ctx: &impl ScoopContext) -> Result<(), anyhow::Error> {
-        let (bucket, raw_pattern) =
-            if let Some((bucket, raw_pattern)) = self.pattern.split_once('/') {
-                warn!("bucket/package syntax is deprecated. Please use the --bucket flag instead");
-                (
-                    Some({
-                        // Bucket flag overrides bucket/package syntax
-                        if let Some(bucket) = self.bucket {
-                            warn!("Using bucket flag instead of bucket/package syntax");
-                            bucket
-                        } else {
-                            bucket.to_string()
-                        }
-                    }),
-                    raw_pattern.to_string(),
-                )
-            } else {
-                (self.bucket, self.pattern)
-            };
+        let mut raw_patterns = self.pattern.clone();
 
-        let pattern = {
-            Regex::new(&format!(
-                "{}{raw_pattern}",
-                if self.case_sensitive { "" } else { "(?i)" },
-            ))
-            .calm_expect(
-                "Invalid Regex provided. See https://docs.rs/regex/latest/regex/ for more info",
-            )
+        let bucket = if let Some((bucket, raw_pattern)) =
+            deprecated_bucket(&raw_patterns, self.bucket.is_some(), &self.mode)
+        {
+            warn!("bucket/package syntax is deprecated. Please use the --bucket flag instead");
+            raw_patterns = vec![raw_pattern];
+            Some(bucket)
+        } else {
+            self.bucket.clone()
         };
 
+        if self.fuzzy
+            && !self
+                .mode
+                .iter()
+                .all(|mode| matches!(mode, Mode::Name | Mode::Binary | Mode::Both))
+        {
+            abandon!("Fuzzy search only supports the name, binary and both modes");
+        }
+
+        if self.fuzzy && raw_patterns.len() > 1 {
+            abandon!("Fuzzy search only supports a single pattern");
+        }
+
+        let individual_patterns = raw_patterns
+            .iter()
+            .map(|raw_pattern| {
+                let pattern = if self.fuzzy {
+                    // Fuzzy patterns are plain text, and only used for the index fallback
+                    self.compile_pattern(&regex::escape(raw_pattern))
+                } else {
+                    self.compile_pattern(raw_pattern)
+                };
+
+                (raw_pattern.clone(), pattern)
+            })
+            .collect_vec();
+
+        let pattern = combine_patterns(&individual_patterns);
+
+        // Fuzzy search only takes a single pattern, and extra patterns are checked for exact matches later
+        let raw_pattern = raw_patterns.swap_remove(0);
+
+        let excludes = self
+            .exclude
+            .iter()
+            .map(|exclude| self.compile_pattern(exclude))
+            .collect::<Vec<_>>();
+
+        if self.remote {
+            return self.search_known_buckets(ctx, &pattern, &excludes);
+        }
+
         let matching_buckets: Vec<Bucket> = match bucket.map(|name| Bucket::from_name(ctx, name)) {
             Some(Ok(bucket)) => vec![bucket],
             _ => Bucket::list_all(ctx)?,
         };
 
-        let buckets: HashMap<String, Vec<MatchedManifest>> = matching_buckets
-            .par_iter()
-            .filter_map(
-                |bucket| match bucket.matches(ctx, self.installed, &pattern, self.mode) {
-                    Ok(manifests) => {
-                        let matches = manifests
-                            .into_par_iter()
-                            .map(|manifest| {
-                                MatchedManifest::new(ctx, manifest, &pattern, self.mode, self.arch)
-                            })
-                            .filter(|matched_manifest| {
-                                matched_manifest.should_match(self.installed)
-                            })
-                            .collect::<Vec<_>>();
-
-                        if matches.is_empty() {
-                            None
+        let scope_contexts = self.scope_contexts()?;
+        let patterns = (&pattern, raw_pattern.as_str());
+
+        let match_bucket = |bucket: &Bucket| match self.find_manifests(ctx, bucket, patterns) {
+            Ok(manifests) => {
+                let mut matches = manifests
+                    .into_par_iter()
+                    .filter(|manifest| {
+                        let name = unsafe { manifest.name() };
+                        !excludes.iter().any(|exclude| exclude.is_match(name))
+                    })
+                    .filter_map(|manifest| {
+                        self.match_manifest(ctx, manifest, patterns, &scope_contexts)
+                    })
+                    .map(|matched_manifest| {
+                        if individual_patterns.len() > 1 {
+                            matched_manifest.with_patterns(&individual_patterns)
                         } else {
-                            Some((bucket.name().to_string(), matches))
+                            matched_manifest
                         }
+                    })
+                    .filter(|matched_manifest| matched_manifest.should_match(self.installed))
+                    .collect::<Vec<_>>();
+
+                matches.par_sort_by(|a, b| self.sort_by.compare(a, b));
+
+                if matches.is_empty() {
+                    None
+                } else {
+                    Some((bucket.name().to_string(), matches))
+                }
+            }
+            _ => None,
+        };
+
+        if self.versions {
+            let buckets: BTreeMap<String, Vec<MatchedManifest>> = matching_buckets
+                .par_iter()
+                .filter_map(match_bucket)
+                .collect();
+
+            return self.search_versions(ctx, buckets);
+        }
+
+        if (self.stream || self.ndjson) && !self.needs_all_matches() {
+            let found = AtomicBool::new(false);
+
+            matching_buckets
+                .par_iter()
+                .filter_map(match_bucket)
+                .for_each(|(bucket, matches)| {
+                    found.store(true, atomic::Ordering::Relaxed);
+
+                    if self.ndjson {
+                        if let Err(e) = print_ndjson(matches) {
+                            debug!("Failed to print matches from {bucket}: {e}");
+                        }
+                    } else {
+                        // Each section is printed in a single call, so sections from different buckets are never interleaved
+                        println!("{}", bucket_section(&bucket, &matches));
                     }
-                    _ => None,
-                },
-            )
+                });
+
+            if !found.load(atomic::Ordering::Relaxed) && !self.ndjson {
+                println!("No results found");
+            }
+
+            return Ok(());
+        }
+
+        // Collect into a BTreeMap so that buckets are always listed in the same order
+        let buckets: BTreeMap<String, Vec<MatchedManifest>> = matching_buckets
+            .par_iter()
+            .filter_map(match_bucket)
             .collect();
 
-        if self.json {
-            let json_matches: HashMap<String, Vec<MatchedOutput>> = buckets
+        let (buckets, truncated) = self.paginate(buckets);
+
+        if self.ndjson {
+            print_ndjson(buckets.into_values().flatten())?;
+        } else if self.flat || self.table || self.shovel {
+            self.print_flat(buckets)?;
+        } else if self.format.is_structured() {
+            let json_matches: BTreeMap<String, Vec<MatchedOutput>> = buckets
                 .into_iter()
                 .map(|(bucket, matches)| {
                     let bucket_matches: Vec<MatchedOutput> = matches
@@ -311,27 +1248,463 @@ impl super::Command for Args {
                 })
                 .collect();
 
-            serde_json::to_writer_pretty(std::io::stdout(), &json_matches)?;
+            self.format.print(&json_matches)?;
         } else {
             let mut matches: Sections<_> = buckets
+                .iter()
+                .map(|(bucket, matches)| bucket_section(bucket, matches))
+                .collect();
+
+            matches.par_sort();
+
+            print!("{matches}");
+        }
+
+        if truncated > 0 && !self.format.is_structured() && !self.ndjson {
+            println!(
+                "\n{truncated} more matches not shown. Use --offset {} to see them",
+                self.offset + self.limit().unwrap_or_default()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Args {
+    /// Compile a pattern from the command line, respecting `--case-sensitive` and `--glob`
+    fn compile_pattern(&self, pattern: &str) -> Regex {
+        let pattern = if self.glob {
+            pattern::glob_to_regex(pattern)
+        } else {
+            pattern.to_string()
+        };
+
+        Regex::new(&format!(
+            "{}{pattern}",
+            if self.case_sensitive { "" } else { "(?i)" }
+        ))
+        .calm_expect(
+            "Invalid Regex provided. See https://docs.rs/regex/latest/regex/ for more info",
+        )
+    }
+
+    /// Find the manifests in a bucket that may match the pattern
+    ///
+    /// Uses the bucket's search index where possible, falling back to parsing every manifest
+    fn find_manifests(
+        &self,
+        ctx: &impl ScoopContext,
+        bucket: &Bucket,
+        (pattern, raw_pattern): (&Regex, &str),
+    ) -> anyhow::Result<Vec<Manifest>> {
+        match BucketIndex::open(ctx, bucket) {
+            Ok(index) => Ok(index
+                .candidates(|name, entry| {
+                    if self.fuzzy {
+                        self.fields().fuzzy_matches_entry(raw_pattern, name, entry)
+                    } else {
+                        self.fields().matches_entry(pattern, name, entry)
+                    }
+                })
+                .into_par_iter()
+                .filter_map(|name| bucket.get_manifest(name).ok())
+                .collect()),
+            Err(e) => {
+                debug!("Failed to open search index for {}: {e}", bucket.name());
+
+                // Sprinkles can only match names and binaries itself,
+                // so for any other fields list all manifests and leave matching to the caller
+                let (pattern, mode) = if self.mode == [Mode::Name] && !self.fuzzy {
+                    (pattern.clone(), SearchMode::Name)
+                } else {
+                    (Regex::new("")?, SearchMode::Name)
+                };
+
+                // Installed apps in other scopes are filtered by the caller
+                let installed = self.installed && self.current_context_only();
+
+                Ok(bucket.matches(ctx, installed, &pattern, mode)?)
+            }
+        }
+    }
+
+    /// Match a manifest against the pattern, or the raw pattern if using fuzzy search
+    fn match_manifest(
+        &self,
+        ctx: &impl ScoopContext,
+        manifest: Manifest,
+        (pattern, raw_pattern): (&Regex, &str),
+        scope_contexts: &[(Scope, AnyContext)],
+    ) -> Option<MatchedManifest> {
+        let matched = if self.fuzzy {
+            MatchedManifest::fuzzy(
+                ctx,
+                manifest,
+                raw_pattern,
+                self.fields(),
+                self.arch,
+                scope_contexts,
+            )
+        } else {
+            Some(MatchedManifest::new(
+                ctx,
+                manifest,
+                (pattern, raw_pattern),
+                self.fields(),
+                self.arch,
+                scope_contexts,
+            ))
+        };
+
+        matched.map(|matched| MatchedManifest {
+            explain: self.explain,
+            ..matched
+        })
+    }
+
+    /// Search the known buckets that haven't been added, and print which bucket to add for each match
+    fn search_known_buckets(
+        &self,
+        ctx: &impl ScoopContext,
+        pattern: &Regex,
+        excludes: &[Regex],
+    ) -> anyhow::Result<()> {
+        let buckets = known_buckets::not_added(ctx);
+        known_buckets::fetch(ctx, &buckets);
+
+        let matches = known_buckets::search(ctx, &buckets, pattern)
+            .into_iter()
+            .filter(|matched| {
+                !excludes
+                    .iter()
+                    .any(|exclude| exclude.is_match(&matched.name))
+            })
+            .collect_vec();
+
+        if self.format.is_structured() {
+            self.format.print(&matches)?;
+            return Ok(());
+        }
+
+        let sections: Sections<_> = matches
+            .into_iter()
+            .chunk_by(|matched| matched.bucket.clone())
+            .into_iter()
+            .map(|(bucket, matches)| {
+                let apps = matches
+                    .map(|matched| match matched.version {
+                        Some(version) => Text::new(format!("{} ({version})", matched.name)),
+                        None => Text::new(matched.name),
+                    })
+                    .collect_vec();
+
+                Section::new(Children::from(apps)).with_title(format!(
+                    "'{bucket}' bucket (add it with `sfsu bucket add {bucket}`):"
+                ))
+            })
+            .collect();
+
+        print!("{sections}");
+
+        Ok(())
+    }
+
+    /// Print the versions each match has been available at, from its bucket's git history
+    fn search_versions(
+        &self,
+        ctx: &impl ScoopContext,
+        buckets: BTreeMap<String, Vec<MatchedManifest>>,
+    ) -> anyhow::Result<()> {
+        let histories = buckets
+            .into_iter()
+            .flat_map(|(bucket, matches)| {
+                matches.into_iter().map(move |matched| {
+                    (
+                        bucket.clone(),
+                        unsafe { matched.manifest.name() }.to_string(),
+                    )
+                })
+            })
+            .collect_vec()
+            .into_par_iter()
+            .filter_map(
+                |(bucket, name)| match history::versions(ctx, &bucket, &name) {
+                    Ok(versions) => Some(history::AppHistory {
+                        name,
+                        bucket,
+                        versions,
+                    }),
+                    Err(e) => {
+                        warn!("Failed to read the history of {bucket}/{name}: {e}");
+                        None
+                    }
+                },
+            )
+            .collect::<Vec<_>>();
+
+        if self.format.is_structured() {
+            self.format.print(&histories)?;
+            return Ok(());
+        }
+
+        if histories.is_empty() {
+            println!("No results found");
+            return Ok(());
+        }
+
+        let sections: Sections<_> = histories
+            .into_iter()
+            .map(|history| {
+                let versions = history
+                    .versions
+                    .into_iter()
+                    .map(|version| {
+                        Text::new(format!("{} (added {})", version.version, version.added))
+                    })
+                    .collect_vec();
+
+                Section::new(Children::from(versions))
+                    .with_title(format!("{} ({}):", history.name, history.bucket))
+            })
+            .collect();
+
+        print!("{sections}");
+
+        Ok(())
+    }
+
+    /// Print the matches from every bucket in one list, ordered by `--sort-by`, or as a table with `--table` or `--shovel`
+    fn print_flat(&self, buckets: BTreeMap<String, Vec<MatchedManifest>>) -> anyhow::Result<()> {
+        let mut matches = buckets.into_values().flatten().collect_vec();
+        matches.par_sort_by(|a, b| self.sort_by.compare(a, b));
+
+        if self.format.is_structured() {
+            let outputs = matches
                 .into_iter()
-                .map(|(bucket, matches)| {
-                    let mut sections = vec![];
+                .map(MatchedManifest::into_output)
+                .collect_vec();
 
-                    matches
-                        .par_iter()
-                        .map(MatchedManifest::to_section)
-                        .collect_into_vec(&mut sections);
+            self.format.print(&outputs)?;
+        } else if self.table {
+            if matches.is_empty() {
+                println!("No results found");
+                return Ok(());
+            }
 
-                    Section::new(Children::from(sections)).with_title(format!("'{bucket}' bucket:"))
+            let values = matches
+                .into_iter()
+                .map(|matched| serde_json::to_value(matched.into_output()))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let outputs = Structured::new(&values)
+                .with_priority("version", Priority::High)
+                .with_priority("bucket", Priority::High)
+                .with_priority("scopes", Priority::High)
+                .with_priority("bins", Priority::Low)
+                .with_priority("matched", Priority::Low)
+                .with_options(&self.table_options);
+
+            print!("{outputs}");
+        } else if self.shovel {
+            if matches.is_empty() {
+                println!("No results found");
+                return Ok(());
+            }
+
+            let mut table = powershell::Table::new(&["Name", "Version", "Source", "Binaries"]);
+
+            for matched in &matches {
+                table.push(vec![
+                    unsafe { matched.manifest.name() }.to_string(),
+                    matched.manifest.version.to_string(),
+                    unsafe { matched.manifest.bucket() }.to_string(),
+                    matched.bins.join(" | "),
+                ]);
+            }
+
+            print!("{table}");
+        } else {
+            let sections: Sections<_> = matches
+                .iter()
+                .map(|matched| {
+                    let mut section = matched.to_section();
+                    // Without the bucket headings, each match is labelled with its bucket instead
+                    section.title = section
+                        .title
+                        .map(|title| format!("{}/{title}", unsafe { matched.manifest.bucket() }));
+                    section
                 })
                 .collect();
 
-            matches.par_sort();
-
-            print!("{matches}");
+            print!("{sections}");
         }
 
         Ok(())
     }
+
+    /// The maximum number of matches to show, if any
+    fn limit(&self) -> Option<usize> {
+        self.limit.or(self.fuzzy.then_some(10))
+    }
+
+    /// Check if every match must be found before anything can be printed
+    fn needs_all_matches(&self) -> bool {
+        self.fuzzy
+            || self.flat
+            || self.table
+            || self.shovel
+            || self.format.is_structured()
+            || self.limit.is_some()
+            || self.offset > 0
+    }
+
+    /// Keep only the matches within `--offset` and `--limit` across all buckets
+    ///
+    /// Fuzzy searches keep the closest matches, otherwise matches are kept in the order they are listed.
+    /// Returns the kept matches, and the number of matches that were left out after them
+    fn paginate(
+        &self,
+        buckets: BTreeMap<String, Vec<MatchedManifest>>,
+    ) -> (BTreeMap<String, Vec<MatchedManifest>>, usize) {
+        let limit = self.limit();
+
+        if limit.is_none() && self.offset == 0 {
+            return (buckets, 0);
+        }
+
+        let mut matches = buckets
+            .into_iter()
+            .flat_map(|(bucket, matches)| {
+                matches
+                    .into_iter()
+                    .map(move |matched| (bucket.clone(), matched))
+            })
+            .collect_vec();
+
+        if self.fuzzy {
+            matches.sort_by(|(_, a), (_, b)| SortBy::Relevance.compare(a, b));
+        } else if self.flat || self.table || self.shovel {
+            matches.sort_by(|(_, a), (_, b)| self.sort_by.compare(a, b));
+        } else {
+            matches.sort_by(|(a_bucket, a), (b_bucket, b)| {
+                a_bucket
+                    .cmp(b_bucket)
+                    .then_with(|| self.sort_by.compare(a, b))
+            });
+        }
+
+        let (matches, left_out) = page(matches, self.offset, limit);
+
+        let mut kept: BTreeMap<String, Vec<MatchedManifest>> = BTreeMap::new();
+
+        for (bucket, matched) in matches {
+            kept.entry(bucket).or_default().push(matched);
+        }
+
+        for matches in kept.values_mut() {
+            matches.sort_by(|a, b| self.sort_by.compare(a, b));
+        }
+
+        (kept, left_out)
+    }
+
+    /// Open the contexts to check for installed apps in, if a scope was selected
+    fn scope_contexts(&self) -> anyhow::Result<Vec<(Scope, AnyContext)>> {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "contexts")] {
+                self.scope.map_or_else(|| Ok(vec![]), Scope::contexts)
+            } else {
+                Ok(vec![])
+            }
+        }
+    }
+
+    /// The fields to match, combined from each mode passed to `--mode`
+    fn fields(&self) -> Fields {
+        Fields::new(&self.mode)
+    }
+
+    /// Check if installed apps are only checked in the current context
+    fn current_context_only(&self) -> bool {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "contexts")] {
+                self.scope.is_none()
+            } else {
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relevance_ranks_prefix_matches_first() {
+        let pattern = Regex::new("code").unwrap();
+        let relevance = |name: &str| Relevance {
+            exact_match: name == "code",
+            score: None,
+            prefix_match: starts_with_match(&pattern, name),
+            name_matched: pattern.is_match(name),
+        };
+
+        let mut names = ["vscode", "codeblocks", "code", "notepad"];
+        names.sort_by_key(|name| relevance(name));
+
+        assert_eq!(names, ["code", "codeblocks", "vscode", "notepad"]);
+    }
+
+    #[test]
+    fn test_page() {
+        let items = (1..=10).collect_vec();
+
+        assert_eq!(page(items.clone(), 0, None), (items.clone(), 0));
+        assert_eq!(page(items.clone(), 0, Some(3)), (vec![1, 2, 3], 7));
+        assert_eq!(page(items.clone(), 8, Some(3)), (vec![9, 10], 0));
+        assert_eq!(page(items.clone(), 4, Some(2)), (vec![5, 6], 4));
+        assert_eq!(page(items, 12, Some(2)), (vec![], 0));
+    }
+
+    #[test]
+    fn test_combine_patterns() {
+        let patterns = ["^sfsu$", "scoop"]
+            .into_iter()
+            .map(|pattern| (pattern.to_string(), Regex::new(pattern).unwrap()))
+            .collect_vec();
+
+        let combined = combine_patterns(&patterns);
+
+        assert!(combined.is_match("sfsu"));
+        assert!(combined.is_match("scoop-search"));
+        assert!(!combined.is_match("sfsu-beta"));
+
+        assert_eq!(combine_patterns(&patterns[..1]).as_str(), "^sfsu$");
+    }
+
+    #[test]
+    fn test_deprecated_bucket() {
+        let patterns = ["extras/sfsu".to_string()];
+
+        assert_eq!(
+            deprecated_bucket(&patterns, false, &[Mode::Name]),
+            Some(("extras".to_string(), "sfsu".to_string()))
+        );
+        assert_eq!(deprecated_bucket(&patterns, true, &[Mode::Name]), None);
+        assert_eq!(
+            deprecated_bucket(&patterns, false, &[Mode::Name, Mode::Homepage]),
+            None
+        );
+        assert_eq!(deprecated_bucket(&patterns, false, &[Mode::Full]), None);
+        assert_eq!(
+            deprecated_bucket(
+                &[patterns[0].clone(), "sfsu".to_string()],
+                false,
+                &[Mode::Name]
+            ),
+            None
+        );
+    }
 }
@@ -0,0 +1,59 @@
+pub mod add;
+pub mod alter;
+pub mod info;
+pub mod list;
+pub mod remove;
+pub mod repair;
+
+use clap::{Parser, Subcommand};
+
+use sprinkles::{config, contexts::ScoopContext};
+
+use super::{Command, CommandRunner, Runnable};
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum Commands {
+    #[clap(alias = "ls")]
+    List(list::Args),
+    Add(add::Args),
+    #[clap(alias = "rm")]
+    Remove(remove::Args),
+    Which(super::which::Args),
+    Info(info::Args),
+    Alter(alter::Args),
+    Repair(repair::Args),
+}
+
+impl Runnable for Commands {
+    async fn run(
+        self,
+        ctx: &impl sprinkles::contexts::ScoopContext<Config = sprinkles::config::Scoop>,
+    ) -> anyhow::Result<()> {
+        match self {
+            Commands::List(args) => args.run(ctx).await,
+            Commands::Add(args) => args.run(ctx).await,
+            Commands::Remove(args) => args.run(ctx).await,
+            Commands::Which(args) => args.run(ctx).await,
+            Commands::Info(args) => args.run(ctx).await,
+            Commands::Alter(args) => args.run(ctx).await,
+            Commands::Repair(args) => args.run(ctx).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Parser)]
+/// Commands for managing shims
+pub struct Args {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+impl Command for Args {
+    #[inline]
+    async fn runner(
+        self,
+        ctx: &impl ScoopContext<Config = config::Scoop>,
+    ) -> Result<(), anyhow::Error> {
+        self.command.run(ctx).await
+    }
+}
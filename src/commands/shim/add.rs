@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use sprinkles::contexts::ScoopContext;
+
+use crate::{
+    abandon,
+    install::shims::{self, Shim},
+    output::colours::eprintln_green,
+};
+
+#[derive(Debug, Clone, Parser)]
+/// Create a shim pointing at any executable
+pub struct Args {
+    #[clap(help = "The name of the shim")]
+    name: String,
+
+    #[clap(help = "The path to the executable the shim should run")]
+    path: PathBuf,
+
+    #[clap(
+        help = "Arguments to always pass to the executable",
+        trailing_var_arg = true,
+        allow_hyphen_values = true
+    )]
+    args: Vec<String>,
+
+    #[clap(short, long, help = "Replace the shim if it already exists")]
+    force: bool,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        if shims::exists(ctx, &self.name) {
+            if !self.force {
+                abandon!(
+                    "A shim named {} already exists. Use --force to replace it",
+                    self.name
+                );
+            }
+
+            shims::remove_any(ctx, &self.name)?;
+        }
+
+        let target = match std::path::absolute(&self.path) {
+            Ok(target) => target,
+            Err(e) => abandon!("Could not resolve {}: {e}", self.path.display()),
+        };
+
+        let shim = Shim {
+            name: self.name,
+            target,
+            args: (!self.args.is_empty()).then(|| shims::join_args(&self.args)),
+        };
+
+        shim.create(ctx)?;
+
+        eprintln_green!("Created shim {} for {}", shim.name, shim.target.display());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use itertools::Itertools;
+use sprinkles::{Architecture, contexts::ScoopContext, packages::models};
+
+use crate::{
+    abandon,
+    commands::completions,
+    install::{
+        self,
+        config::{Bin, InstallConfig},
+        shims::{self, Shim},
+    },
+    output::colours::eprintln_green,
+};
+
+#[derive(Debug, Clone, Parser)]
+/// Switch a shim to another installed app that provides the same binary, or repair a broken shim
+///
+/// If only one installed app provides the binary, the shim is recreated to point at it
+pub struct Args {
+    #[clap(help = "The name of the shim", add = ArgValueCandidates::new(completions::shims))]
+    name: String,
+
+    #[clap(
+        help = "The app the shim should point to. Required if multiple apps provide the binary",
+        add = ArgValueCandidates::new(completions::installed_apps)
+    )]
+    app: Option<String>,
+
+    #[clap(from_global)]
+    arch: Architecture,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let providers = providers(ctx, &self.name, self.arch)?;
+
+        let (app, bin) = match (&self.app, providers.as_slice()) {
+            (_, []) => abandon!("No installed app provides {}", self.name),
+            (Some(app), providers) => {
+                let Some(provider) = providers
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(app))
+                else {
+                    abandon!(
+                        "{app} does not provide {}. It is provided by: {}",
+                        self.name,
+                        providers.iter().map(|(name, _)| name).join(", ")
+                    );
+                };
+
+                provider.clone()
+            }
+            (None, [provider]) => provider.clone(),
+            (None, providers) => abandon!(
+                "{} is provided by multiple apps: {}. Pass the app to switch to",
+                self.name,
+                providers.iter().map(|(name, _)| name).join(", ")
+            ),
+        };
+
+        if shims::exists(ctx, &self.name) {
+            shims::remove_any(ctx, &self.name)?;
+        }
+
+        let shim = Shim::new(&bin, &ctx.apps_path().join(&app).join("current"));
+        shim.create(ctx)?;
+
+        eprintln_green!("{} now points to {}", self.name, shim.target.display());
+
+        Ok(())
+    }
+}
+
+/// Find the installed apps that provide a binary with the given name
+pub(super) fn providers(
+    ctx: &impl ScoopContext,
+    name: &str,
+    arch: Architecture,
+) -> anyhow::Result<Vec<(String, Bin)>> {
+    let apps = models::install::Manifest::list_all_unchecked(ctx)?;
+
+    let providers = apps
+        .iter()
+        .filter_map(|app| {
+            let app_name = unsafe { app.name() }.to_string();
+
+            let (manifest, installed_arch) =
+                install::installed_manifest(&ctx.apps_path().join(&app_name)).ok()?;
+            let config =
+                InstallConfig::from_manifest(&manifest, installed_arch.unwrap_or(arch)).ok()?;
+
+            let bin = config
+                .bins()
+                .into_iter()
+                .find(|bin| bin.name.eq_ignore_ascii_case(name))?;
+
+            Some((app_name, bin))
+        })
+        .collect();
+
+    Ok(providers)
+}
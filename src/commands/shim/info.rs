@@ -0,0 +1,66 @@
+use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use schemars::JsonSchema;
+use serde::Serialize;
+use sprinkles::contexts::ScoopContext;
+
+use crate::{
+    abandon,
+    commands::completions,
+    install::shims,
+    output::{format::Format, structured::vertical::VTable},
+};
+
+use super::list::{self, ShimInfo};
+
+#[derive(Debug, Clone, Parser)]
+/// Show information about a shim
+pub struct Args {
+    #[clap(help = "The name of the shim", add = ArgValueCandidates::new(completions::shims))]
+    name: String,
+
+    #[clap(from_global)]
+    format: Format,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub(crate) struct ShimDetails {
+    #[serde(flatten)]
+    shim: ShimInfo,
+    /// The arguments always passed to the target
+    args: Option<String>,
+    /// Whether the shim points at a version directory, rather than the app's `current` directory
+    outdated: bool,
+    /// The files that make up the shim
+    files: Vec<String>,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        if !shims::exists(ctx, &self.name) {
+            abandon!("No shim named {} exists", self.name);
+        }
+
+        let outdated = shims::target(ctx, &self.name)
+            .is_some_and(|target| list::targets_version_dir(&ctx.apps_path(), &target));
+
+        let details = ShimDetails {
+            shim: ShimInfo::new(ctx, self.name.clone()),
+            args: shims::args(ctx, &self.name),
+            outdated,
+            files: shims::shim_files(ctx, &self.name)
+                .into_iter()
+                .filter(|file| file.exists())
+                .map(|file| file.display().to_string())
+                .collect(),
+        };
+
+        if self.format.is_structured() {
+            self.format.print(&details)?;
+        } else {
+            println!("{}", VTable::new(&details));
+        }
+
+        Ok(())
+    }
+}
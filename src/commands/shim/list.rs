@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use clap::Parser;
+use schemars::JsonSchema;
+use serde::Serialize;
+use sprinkles::contexts::ScoopContext;
+
+use crate::{
+    install::shims,
+    output::{
+        format::Format,
+        structured::{self, Priority, Structured},
+    },
+};
+
+#[derive(Debug, Clone, Parser)]
+/// List all shims, along with the binaries they point to
+pub struct Args {
+    #[clap(long, help = "Only show shims whose target no longer exists")]
+    broken: bool,
+
+    #[clap(
+        long,
+        help = "Only show shims that point into an app which is no longer installed"
+    )]
+    orphaned: bool,
+
+    #[clap(flatten)]
+    table: structured::Options,
+
+    #[clap(from_global)]
+    format: Format,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub(crate) struct ShimInfo {
+    name: String,
+    app: Option<String>,
+    target: Option<String>,
+    broken: bool,
+    orphaned: bool,
+}
+
+impl ShimInfo {
+    pub(super) fn new(ctx: &impl ScoopContext, name: String) -> Self {
+        let target = shims::target(ctx, &name);
+        let app = target
+            .as_deref()
+            .and_then(|target| owning_app(&ctx.apps_path(), target));
+        let broken = !target.as_ref().is_some_and(|target| target.exists());
+        let orphaned = app
+            .as_ref()
+            .is_some_and(|app| !ctx.apps_path().join(app).exists());
+
+        Self {
+            name,
+            app,
+            target: target.map(|target| target.display().to_string()),
+            broken,
+            orphaned,
+        }
+    }
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let shims = shims::list(ctx)?
+            .into_iter()
+            .map(|name| ShimInfo::new(ctx, name))
+            .filter(|shim| !self.broken || shim.broken)
+            .filter(|shim| !self.orphaned || shim.orphaned)
+            .collect::<Vec<_>>();
+
+        if self.format.is_structured() {
+            self.format.print(&shims)?;
+        } else if shims.is_empty() {
+            println!("No shims found.");
+        } else {
+            let values = shims
+                .iter()
+                .map(serde_json::to_value)
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let outputs = Structured::new(&values)
+                .with_priority("app", Priority::High)
+                .with_priority("target", Priority::Low)
+                .with_priority("orphaned", Priority::Low)
+                .with_options(&self.table);
+
+            print!("{outputs}");
+        }
+
+        Ok(())
+    }
+}
+
+/// Get the name of the installed app a shim target lives in, if any
+pub(crate) fn owning_app(apps_path: &Path, target: &Path) -> Option<String> {
+    let relative = target.strip_prefix(apps_path).ok()?;
+
+    relative
+        .components()
+        .next()
+        .map(|app| app.as_os_str().to_string_lossy().to_string())
+}
+
+/// Check if a shim target points into a specific version directory of an app, rather than its `current` junction
+///
+/// These shims are left behind by older installs, and break once the version is cleaned up
+pub(super) fn targets_version_dir(apps_path: &Path, target: &Path) -> bool {
+    target
+        .strip_prefix(apps_path)
+        .ok()
+        .and_then(|relative| relative.components().nth(1))
+        .is_some_and(|version| version.as_os_str() != "current")
+}
@@ -0,0 +1,36 @@
+use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use sprinkles::contexts::ScoopContext;
+
+use crate::{
+    commands::completions,
+    install::shims,
+    output::colours::{eprintln_green, eprintln_yellow},
+};
+
+#[derive(Debug, Clone, Parser)]
+/// Remove shims, regardless of which app they belong to
+pub struct Args {
+    #[clap(
+        help = "The name(s) of the shims to remove",
+        required = true,
+        add = ArgValueCandidates::new(completions::shims)
+    )]
+    names: Vec<String>,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        for name in &self.names {
+            if !shims::exists(ctx, name) {
+                eprintln_yellow!("No shim named {name} exists");
+                continue;
+            }
+
+            shims::remove_any(ctx, name)?;
+            eprintln_green!("Removed shim {name}");
+        }
+
+        Ok(())
+    }
+}
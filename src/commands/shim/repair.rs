@@ -0,0 +1,157 @@
+use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use itertools::Itertools;
+use sprinkles::{Architecture, contexts::ScoopContext};
+
+use crate::{
+    commands::completions,
+    install::shims::{self, Shim},
+    output::colours::{eprintln_green, eprintln_yellow},
+};
+
+use super::{alter, list};
+
+#[derive(Debug, Clone, Parser)]
+/// Repair broken shims, pointing them at the installed app that provides their binary
+///
+/// Shims that point at an old version directory, rather than the app's `current` directory, are repaired as well.
+/// Shims that point into an app which is no longer installed are left alone, unless `--remove-orphaned` is passed
+pub struct Args {
+    #[clap(
+        help = "The name(s) of the shims to repair. Repairs all broken shims if none are provided",
+        add = ArgValueCandidates::new(completions::shims)
+    )]
+    names: Vec<String>,
+
+    #[clap(
+        long,
+        help = "Remove broken shims that no installed app provides a binary for"
+    )]
+    remove_orphaned: bool,
+
+    #[clap(
+        long,
+        help = "Print what would be done, but don't actually do anything"
+    )]
+    dry_run: bool,
+
+    #[clap(from_global)]
+    arch: Architecture,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let explicit = !self.names.is_empty();
+
+        let names = if explicit {
+            self.names.clone()
+        } else {
+            shims::list(ctx)?
+        };
+
+        let mut repaired = 0;
+
+        for name in &names {
+            if !shims::exists(ctx, name) {
+                eprintln_yellow!("No shim named {name} exists");
+                continue;
+            }
+
+            let target = shims::target(ctx, name);
+
+            let outdated = target
+                .as_ref()
+                .is_some_and(|target| list::targets_version_dir(&ctx.apps_path(), target));
+
+            if !outdated && target.as_ref().is_some_and(|target| target.exists()) {
+                if explicit {
+                    eprintln!("{name} is not broken");
+                }
+                continue;
+            }
+
+            if self.repair(ctx, name, target.as_deref())? {
+                repaired += 1;
+            }
+        }
+
+        if repaired == 0 {
+            eprintln_green!("No shims were repaired");
+        } else if !self.dry_run {
+            eprintln_green!(
+                "Repaired {repaired} {}",
+                if repaired == 1 { "shim" } else { "shims" }
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Args {
+    /// Repair a single broken or outdated shim
+    ///
+    /// Prefers the app the shim pointed into, falling back to the only other app that provides the binary.
+    /// Returns whether the shim was recreated or removed
+    fn repair(
+        &self,
+        ctx: &impl ScoopContext,
+        name: &str,
+        target: Option<&std::path::Path>,
+    ) -> anyhow::Result<bool> {
+        let owner = target.and_then(|target| list::owning_app(&ctx.apps_path(), target));
+        let providers = alter::providers(ctx, name, self.arch)?;
+
+        let provider = owner
+            .as_ref()
+            .and_then(|owner| {
+                providers
+                    .iter()
+                    .find(|(app, _)| app.eq_ignore_ascii_case(owner))
+            })
+            .or(match providers.as_slice() {
+                [provider] => Some(provider),
+                _ => None,
+            });
+
+        if let Some((app, bin)) = provider {
+            let shim = Shim::new(bin, &ctx.apps_path().join(app).join("current"));
+
+            if self.dry_run {
+                eprintln!("Would point {name} to {}", shim.target.display());
+            } else {
+                shims::remove_any(ctx, name)?;
+                shim.create(ctx)?;
+
+                eprintln!("{name} now points to {}", shim.target.display());
+            }
+
+            return Ok(true);
+        }
+
+        if !providers.is_empty() {
+            eprintln_yellow!(
+                "{name} is provided by multiple apps: {}. Use `shim alter` to choose one",
+                providers.iter().map(|(app, _)| app).join(", ")
+            );
+            return Ok(false);
+        }
+
+        if !self.remove_orphaned {
+            eprintln_yellow!(
+                "No installed app provides {name}. Use --remove-orphaned to remove the shim"
+            );
+            return Ok(false);
+        }
+
+        if self.dry_run {
+            eprintln!("Would remove {name}");
+        } else {
+            shims::remove_any(ctx, name)?;
+
+            eprintln!("Removed {name}");
+        }
+
+        Ok(true)
+    }
+}
@@ -1,5 +1,8 @@
-use std::{fmt::Write, sync::Arc};
+use std::{collections::HashMap, fmt::Write, sync::Arc};
 
+use chrono::{DateTime, Local};
+
+use anyhow::Context;
 use clap::{Parser, ValueEnum};
 use parking_lot::Mutex;
 use quork::prelude::*;
@@ -10,18 +13,29 @@ use sprinkles::{
     buckets::Bucket,
     contexts::ScoopContext,
     packages::models::install,
-    progress::{indicatif::ProgressBar, style},
+    progress::{
+        Message, ProgressOptions,
+        indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressFinish},
+        style,
+    },
 };
 
+#[cfg(feature = "contexts")]
+use crate::scope::Scope;
 use crate::{
-    models::status::Info,
+    freshness,
+    models::{
+        min,
+        status::{Info, Stale, Threshold},
+    },
     output::{
+        format::Format,
         sectioned::{Children, Section},
-        structured::Structured,
+        structured::{Priority, Structured},
     },
 };
 
-#[derive(Debug, Copy, Clone, ValueEnum, ListVariants)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum, ListVariants)]
 enum Command {
     Scoop,
     Buckets,
@@ -32,7 +46,7 @@ enum Command {
 /// Show status and check for new app versions
 pub struct Args {
     #[clap(from_global)]
-    json: bool,
+    format: Format,
 
     #[clap(from_global)]
     verbose: bool,
@@ -42,13 +56,43 @@ pub struct Args {
 
     #[clap(short = 'H', long, help = "Ignore held packages")]
     ignore_held: bool,
+
+    #[clap(
+        short,
+        long,
+        help = "Fetch the latest bucket changes before checking for outdated apps"
+    )]
+    remote: bool,
+
+    #[clap(
+        short,
+        long,
+        help = "Also flag apps and buckets that have not been updated within the given duration (e.g. 12h, 7d, 2w)"
+    )]
+    threshold: Option<Threshold>,
+
+    #[cfg(feature = "contexts")]
+    #[clap(
+        long,
+        help = "Check apps from the user install, the global install, or both. Each app is annotated with its install"
+    )]
+    scope: Option<Scope>,
 }
 
 impl super::Command for Args {
     async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        if self.remote {
+            freshness::refresh_buckets(ctx)?;
+        }
+
         let value = Arc::new(Mutex::new(Value::default()));
 
-        let pb = ProgressBar::new(3).with_style(style(None, None));
+        let mp = MultiProgress::new();
+        if self.format.is_structured() {
+            mp.set_draw_target(ProgressDrawTarget::hidden());
+        }
+
+        let pb = mp.add(ProgressBar::new(3).with_style(style(None, None)));
 
         let commands: &[Command] = {
             if self.only.is_empty() {
@@ -62,6 +106,7 @@ impl super::Command for Args {
             let command = *command;
             let this = self.clone();
             let pb = pb.clone();
+            let mp = mp.clone();
             let value = value.clone();
             async move {
                 let mut output = String::new();
@@ -69,7 +114,7 @@ impl super::Command for Args {
                 match command {
                     Command::Scoop => this.handle_scoop(ctx, &value, &mut output).await?,
                     Command::Buckets => this.handle_buckets(ctx, &value, &mut output)?,
-                    Command::Apps => this.handle_packages(ctx, &value, &mut output)?,
+                    Command::Apps => this.handle_packages(ctx, &value, &mut output, &mp)?,
                 };
 
                 pb.inc(1);
@@ -78,13 +123,18 @@ impl super::Command for Args {
             }
         });
 
-        let outputs = futures::future::try_join_all(outputs).await?;
+        let mut outputs = futures::future::try_join_all(outputs).await?;
+
+        if let Some(threshold) = self.threshold {
+            let mut output = String::new();
+            self.handle_stale(ctx, threshold, commands, &value, &mut output)?;
+            outputs.push(output);
+        }
 
         pb.finish_and_clear();
 
-        if self.json {
-            let output = serde_json::to_string(&value.lock().clone())?;
-            println!("{output}");
+        if self.format.is_structured() {
+            self.format.print(&value.lock().clone())?;
         } else {
             for output in outputs {
                 print!("{output}");
@@ -102,9 +152,16 @@ impl Args {
         value: &Mutex<Value>,
         output: &mut dyn Write,
     ) -> anyhow::Result<()> {
+        if crate::is_offline() {
+            if !self.format.is_structured() {
+                writeln!(output, "Skipping Scoop update check in offline mode.")?;
+            }
+            return Ok(());
+        }
+
         let is_outdated = ctx.outdated().await?;
 
-        if self.json {
+        if self.format.is_structured() {
             value.lock()["scoop"] = serde_json::to_value(is_outdated)?;
             return Ok(());
         } else if is_outdated {
@@ -129,10 +186,17 @@ impl Args {
         value: &Mutex<Value>,
         output: &mut dyn Write,
     ) -> anyhow::Result<()> {
+        if crate::is_offline() {
+            if !self.format.is_structured() {
+                writeln!(output, "Skipping bucket update checks in offline mode.")?;
+            }
+            return Ok(());
+        }
+
         let buckets = Bucket::list_all(ctx)?;
 
         // Handle buckets
-        if self.verbose || self.json {
+        if self.verbose || self.format.is_structured() {
             let outdated_buckets = buckets
                 .par_iter()
                 .filter_map(|bucket| {
@@ -146,7 +210,7 @@ impl Args {
                 })
                 .collect::<Vec<_>>();
 
-            if self.json {
+            if self.format.is_structured() {
                 value.lock()["buckets"] = serde_json::to_value(&outdated_buckets)?;
                 return Ok(());
             }
@@ -195,14 +259,163 @@ impl Args {
         ctx: &impl ScoopContext,
         value: &Mutex<Value>,
         output: &mut dyn Write,
+        mp: &MultiProgress,
+    ) -> anyhow::Result<()> {
+        let mut invalid_apps = self.check_scopes(ctx, mp)?;
+
+        if self.format.is_structured() {
+            value.lock()["packages"] = serde_json::to_value(&invalid_apps)?;
+            return Ok(());
+        }
+
+        if invalid_apps.is_empty() {
+            writeln!(output, "All packages are okay and up to date.")?;
+        } else {
+            invalid_apps.par_sort_by(|a, b| a.name.cmp(&b.name));
+
+            let values = invalid_apps
+                .par_iter()
+                .map(serde_json::to_value)
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let outputs = Structured::new(&values)
+                .with_priority("current", Priority::High)
+                .with_priority("available", Priority::High)
+                .with_priority("info", Priority::Low)
+                // Already summarised in the info column
+                .with_priority("held", Priority::Low)
+                .with_priority("failed", Priority::Low)
+                .with_priority("pinned", Priority::Low)
+                .with_priority("scope", Priority::High);
+
+            write!(output, "{outputs}")?;
+        }
+
+        Ok(())
+    }
+
+    /// Find the apps and buckets that have not been updated within the threshold
+    fn handle_stale(
+        &self,
+        ctx: &impl ScoopContext,
+        threshold: Threshold,
+        commands: &[Command],
+        value: &Mutex<Value>,
+        output: &mut dyn Write,
     ) -> anyhow::Result<()> {
+        let (stale_buckets, stale_apps) = rayon::join(
+            || -> anyhow::Result<_> {
+                if !commands.contains(&Command::Buckets) {
+                    return Ok(None);
+                }
+
+                let buckets = Bucket::list_all(ctx)?;
+
+                let stale = buckets
+                    .par_iter()
+                    .filter_map(|bucket| match last_commit_time(bucket) {
+                        Ok(updated) => threshold
+                            .is_stale(&updated)
+                            .then(|| Stale::new(bucket.name().to_string(), updated)),
+                        Err(err) => {
+                            error!(
+                                "Failed to get last update time for bucket {}: {err:?}",
+                                bucket.name()
+                            );
+                            None
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                Ok(Some(stale))
+            },
+            || -> anyhow::Result<_> {
+                if !commands.contains(&Command::Apps) {
+                    return Ok(None);
+                }
+
+                let apps = min::Info::list_installed(ctx, None)?;
+
+                let stale = apps
+                    .into_par_iter()
+                    .filter(|app| threshold.is_stale(&app.updated))
+                    .map(|app| Stale::new(app.name, *app.updated))
+                    .collect::<Vec<_>>();
+
+                Ok(Some(stale))
+            },
+        );
+
+        let sections = [("buckets", stale_buckets?), ("apps", stale_apps?)];
+
+        for (kind, stale) in sections {
+            let Some(mut stale) = stale else {
+                continue;
+            };
+
+            stale.sort_by(|a, b| a.name.cmp(&b.name));
+
+            if self.format.is_structured() {
+                value.lock()[format!("stale_{kind}")] = serde_json::to_value(&stale)?;
+            } else if stale.is_empty() {
+                writeln!(output, "All {kind} updated within the last {threshold}.")?;
+            } else {
+                let title = format!(
+                    "{} {kind} not updated within the last {threshold}:",
+                    stale.len()
+                );
+
+                let section = Section::new(Children::from(stale)).with_title(title);
+
+                writeln!(output, "{section}")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check the apps in each Scoop install in the selected scope, or only the current context if no scope was selected
+    fn check_scopes(
+        &self,
+        ctx: &impl ScoopContext,
+        mp: &MultiProgress,
+    ) -> anyhow::Result<Vec<Info>> {
+        #[cfg(feature = "contexts")]
+        if let Some(scope) = self.scope {
+            let mut invalid_apps = vec![];
+
+            for (scope, ctx) in scope.contexts()? {
+                let infos = self.check_apps(&ctx, mp)?;
+
+                invalid_apps.extend(infos.into_iter().map(|info| Info {
+                    scope: Some(scope),
+                    ..info
+                }));
+            }
+
+            return Ok(invalid_apps);
+        }
+
+        self.check_apps(ctx, mp)
+    }
+
+    /// Find the installed apps that are outdated or otherwise broken
+    fn check_apps(&self, ctx: &impl ScoopContext, mp: &MultiProgress) -> anyhow::Result<Vec<Info>> {
         let apps = install::Manifest::list_all_unchecked(ctx)?;
 
         debug!("Checking {} apps", apps.len());
 
+        let bucket_bars = Self::bucket_bars(&apps, mp);
+
         let mut invalid_apps = apps
             .par_iter()
             .flat_map(|app| -> anyhow::Result<Info> {
+                let _progress = app
+                    .bucket
+                    .as_ref()
+                    .and_then(|bucket| bucket_bars.get(bucket))
+                    .map(IncOnDrop);
+
                 if let Some(bucket) = &app.bucket {
                     let local_manifest = app.get_manifest(ctx)?;
                     // TODO: Add the option to check all buckets and find the highest version (will require semver to order versions)
@@ -238,32 +451,64 @@ impl Args {
                 };
 
                 // Filter out apps that are okay
-                info_exists || missing_deps || app.current != app.available
+                info_exists || missing_deps || app.removed || app.current != app.available
             })
             .collect::<Vec<_>>();
 
         invalid_apps.dedup();
 
-        if self.json {
-            value.lock()["packages"] = serde_json::to_value(&invalid_apps)?;
-            return Ok(());
+        for pb in bucket_bars.values() {
+            pb.finish_and_clear();
         }
 
-        if invalid_apps.is_empty() {
-            writeln!(output, "All packages are okay and up to date.")?;
-        } else {
-            invalid_apps.par_sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(invalid_apps)
+    }
 
-            let values = invalid_apps
-                .par_iter()
-                .map(serde_json::to_value)
-                .collect::<Result<Vec<_>, _>>()?;
+    /// Create a progress bar for each bucket, with a length of the number of apps installed from it
+    fn bucket_bars(apps: &[install::Manifest], mp: &MultiProgress) -> HashMap<String, ProgressBar> {
+        let mut counts = HashMap::<&str, u64>::new();
+        for bucket in apps.iter().filter_map(|app| app.bucket.as_deref()) {
+            *counts.entry(bucket).or_default() += 1;
+        }
 
-            let outputs = Structured::new(&values);
+        let longest_bucket_name = counts.keys().map(|bucket| bucket.len()).max().unwrap_or(0);
+        let progress_style = style(Some(ProgressOptions::PosLen), Some(Message::suffix()));
 
-            write!(output, "{outputs}")?;
-        }
+        let mut buckets = counts.into_iter().collect::<Vec<_>>();
+        buckets.sort_unstable();
 
-        Ok(())
+        buckets
+            .into_iter()
+            .map(|(bucket, count)| {
+                let pb = mp.add(
+                    ProgressBar::new(count)
+                        .with_style(progress_style.clone())
+                        .with_message("Checking apps")
+                        .with_prefix(format!("🪣 {bucket:<longest_bucket_name$}"))
+                        .with_finish(ProgressFinish::AndClear),
+                );
+
+                (bucket.to_string(), pb)
+            })
+            .collect()
+    }
+}
+
+/// Get the time of the latest commit in the bucket's repository
+fn last_commit_time(bucket: &Bucket) -> anyhow::Result<DateTime<Local>> {
+    let latest_commit = bucket.open_repo()?.latest_commit()?;
+    let secs = latest_commit.time()?.seconds;
+
+    let utc_time = DateTime::from_timestamp(secs, 0).context("Invalid commit time")?;
+
+    Ok(utc_time.with_timezone(&Local))
+}
+
+/// Increments a progress bar when dropped, so that it is incremented regardless of how the app check exits
+struct IncOnDrop<'a>(&'a ProgressBar);
+
+impl Drop for IncOnDrop<'_> {
+    fn drop(&mut self) {
+        self.0.inc(1);
     }
 }
@@ -1,22 +1,48 @@
 use clap::Parser;
-use sprinkles::{config, contexts::ScoopContext};
+use sprinkles::{Architecture, config, contexts::ScoopContext};
 
 use crate::output::colours::eprintln_yellow;
 
 use super::bucket;
+#[cfg(feature = "download")]
+use super::{CommandRunner, app};
 
 #[derive(Debug, Clone, Parser)]
 /// Update Scoop and Scoop buckets
 pub struct ArgsWrapper {
     #[clap(flatten)]
     args: bucket::update::Args,
+
+    #[cfg(feature = "download")]
+    #[clap(
+        short,
+        long,
+        help = "Update all outdated apps after updating the buckets"
+    )]
+    apps: bool,
+
+    #[cfg(feature = "download")]
+    #[clap(from_global)]
+    arch: Architecture,
 }
 
 impl super::Command for ArgsWrapper {
+    const NEEDS_NETWORK: bool = true;
+
     async fn runner(self, ctx: &impl ScoopContext<Config = config::Scoop>) -> anyhow::Result<()> {
         eprintln_yellow!(
-            "Updating buckets has been renamed to `bucket update`. Updating apps is not yet supported and will be added in a future release."
+            "Updating buckets has been renamed to `bucket update`. Use `app update` to update apps."
         );
-        bucket::update::Args::runner(self.args, ctx).await
+        bucket::update::Args::runner(self.args, ctx).await?;
+
+        #[cfg(feature = "download")]
+        if self.apps {
+            eprintln!();
+
+            // Run through the command runner, so the app update's beta and offline checks apply
+            app::update::Args::all(self.arch).run(ctx).await?;
+        }
+
+        Ok(())
     }
 }
@@ -0,0 +1,274 @@
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use clap::Parser;
+use rayon::prelude::*;
+use schemars::JsonSchema;
+use serde::Serialize;
+use sha3::{Digest, Sha3_256};
+use sprinkles::{
+    Architecture, config,
+    contexts::ScoopContext,
+    hash::{Hash, HashType},
+    packages::reference::package,
+};
+
+use crate::{
+    install::{self, config::InstallConfig},
+    output::{
+        colours::{eprintln_green, eprintln_yellow},
+        format::Format,
+        structured::{Priority, Structured},
+    },
+};
+
+use super::Command;
+
+#[derive(Debug, Clone, Parser)]
+/// Verify the cached downloads of installed apps against their manifest hashes
+pub struct Args {
+    #[clap(help = "The apps to verify. Defaults to all installed apps")]
+    apps: Vec<package::Reference>,
+
+    #[clap(from_global)]
+    arch: Architecture,
+
+    #[clap(from_global)]
+    format: Format,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// The result of verifying a single file
+pub(crate) enum Status {
+    /// The file matches the manifest hash, or exists if it has no hash
+    Ok,
+    /// The file does not match the manifest hash
+    Mismatch,
+    /// The file does not exist
+    Missing,
+    /// The manifest hash uses an algorithm that cannot be verified
+    Unsupported,
+    /// The file exists, but could not be read to hash it
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub(crate) struct Verification {
+    name: String,
+    version: String,
+    status: Status,
+    url: String,
+    expected: String,
+    actual: Option<String>,
+}
+
+/// A download to verify, along with the app it belongs to
+struct Download {
+    name: String,
+    version: String,
+    url: String,
+    hash: String,
+}
+
+impl Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext<Config = config::Scoop>) -> anyhow::Result<()> {
+        let names = super::app::names_or_installed(ctx, &self.apps)?;
+
+        let downloads = names
+            .into_iter()
+            .flat_map(|name| self.downloads(ctx, name))
+            .collect::<Vec<_>>();
+
+        let mut verifications = downloads
+            .into_par_iter()
+            .map(|download| verify(ctx, download))
+            .collect::<Vec<_>>();
+
+        verifications.sort_by(|a, b| a.name.cmp(&b.name));
+
+        if self.format.is_structured() {
+            self.format.print(&verifications)?;
+            return Ok(());
+        }
+
+        let failed = verifications
+            .iter()
+            .filter(|verification| verification.status != Status::Ok)
+            .collect::<Vec<_>>();
+
+        if failed.is_empty() {
+            eprintln_green!(
+                "All {} cached downloads match their manifest hashes",
+                verifications.len()
+            );
+        } else {
+            let outputs = Structured::new(&failed)
+                .with_priority("status", Priority::Required)
+                .with_priority("url", Priority::Low)
+                .with_priority("actual", Priority::Low);
+
+            print!("{outputs}");
+
+            eprintln_yellow!(
+                "{} of {} cached downloads could not be verified",
+                failed.len(),
+                verifications.len()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Args {
+    /// List the downloads of an installed app, with the hashes from the manifest it was installed from
+    fn downloads(&self, ctx: &impl ScoopContext, name: String) -> Vec<Download> {
+        let app_dir = ctx.apps_path().join(&name);
+
+        let (manifest, arch) = match install::installed_manifest(&app_dir) {
+            Ok(installed) => installed,
+            Err(e) => {
+                eprintln_yellow!("Skipping {name}, as its manifest could not be read: {e}");
+                return vec![];
+            }
+        };
+
+        let Ok(config) = InstallConfig::from_manifest(&manifest, arch.unwrap_or(self.arch)) else {
+            eprintln_yellow!("Skipping {name}, as its manifest could not be parsed");
+            return vec![];
+        };
+
+        let version = manifest.version.to_string();
+
+        config
+            .url
+            .into_vec()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, url)| {
+                Some(Download {
+                    name: name.clone(),
+                    version: version.clone(),
+                    url,
+                    hash: config.hash.get(index)?.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Recompute the hash of a cached download and compare it to the manifest hash
+fn verify(ctx: &impl ScoopContext, download: Download) -> Verification {
+    let path = install::cache_file(ctx, &download.name, &download.version, &download.url);
+
+    let (algorithm, expected) = split_hash(&download.hash);
+
+    let (status, actual) = if path.exists() {
+        match hash_file(&path, &algorithm) {
+            Ok(Some(actual)) if actual == expected => (Status::Ok, Some(actual)),
+            Ok(Some(actual)) => (Status::Mismatch, Some(actual)),
+            Ok(None) => (Status::Unsupported, None),
+            Err(e) => {
+                warn!("Failed to hash {}: {e}", path.display());
+                (Status::Error, None)
+            }
+        }
+    } else {
+        (Status::Missing, None)
+    };
+
+    Verification {
+        name: download.name,
+        version: download.version,
+        status,
+        url: download.url,
+        expected,
+        actual,
+    }
+}
+
+/// Split a manifest hash into its lowercase algorithm and digest
+///
+/// Hashes without an algorithm prefix are SHA256, as in Scoop
+pub(crate) fn split_hash(hash: &str) -> (String, String) {
+    match hash.split_once(':') {
+        Some((algorithm, hash)) => (algorithm.to_lowercase(), hash.to_lowercase()),
+        None => ("sha256".to_string(), hash.to_lowercase()),
+    }
+}
+
+/// Hash a file with the given algorithm, as a lowercase hex string
+///
+/// Supports every algorithm Scoop manifests use, as well as blake3 and sha3 (SHA3-256).
+/// Returns [`None`] if the algorithm is not supported
+pub(crate) fn hash_file(path: &Path, algorithm: &str) -> std::io::Result<Option<String>> {
+    let hash_type = match algorithm {
+        "md5" => HashType::MD5,
+        "sha1" => HashType::SHA1,
+        "sha256" => HashType::SHA256,
+        "sha512" => HashType::SHA512,
+        "blake3" => {
+            let mut hasher = blake3::Hasher::new();
+            std::io::copy(&mut File::open(path)?, &mut hasher)?;
+            return Ok(Some(hasher.finalize().to_hex().to_string()));
+        }
+        "sha3" => {
+            let mut hasher = Sha3_256::new();
+            std::io::copy(&mut File::open(path)?, &mut hasher)?;
+            return Ok(Some(format!("{:x}", hasher.finalize())));
+        }
+        _ => return Ok(None),
+    };
+
+    let hash = Hash::compute(BufReader::new(File::open(path)?), hash_type);
+
+    Ok(Some(hash.no_prefix().to_lowercase()))
+}
+
+/// A manifest hash that sprinkles can't compute while downloading, checked against the cached download instead
+pub(crate) struct CachedHash {
+    path: PathBuf,
+    algorithm: String,
+    /// The hash the manifest expects
+    pub(crate) expected: String,
+}
+
+impl CachedHash {
+    /// The algorithms sprinkles doesn't compute while downloading
+    const ALGORITHMS: &[&str] = &["blake3", "sha3"];
+
+    /// Check the download of the given url against the manifest hash once it is cached
+    ///
+    /// Returns [`None`] if sprinkles computes the hash's algorithm itself
+    pub(crate) fn new(
+        ctx: &impl ScoopContext,
+        name: &str,
+        version: &str,
+        url: &str,
+        hash: &str,
+    ) -> Option<Self> {
+        let (algorithm, expected) = split_hash(hash);
+
+        Self::ALGORITHMS
+            .contains(&algorithm.as_str())
+            .then(|| Self {
+                path: install::cache_file(ctx, name, version, url),
+                algorithm,
+                expected,
+            })
+    }
+
+    /// Hash the cached download, returning the computed hash if it does not match the manifest hash
+    ///
+    /// # Errors
+    /// - The cached download could not be read
+    pub(crate) fn mismatch(&self) -> std::io::Result<Option<String>> {
+        let actual = hash_file(&self.path, &self.algorithm)?.unwrap_or_default();
+
+        Ok((actual != self.expected).then_some(actual))
+    }
+}
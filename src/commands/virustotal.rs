@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use anyhow::Context;
-use clap::{Parser, ValueEnum};
+use clap::Parser;
 use rayon::prelude::*;
 use sprinkles::{
     Architecture, config,
@@ -13,10 +13,16 @@ use sprinkles::{
 };
 
 use crate::{
+    abandon,
     calm_panic::CalmUnwrap,
     errors::{RecoverableError, RecoverableResult},
     limits::RateLimiter,
-    output::colours::{eprintln_green, eprintln_red, eprintln_yellow},
+    models::virustotal::{Report, Status},
+    output::{
+        colours::{eprintln_green, eprintln_red, eprintln_yellow},
+        format::Format,
+        structured::{self, Priority, Structured},
+    },
 };
 
 impl RecoverableError for vt3::error::VtError {
@@ -31,30 +37,6 @@ impl RecoverableError for vt3::error::VtError {
     }
 }
 
-#[derive(Debug, Copy, Clone, ValueEnum, PartialEq, Eq, PartialOrd, Ord)]
-enum Status {
-    Undetected,
-    Suspicious,
-    Malicious,
-}
-
-impl Status {
-    #[allow(clippy::cast_precision_loss, clippy::cast_sign_loss)]
-    pub fn from_stats(dangerous: u64, total: u64) -> Self {
-        let dangerous = dangerous as f64;
-        let total = total as f64;
-        let ratio = dangerous / total;
-
-        if ratio > 0.1 {
-            Self::Malicious
-        } else if dangerous > 0.0 {
-            Self::Suspicious
-        } else {
-            Self::Undetected
-        }
-    }
-}
-
 #[derive(Debug, Clone)]
 enum SearchType {
     FileHash(Hash),
@@ -76,6 +58,20 @@ impl StrippedManifest {
             search_type,
         }
     }
+
+    fn into_report(self, detected: u64, total: u64) -> Report {
+        let (lookup, link) = match self.search_type {
+            SearchType::FileHash(hash) => {
+                let hash = hash.no_prefix().to_string();
+                let link = format!("https://www.virustotal.com/gui/file/{hash}");
+
+                (hash, Some(link))
+            }
+            SearchType::Url(url) => (url, None),
+        };
+
+        Report::new(self.name, self.bucket, lookup, link, detected, total)
+    }
 }
 
 /// Value should be a `Root` object
@@ -117,12 +113,34 @@ pub struct Args {
     #[clap(short = 'A', long, help = "Scan all installed apps")]
     all: bool,
 
+    #[clap(
+        short,
+        long,
+        help = "Exit with an error if any download is detected by more than this many engines"
+    )]
+    threshold: Option<u64>,
+
+    #[clap(
+        long,
+        help = "The maximum number of requests to send to VirusTotal per minute. Defaults to the public API limit",
+        default_value_t = 4,
+        env = "SFSU_VIRUSTOTAL_RATE_LIMIT"
+    )]
+    requests_per_minute: u64,
+
+    #[clap(flatten)]
+    table: structured::Options,
+
+    #[clap(from_global)]
+    format: Format,
+
     #[clap(from_global)]
-    json: bool,
+    verbose: bool,
 }
 
 impl super::Command for Args {
     const BETA: bool = true;
+    const NEEDS_NETWORK: bool = true;
 
     async fn runner(
         self,
@@ -160,7 +178,9 @@ impl super::Command for Args {
         let pb = ProgressBar::new(manifests.len() as u64)
             .with_style(style(Some(ProgressOptions::PosLen), None));
 
-        let rate_limiter = RateLimiter::new(4, Duration::from_secs(5));
+        // Requests are sent in batches, so that the API's per minute quota is never exceeded
+        let rate_limiter =
+            RateLimiter::new(self.requests_per_minute.max(1), Duration::from_secs(60));
 
         let matches = manifests
             .into_iter()
@@ -199,7 +219,7 @@ impl super::Command for Args {
                             match result {
                                 Some(result) => serde_json::to_value(result?)?,
                                 _ => {
-                                    return anyhow::Ok((manifest, None));
+                                    return anyhow::Ok((manifest.name, None));
                                 }
                             }
                         }
@@ -210,7 +230,7 @@ impl super::Command for Args {
 
                             match result {
                                 Some(result) => serde_json::to_value(result?)?,
-                                None => return anyhow::Ok((manifest, None)),
+                                None => return anyhow::Ok((manifest.name, None)),
                             }
                         }
                     };
@@ -220,71 +240,76 @@ impl super::Command for Args {
                     pb.inc(1);
 
                     anyhow::Ok((
-                        manifest,
-                        Some((Status::from_stats(detected, total), detected, total)),
+                        manifest.name.clone(),
+                        Some(manifest.into_report(detected, total)),
                     ))
                 }
             });
 
         let matches = futures::future::try_join_all(matches).await?;
 
-        for (manifest, result) in matches {
-            if let Some((file_status, detected, total)) = result {
-                self.handle_output(manifest, file_status, detected, total)?;
+        pb.finish_and_clear();
+
+        let mut reports = vec![];
+
+        for (name, report) in matches {
+            if let Some(report) = report {
+                reports.push(report);
             } else {
-                eprintln!(
-                    "Error while getting info for {}. Try again later.",
-                    manifest.name
-                );
+                eprintln!("Error while getting info for {name}. Try again later.");
             }
         }
 
-        Ok(())
-    }
-}
+        let exceeding = self.threshold.map_or(0, |threshold| {
+            reports
+                .iter()
+                .filter(|report| report.detected > threshold)
+                .count()
+        });
 
-impl Args {
-    fn handle_output(
-        &self,
-        manifest: StrippedManifest,
-        file_status: Status,
-        detected: u64,
-        total: u64,
-    ) -> std::fmt::Result {
-        if let Some(filter) = self.filter {
-            if file_status <= filter {
-                return Ok(());
-            }
-        }
+        let reports = reports
+            .into_iter()
+            .filter(|report| self.filter.is_none_or(|filter| report.status > filter))
+            .collect::<Vec<_>>();
 
-        let mut info = format!("{}/{}: {detected}/{total}", manifest.bucket, manifest.name,);
+        if self.format.is_structured() {
+            self.format.print(&reports)?;
+        } else if self.verbose {
+            let values = reports
+                .iter()
+                .map(serde_json::to_value)
+                .collect::<Result<Vec<_>, _>>()?;
 
-        if let SearchType::FileHash(hash) = manifest.search_type {
-            use std::fmt::Write;
+            let outputs = Structured::new(&values)
+                .with_priority("status", Priority::High)
+                .with_priority("detected", Priority::High)
+                .with_priority("lookup", Priority::Low)
+                .with_priority("link", Priority::Low)
+                .with_options(&self.table);
 
-            write!(
-                info,
-                ". See more at https://www.virustotal.com/gui/url/{hash}"
-            )?;
+            print!("{outputs}");
+        } else {
+            for report in &reports {
+                match report.status {
+                    Status::Malicious => eprintln_red!("{report}"),
+                    Status::Suspicious => eprintln_yellow!("{report}"),
+                    Status::Undetected => eprintln_green!("{report}"),
+                }
+            }
         }
 
-        match file_status {
-            Status::Malicious => eprintln_red!("{info}"),
-            Status::Suspicious => eprintln_yellow!("{info}"),
-            Status::Undetected => eprintln_green!("{info}"),
-        };
+        if exceeding > 0 {
+            abandon!(
+                "{exceeding} {} detected by more than {} engines",
+                if exceeding == 1 {
+                    "download was"
+                } else {
+                    "downloads were"
+                },
+                self.threshold.unwrap_or_default()
+            );
+        }
 
         Ok(())
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_status_ord() {
-        assert!(Status::Malicious > Status::Suspicious);
-        assert!(Status::Suspicious > Status::Undetected);
-    }
-}
@@ -0,0 +1,139 @@
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use clap_complete::engine::ArgValueCandidates;
+use schemars::JsonSchema;
+use serde::Serialize;
+use sprinkles::{contexts::ScoopContext, packages::InstallManifest};
+
+use crate::{
+    abandon,
+    commands::completions,
+    install::{self, shims},
+    output::{format::Format, structured::vertical::VTable},
+};
+
+use super::shim::list::owning_app;
+
+/// The extensions a binary may be given with, which shims are named without
+const BINARY_EXTENSIONS: &[&str] = &["exe", "com", "bat", "cmd", "ps1"];
+
+#[derive(Debug, Clone, Parser)]
+/// Find the app that provides a binary, and the file its shim runs
+///
+/// Binaries that aren't shimmed are looked up in `PATH` instead
+pub struct Args {
+    #[clap(
+        help = "The name of the binary",
+        add = ArgValueCandidates::new(completions::shims)
+    )]
+    binary: String,
+
+    #[clap(from_global)]
+    format: Format,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+/// The app that provides a binary
+pub(crate) struct Which {
+    /// The name of the binary
+    name: String,
+    /// The app that provides the binary, if it was installed by Scoop
+    app: Option<String>,
+    /// The installed version of the app
+    version: Option<String>,
+    /// The bucket the app was installed from
+    bucket: Option<String>,
+    /// The path the shim points to
+    target: String,
+    /// The file that is run, with the app's `current` junction resolved
+    path: String,
+}
+
+impl super::Command for Args {
+    async fn runner(self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        let name = strip_extension(&self.binary);
+
+        let which = if shims::exists(ctx, name) {
+            let Some(target) = shims::target(ctx, name) else {
+                abandon!("Could not read the target of the {name} shim");
+            };
+
+            let app = owning_app(&ctx.apps_path(), &target);
+            let app_dir = app.as_ref().map(|app| ctx.apps_path().join(app));
+
+            let version = app_dir.as_deref().and_then(installed_version);
+            let bucket = app_dir.as_deref().and_then(installed_bucket);
+
+            let path = match &app_dir {
+                Some(app_dir) => resolve_current(app_dir, &target),
+                None => target.clone(),
+            };
+
+            Which {
+                name: name.to_string(),
+                app,
+                version,
+                bucket,
+                target: target.display().to_string(),
+                path: path.display().to_string(),
+            }
+        } else {
+            let Ok(path) = ::which::which(&self.binary) else {
+                abandon!("Could not find {} in the shims or PATH", self.binary);
+            };
+
+            let app = owning_app(&ctx.apps_path(), &path);
+            let app_dir = app.as_ref().map(|app| ctx.apps_path().join(app));
+
+            Which {
+                name: name.to_string(),
+                version: app_dir.as_deref().and_then(installed_version),
+                bucket: app_dir.as_deref().and_then(installed_bucket),
+                app,
+                target: path.display().to_string(),
+                path: path.display().to_string(),
+            }
+        };
+
+        if self.format.is_structured() {
+            self.format.print(&which)?;
+        } else {
+            println!("{}", VTable::new(&which));
+        }
+
+        Ok(())
+    }
+}
+
+/// Remove a known binary extension from the name, as shims are named without them
+fn strip_extension(binary: &str) -> &str {
+    binary
+        .rsplit_once('.')
+        .filter(|(_, extension)| BINARY_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+        .map_or(binary, |(name, _)| name)
+}
+
+/// Read the installed version of an app
+fn installed_version(app_dir: &Path) -> Option<String> {
+    install::installed_manifest(app_dir)
+        .ok()
+        .map(|(manifest, _)| manifest.version.to_string())
+}
+
+/// Read the bucket an app was installed from
+fn installed_bucket(app_dir: &Path) -> Option<String> {
+    InstallManifest::from_path(app_dir.join("current").join("install.json"))
+        .ok()
+        .and_then(|install_manifest| install_manifest.bucket)
+}
+
+/// Replace the app's `current` junction in the path with the version directory it points to
+fn resolve_current(app_dir: &Path, target: &Path) -> PathBuf {
+    let current = app_dir.join("current");
+
+    match (target.strip_prefix(&current), install::read_link(&current)) {
+        (Ok(relative), Ok(version_dir)) => version_dir.join(relative),
+        _ => target.to_path_buf(),
+    }
+}
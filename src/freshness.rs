@@ -0,0 +1,98 @@
+//! Bringing the local bucket copies up to date before checking for outdated apps
+
+use itertools::Itertools;
+use rayon::prelude::*;
+use sprinkles::{
+    buckets::Bucket,
+    contexts::ScoopContext,
+    progress::{
+        Message, ProgressOptions,
+        indicatif::{MultiProgress, ProgressBar, ProgressFinish},
+        style,
+    },
+};
+
+use crate::{abandon, index::BucketIndex};
+
+/// Pull the latest changes for every bucket, in parallel
+///
+/// Each bucket gets its own progress bar. Buckets that fail to update are reported,
+/// but do not stop the others from updating, so outdated checks can still use what is available
+///
+/// # Errors
+/// - The buckets could not be listed
+pub fn refresh_buckets(ctx: &impl ScoopContext) -> anyhow::Result<()> {
+    if crate::is_offline() {
+        abandon!("Cannot fetch the latest bucket changes in offline mode");
+    }
+
+    let buckets = Bucket::list_all(ctx)?;
+
+    let longest_bucket_name = buckets
+        .iter()
+        .map(|bucket| bucket.name().len())
+        .max()
+        .unwrap_or(0);
+
+    let progress_style = style(Some(ProgressOptions::Hide), Some(Message::suffix()));
+    let mp = MultiProgress::new();
+
+    let buckets = buckets
+        .into_iter()
+        .map(|bucket| {
+            let pb = mp.add(
+                ProgressBar::new(1)
+                    .with_style(progress_style.clone())
+                    .with_message("Fetching latest changes")
+                    .with_prefix(format!("🪣 {:<longest_bucket_name$}", bucket.name()))
+                    .with_finish(ProgressFinish::AndClear),
+            );
+
+            (bucket, pb)
+        })
+        .collect_vec();
+
+    buckets.par_iter().for_each(|(bucket, pb)| {
+        let result = bucket.open_repo().and_then(|repo| {
+            if repo.outdated()? {
+                repo.pull(ctx, Some(&stats_callback(pb)))?;
+
+                if let Err(e) = BucketIndex::open(ctx, bucket) {
+                    warn!("Failed to update search index for {}: {e}", bucket.name());
+                }
+            }
+
+            Ok(())
+        });
+
+        if let Err(e) = result {
+            pb.println(format!("Failed to update {}: {e}", bucket.name()));
+        }
+
+        pb.finish();
+    });
+
+    Ok(())
+}
+
+/// Create a callback that reports the progress of a pull on the given progress bar
+pub fn stats_callback(
+    pb: &ProgressBar,
+) -> impl Fn(sprinkles::git::implementations::git2::Progress<'_>, bool) -> bool + '_ {
+    |stats, thin| {
+        if thin {
+            pb.set_position(stats.indexed_objects() as u64);
+            pb.set_length(stats.total_objects() as u64);
+        } else if stats.received_objects() == stats.total_objects() {
+            pb.set_position(stats.indexed_deltas() as u64);
+            pb.set_length(stats.total_deltas() as u64);
+            pb.set_message("Resolving deltas");
+        } else if stats.total_objects() > 0 {
+            pb.set_position(stats.received_objects() as u64);
+            pb.set_length(stats.total_objects() as u64);
+            pb.set_message("Receiving objects");
+        }
+
+        true
+    }
+}
@@ -0,0 +1,75 @@
+//! Approximate string matching, for finding apps despite typos in their names
+
+/// Score how closely the text matches the pattern, ignoring case
+///
+/// The text matches if the pattern is a subsequence of it (e.g. `vsc` in `vscode`),
+/// or if it is within a few typos of the pattern.
+/// Returns the edit distance between the two, so lower scores are closer matches,
+/// or `None` if the text does not match
+pub fn score(pattern: &str, text: &str) -> Option<usize> {
+    let pattern = pattern.to_lowercase().chars().collect::<Vec<_>>();
+    let text = text.to_lowercase().chars().collect::<Vec<_>>();
+
+    let distance = edit_distance(&pattern, &text);
+
+    // Allow roughly one typo for every three characters
+    let max_typos = (pattern.len() / 3).max(1);
+
+    (distance <= max_typos || is_subsequence(&pattern, &text)).then_some(distance)
+}
+
+/// Get the Levenshtein distance between two strings
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    // Only the previous row of the distance matrix is needed to compute the next
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a_char) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a_char != b_char);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(diagonal + 1).min(row[j] + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+/// Check if every character in the pattern appears in the text, in order
+fn is_subsequence(pattern: &[char], text: &[char]) -> bool {
+    let mut text = text.iter();
+
+    pattern.iter().all(|c| text.any(|t| t == c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_match() {
+        assert_eq!(score("nodejs", "nodejs"), Some(0));
+        assert_eq!(score("NodeJS", "nodejs"), Some(0));
+    }
+
+    #[test]
+    fn test_typos() {
+        assert_eq!(score("nodjes", "nodejs"), Some(2));
+        assert_eq!(score("pyhton", "python"), Some(2));
+        assert_eq!(score("firefix", "firefox"), Some(1));
+    }
+
+    #[test]
+    fn test_subsequence() {
+        assert_eq!(score("vsc", "vscode"), Some(3));
+        assert_eq!(score("ffx", "firefox"), Some(4));
+    }
+
+    #[test]
+    fn test_no_match() {
+        assert_eq!(score("python", "rust"), None);
+        assert_eq!(score("vscode", "vsc"), None);
+    }
+}
@@ -0,0 +1,111 @@
+//! Finding the versions a bucket has offered for an app, by walking the bucket's git history
+//!
+//! Useful for finding which version to downgrade to, and when it was available
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+use sprinkles::{contexts::ScoopContext, git::implementations::git2};
+
+use crate::wrappers::time::NicerTime;
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+/// A version of an app that a bucket has offered
+pub struct Version {
+    /// The version of the app
+    pub version: String,
+    /// The date of the commit that added the version to the bucket
+    #[schemars(with = "String")]
+    pub added: NicerTime<Local>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+/// The versions of an app that a bucket has offered
+pub struct AppHistory {
+    /// The name of the app
+    pub name: String,
+    /// The bucket the app is in
+    pub bucket: String,
+    /// The versions of the app, newest first
+    pub versions: Vec<Version>,
+}
+
+/// List the versions of an app in a bucket's history, newest first
+///
+/// Only the first parent of each commit is followed, so versions from merged branches are attributed to the merge
+///
+/// # Errors
+/// - The bucket is not a git repository
+/// - The bucket's history could not be read
+pub fn versions(
+    ctx: &impl ScoopContext,
+    bucket_name: &str,
+    app_name: &str,
+) -> anyhow::Result<Vec<Version>> {
+    let bucket_path = ctx.buckets_path().join(bucket_name);
+    let repo = git2::Repository::open(&bucket_path)?;
+    let manifest_path = manifest_path(&bucket_path, app_name);
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    revwalk.simplify_first_parent()?;
+
+    let mut versions: Vec<Version> = vec![];
+    let mut previous_blob = None;
+
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+
+        let Ok(entry) = commit.tree()?.get_path(&manifest_path) else {
+            // The manifest didn't exist yet, or was moved
+            continue;
+        };
+
+        let Some(added) = DateTime::from_timestamp(commit.time().seconds(), 0)
+            .map(|time| NicerTime::from(time.with_timezone(&Local)))
+        else {
+            continue;
+        };
+
+        // Most commits don't touch the manifest, so only parse it when it changes
+        if previous_blob == Some(entry.id()) {
+            if let Some(last) = versions.last_mut() {
+                last.added = added;
+            }
+            continue;
+        }
+
+        let Some(version) = repo
+            .find_blob(entry.id())
+            .ok()
+            .and_then(|blob| serde_json::from_slice::<Value>(blob.content()).ok())
+            .and_then(|manifest| manifest["version"].as_str().map(String::from))
+        else {
+            continue;
+        };
+
+        previous_blob = Some(entry.id());
+
+        // The history is walked newest first, so each older commit with the same version moves its date back
+        match versions.last_mut() {
+            Some(last) if last.version == version => last.added = added,
+            _ => versions.push(Version { version, added }),
+        }
+    }
+
+    Ok(versions)
+}
+
+/// Get the path of the app's manifest relative to the bucket, which may be in its `bucket` directory or its root
+fn manifest_path(bucket_path: &Path, app_name: &str) -> PathBuf {
+    let file_name = format!("{app_name}.json");
+
+    if bucket_path.join("bucket").is_dir() {
+        Path::new("bucket").join(file_name)
+    } else {
+        PathBuf::from(file_name)
+    }
+}
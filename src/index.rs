@@ -0,0 +1,377 @@
+//! An on-disk index of bucket manifests
+//!
+//! Each bucket's index is keyed by the bucket's git HEAD and the latest modified time of its manifests,
+//! so uncommitted changes to a bucket are picked up too.
+//! Each entry is keyed by its manifest's modified time,
+//! so only manifests that have changed since the index was last built are re-parsed.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sprinkles::{buckets::Bucket, contexts::ScoopContext};
+
+/// The version of the index format
+///
+/// Bump this whenever [`BucketIndex`] or [`Entry`] change, to force existing indexes to be rebuilt
+const FORMAT_VERSION: u32 = 6;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// The indexed fields of a single manifest
+pub struct Entry {
+    /// The version of the app
+    pub version: Option<String>,
+    /// The binaries and aliases of the app, across all architectures
+    pub bins: Vec<String>,
+    /// The description of the app
+    pub description: Option<String>,
+    /// The homepage of the app
+    pub homepage: Option<String>,
+    /// The notes shown after installing the app
+    pub notes: Option<String>,
+    /// The names of the app's start menu shortcuts, across all architectures
+    pub shortcuts: Vec<String>,
+    /// The directories the app adds to `PATH`, across all architectures
+    pub env_add_path: Vec<String>,
+    /// The modified time of the manifest when it was indexed
+    modified: u64,
+}
+
+impl Entry {
+    fn parse(path: &Path, modified: u64) -> anyhow::Result<Self> {
+        let manifest: Value = serde_json::from_slice(&std::fs::read(path)?)?;
+
+        let mut bins = vec![];
+        let mut shortcuts = vec![];
+        let mut env_add_path = vec![];
+
+        let architectures = match &manifest["architecture"] {
+            Value::Object(architectures) => architectures.values().collect(),
+            _ => vec![],
+        };
+
+        for config in std::iter::once(&manifest).chain(architectures) {
+            collect_bins(&config["bin"], &mut bins);
+            collect_shortcuts(&config["shortcuts"], &mut shortcuts);
+            collect_strings(&config["env_add_path"], &mut env_add_path);
+        }
+
+        for list in [&mut bins, &mut shortcuts, &mut env_add_path] {
+            list.sort();
+            list.dedup();
+        }
+
+        // Some manifests split long descriptions across multiple lines
+        let description = join_lines(&manifest["description"], " ");
+        let version = manifest["version"].as_str().map(String::from);
+        let homepage = manifest["homepage"].as_str().map(String::from);
+        let notes = join_lines(&manifest["notes"], "\n");
+
+        Ok(Self {
+            version,
+            bins,
+            description,
+            homepage,
+            notes,
+            shortcuts,
+            env_add_path,
+            modified,
+        })
+    }
+}
+
+/// Read a manifest field that can either be a single line or a list of lines
+fn join_lines(field: &Value, separator: &str) -> Option<String> {
+    match field {
+        Value::String(line) => Some(line.clone()),
+        Value::Array(lines) => Some(
+            lines
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(separator),
+        ),
+        _ => None,
+    }
+}
+
+/// Collect the strings from a manifest field that can either be a single string or a list of strings
+fn collect_strings(field: &Value, strings: &mut Vec<String>) {
+    match field {
+        Value::String(string) => strings.push(string.clone()),
+        Value::Array(entries) => {
+            strings.extend(entries.iter().filter_map(Value::as_str).map(String::from));
+        }
+        _ => {}
+    }
+}
+
+/// Collect the shortcut names from a manifest's `shortcuts` field
+fn collect_shortcuts(shortcuts: &Value, names: &mut Vec<String>) {
+    if let Value::Array(entries) = shortcuts {
+        // Each shortcut is the target path, followed by the shortcut name and optional arguments and icon
+        names.extend(
+            entries
+                .iter()
+                .filter_map(|entry| entry.get(1)?.as_str())
+                .map(String::from),
+        );
+    }
+}
+
+/// Collect the binary paths and aliases from a manifest's `bin` field
+fn collect_bins(bin: &Value, bins: &mut Vec<String>) {
+    match bin {
+        Value::String(path) => bins.push(path.clone()),
+        Value::Array(entries) => {
+            for entry in entries {
+                match entry {
+                    Value::String(path) => bins.push(path.clone()),
+                    // Only the path and alias are relevant, any further values are arguments
+                    Value::Array(parts) => bins.extend(
+                        parts
+                            .iter()
+                            .take(2)
+                            .filter_map(Value::as_str)
+                            .map(String::from),
+                    ),
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+/// The index of a single bucket
+pub struct BucketIndex {
+    format: u32,
+    head: Option<String>,
+    /// The latest modified time of the manifests and the directory containing them
+    ///
+    /// The directory's modified time changes when manifests are added or removed
+    modified: u64,
+    /// The indexed manifests, keyed by app name
+    pub entries: HashMap<String, Entry>,
+}
+
+impl BucketIndex {
+    /// Get the path the index for the given bucket is stored at
+    pub fn path(ctx: &impl ScoopContext, bucket_name: &str) -> PathBuf {
+        ctx.cache_path()
+            .join("sfsu-index")
+            .join(format!("{bucket_name}.json"))
+    }
+
+    /// Open the index for the given bucket
+    ///
+    /// If the bucket's git HEAD or any of its manifests have changed since the index was built,
+    /// the index is updated and saved before being returned
+    ///
+    /// # Errors
+    /// - Reading the bucket's manifests directory failed
+    /// - Saving the updated index failed
+    pub fn open(ctx: &impl ScoopContext, bucket: &Bucket) -> anyhow::Result<Self> {
+        let bucket_name = bucket.name();
+        let bucket_path = ctx.buckets_path().join(bucket_name.as_ref());
+        let index_path = Self::path(ctx, &bucket_name);
+        let head = head_commit(&bucket_path);
+
+        let manifests_path = manifests_path(&bucket_path);
+        let paths = manifest_paths(&manifests_path)?;
+        let modified = paths
+            .iter()
+            .map(|path| modified_secs(path))
+            .chain([modified_secs(&manifests_path)])
+            .max()
+            .unwrap_or_default();
+
+        let existing = std::fs::read(&index_path)
+            .ok()
+            .and_then(|contents| serde_json::from_slice::<Self>(&contents).ok())
+            .filter(|index| index.format == FORMAT_VERSION);
+
+        let previous = match existing {
+            Some(index) if head.is_some() && index.head == head && index.modified == modified => {
+                return Ok(index);
+            }
+            Some(index) => index.entries,
+            None => HashMap::new(),
+        };
+
+        debug!("Updating search index for {bucket_name}");
+
+        let index = Self {
+            format: FORMAT_VERSION,
+            head,
+            modified,
+            entries: Self::index_manifests(&paths, &previous),
+        };
+
+        if let Some(parent) = index_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&index_path, serde_json::to_vec(&index)?)?;
+
+        Ok(index)
+    }
+
+    /// Index all manifests in a bucket, reusing entries from the previous index where the manifest is unchanged
+    fn index_manifests(
+        paths: &[PathBuf],
+        previous: &HashMap<String, Entry>,
+    ) -> HashMap<String, Entry> {
+        paths
+            .par_iter()
+            .filter_map(|path| {
+                let name = path.file_stem()?.to_string_lossy().to_string();
+
+                let modified = modified_secs(path);
+
+                if let Some(entry) = previous
+                    .get(&name)
+                    .filter(|entry| entry.modified == modified)
+                {
+                    return Some((name, entry.clone()));
+                }
+
+                match Entry::parse(path, modified) {
+                    Ok(entry) => Some((name, entry)),
+                    Err(e) => {
+                        debug!("Failed to index {}: {e}", path.display());
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// List the names of apps whose entries match the given predicate
+    ///
+    /// Binaries are indexed across all architectures,
+    /// so the returned manifests should still be checked against the pattern
+    pub fn candidates(&self, predicate: impl Fn(&str, &Entry) -> bool) -> Vec<&str> {
+        self.entries
+            .iter()
+            .filter(|(name, entry)| predicate(name, entry))
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+}
+
+/// List the buckets that contain a manifest for the given app
+///
+/// # Errors
+/// - Listing the buckets failed
+/// - Opening a bucket's index failed
+pub fn buckets_containing(ctx: &impl ScoopContext, name: &str) -> anyhow::Result<Vec<Bucket>> {
+    let buckets = Bucket::list_all(ctx)?
+        .into_par_iter()
+        .map(|bucket| {
+            let index = BucketIndex::open(ctx, &bucket)?;
+
+            anyhow::Ok(index.entries.contains_key(name).then_some(bucket))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok(buckets.into_iter().flatten().collect())
+}
+
+/// Get the directory a bucket's manifests are in, which may be its `bucket` directory or its root
+pub(crate) fn manifests_path(bucket_path: &Path) -> PathBuf {
+    let nested = bucket_path.join("bucket");
+
+    if nested.is_dir() {
+        nested
+    } else {
+        bucket_path.to_path_buf()
+    }
+}
+
+/// List the manifests in a bucket's manifests directory
+pub(crate) fn manifest_paths(manifests_path: &Path) -> std::io::Result<Vec<PathBuf>> {
+    Ok(std::fs::read_dir(manifests_path)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .collect())
+}
+
+/// Get the modified time of a file or directory in seconds since the Unix epoch, or 0 if it can't be read
+fn modified_secs(path: &Path) -> u64 {
+    path.metadata()
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|modified| modified.as_secs())
+        .unwrap_or_default()
+}
+
+/// Read the commit a git repository's HEAD points to, without opening the repository
+fn head_commit(repo_path: &Path) -> Option<String> {
+    let git_dir = repo_path.join(".git");
+
+    let head = std::fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+
+    let Some(reference) = head.strip_prefix("ref: ") else {
+        // Detached HEAD
+        return Some(head.to_string());
+    };
+
+    if let Ok(commit) = std::fs::read_to_string(git_dir.join(reference)) {
+        return Some(commit.trim().to_string());
+    }
+
+    let packed_refs = std::fs::read_to_string(git_dir.join("packed-refs")).ok()?;
+
+    packed_refs.lines().find_map(|line| {
+        let (commit, name) = line.split_once(' ')?;
+
+        (name == reference).then(|| commit.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_bins() {
+        let manifest = serde_json::json!({
+            "bin": [
+                "app.exe",
+                ["tools/helper.exe", "helper", "--flag"],
+            ],
+        });
+
+        let mut bins = vec![];
+        collect_bins(&manifest["bin"], &mut bins);
+
+        assert_eq!(bins, ["app.exe", "tools/helper.exe", "helper"]);
+    }
+
+    #[test]
+    fn test_collect_shortcuts() {
+        let manifest = serde_json::json!({
+            "shortcuts": [
+                ["app.exe", "App"],
+                ["tools/helper.exe", "App Helper", "--flag", "icon.ico"],
+            ],
+        });
+
+        let mut shortcuts = vec![];
+        collect_shortcuts(&manifest["shortcuts"], &mut shortcuts);
+
+        assert_eq!(shortcuts, ["App", "App Helper"]);
+    }
+}
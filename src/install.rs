@@ -0,0 +1,357 @@
+//! Native app installation helpers
+//!
+//! These mirror the steps Scoop takes when installing an app.
+//!
+//! TODO: Move this into the sprinkles crate
+
+pub mod config;
+pub mod env;
+#[cfg(feature = "download")]
+pub mod extract;
+pub mod installer;
+pub mod persist;
+pub mod psmodule;
+pub mod scripts;
+pub mod shims;
+pub mod shortcuts;
+
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use quork::traits::truthy::ContainsTruth;
+use serde::Deserialize;
+#[cfg(feature = "download")]
+use sha2::{Digest, Sha256};
+use sprinkles::{
+    Architecture,
+    contexts::ScoopContext,
+    packages::{CreateManifest, InstallManifest, Manifest},
+};
+
+/// Create a directory junction at `link`, pointing to `target`
+///
+/// Junctions do not require elevation or developer mode, unlike symlinks
+///
+/// # Errors
+/// - Spawning `cmd` failed
+/// - `mklink` exited unsuccessfully
+pub fn junction(link: &Path, target: &Path) -> std::io::Result<()> {
+    let status = Command::new("cmd")
+        .arg("/c")
+        .arg("mklink")
+        .arg("/J")
+        .arg(link)
+        .arg(target)
+        .stdout(std::process::Stdio::null())
+        .status()?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!(
+            "Failed to create junction from {} to {}",
+            link.display(),
+            target.display()
+        )))
+    }
+}
+
+/// Point the app's `current` junction at the given version directory
+///
+/// Returns the path to the `current` directory
+///
+/// # Errors
+/// - Removing the existing junction failed
+/// - Creating the new junction failed
+pub fn link_current(app_dir: &Path, version_dir: &Path) -> std::io::Result<PathBuf> {
+    let current = app_dir.join("current");
+
+    // A broken junction doesn't "exist", but still has to be removed
+    if current.symlink_metadata().is_ok() {
+        // Removing a junction with `remove_dir` only removes the link, not the target
+        std::fs::remove_dir(&current)?;
+    }
+
+    junction(&current, version_dir)?;
+
+    Ok(current)
+}
+
+/// Read the target of a junction or symlink
+///
+/// Windows prefixes junction targets with `\\?\`, which is stripped so the target can be compared with other paths
+///
+/// # Errors
+/// - The path is not a link, or could not be read
+pub fn read_link(link: &Path) -> std::io::Result<PathBuf> {
+    let target = std::fs::read_link(link)?;
+
+    Ok(match target.to_string_lossy().strip_prefix(r"\\?\") {
+        Some(stripped) => PathBuf::from(stripped),
+        None => target,
+    })
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+/// The fields of `install.json` needed to inspect an installed app
+struct InstallInfo {
+    architecture: Option<Architecture>,
+    /// The version the app has been pinned to with `app pin`. Scoop ignores this field
+    pinned: Option<String>,
+}
+
+/// Read the manifest an app was installed from, and the architecture it was installed for
+///
+/// # Errors
+/// - The app's `manifest.json` could not be read or parsed
+pub fn installed_manifest(app_dir: &Path) -> anyhow::Result<(Manifest, Option<Architecture>)> {
+    let current_dir = app_dir.join("current");
+
+    let manifest = Manifest::from_path(current_dir.join("manifest.json"))?;
+
+    Ok((manifest, installed_architecture(app_dir)))
+}
+
+/// Read the architecture an app was installed for, if it was recorded
+pub fn installed_architecture(app_dir: &Path) -> Option<Architecture> {
+    version_architecture(&app_dir.join("current"))
+}
+
+/// Read the architecture a version of an app was installed for, if it was recorded
+pub fn version_architecture(version_dir: &Path) -> Option<Architecture> {
+    std::fs::read_to_string(version_dir.join("install.json"))
+        .ok()
+        .and_then(|contents| serde_json::from_str::<InstallInfo>(&contents).ok())
+        .and_then(|info| info.architecture)
+}
+
+/// Check if an installed app has been held with `scoop hold`
+pub fn is_held(app_dir: &Path) -> bool {
+    InstallManifest::from_path(app_dir.join("current").join("install.json"))
+        .is_ok_and(|install_manifest| install_manifest.hold.contains_truth())
+}
+
+/// Set whether an installed app is held, so that updates skip it
+///
+/// Returns whether the app's hold state changed
+///
+/// # Errors
+/// - The app's `install.json` could not be read, parsed or written
+pub fn set_held(app_dir: &Path, held: bool) -> anyhow::Result<bool> {
+    edit_install_info(app_dir, |install_info| {
+        let was_held = install_info
+            .get("hold")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or_default();
+
+        if was_held == held {
+            return false;
+        }
+
+        // Scoop removes the field rather than setting it to false
+        if held {
+            install_info.insert("hold".into(), true.into());
+        } else {
+            install_info.remove("hold");
+        }
+
+        true
+    })
+}
+
+/// Read the version an installed app has been pinned to, if any
+pub fn pinned_version(app_dir: &Path) -> Option<String> {
+    std::fs::read_to_string(app_dir.join("current").join("install.json"))
+        .ok()
+        .and_then(|contents| serde_json::from_str::<InstallInfo>(&contents).ok())
+        .and_then(|info| info.pinned)
+}
+
+/// Pin an installed app to a version, or unpin it if `version` is [`None`]
+///
+/// Returns whether the app's pinned version changed
+///
+/// # Errors
+/// - The app's `install.json` could not be read, parsed or written
+pub fn set_pinned(app_dir: &Path, version: Option<&str>) -> anyhow::Result<bool> {
+    edit_install_info(app_dir, |install_info| {
+        let pinned = install_info
+            .get("pinned")
+            .and_then(serde_json::Value::as_str);
+
+        if pinned == version {
+            return false;
+        }
+
+        match version {
+            Some(version) => install_info.insert("pinned".into(), version.into()),
+            None => install_info.remove("pinned"),
+        };
+
+        true
+    })
+}
+
+/// The fields of `install.json` that are set with `app hold` and `app pin`, rather than by the install
+const USER_FIELDS: [&str; 2] = ["hold", "pinned"];
+
+/// Read the fields of an installed app's `install.json` that were set with `app hold` and `app pin`
+///
+/// These are written into each new version's `install.json`, so that updating or switching versions keeps them
+pub fn user_install_info(app_dir: &Path) -> serde_json::Map<String, serde_json::Value> {
+    std::fs::read_to_string(app_dir.join("current").join("install.json"))
+        .ok()
+        .and_then(|contents| {
+            serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&contents).ok()
+        })
+        .map(|install_info| {
+            install_info
+                .into_iter()
+                .filter(|(field, _)| USER_FIELDS.contains(&field.as_str()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Replace the fields of an installed app's `install.json` that are set with `app hold` and `app pin`
+///
+/// Returns whether the app's `install.json` changed
+///
+/// # Errors
+/// - The app's `install.json` could not be read, parsed or written
+pub fn set_user_install_info(
+    app_dir: &Path,
+    user_info: &serde_json::Map<String, serde_json::Value>,
+) -> anyhow::Result<bool> {
+    edit_install_info(app_dir, |install_info| {
+        let mut changed = false;
+
+        for field in USER_FIELDS {
+            if install_info.get(field) == user_info.get(field) {
+                continue;
+            }
+
+            match user_info.get(field) {
+                Some(value) => install_info.insert(field.into(), value.clone()),
+                None => install_info.remove(field),
+            };
+            changed = true;
+        }
+
+        changed
+    })
+}
+
+/// Edit an installed app's `install.json`, writing it back if `edit` returns true
+///
+/// The fields sfsu doesn't know about are kept, and the file is replaced in one step so it is never left half written.
+/// Returns whether the file was changed
+fn edit_install_info(
+    app_dir: &Path,
+    edit: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>) -> bool,
+) -> anyhow::Result<bool> {
+    let path = app_dir.join("current").join("install.json");
+
+    let mut install_info: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+
+    if !edit(&mut install_info) {
+        return Ok(false);
+    }
+
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, serde_json::to_string_pretty(&install_info)?)?;
+    std::fs::rename(&temp_path, &path)?;
+
+    Ok(true)
+}
+
+/// Check that a manifest hash is well formed, with the right number of hex digits for its algorithm
+///
+/// Hashes without an algorithm prefix are SHA256, as in Scoop
+pub fn is_valid_hash(hash: &str) -> bool {
+    let (algorithm, digest) = hash.split_once(':').unwrap_or(("sha256", hash));
+
+    let length = match algorithm.to_lowercase().as_str() {
+        "md5" => 32,
+        "sha1" => 40,
+        "sha256" => 64,
+        "sha512" => 128,
+        "blake3" | "sha3" => 64,
+        _ => return false,
+    };
+
+    digest.len() == length && digest.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(feature = "download")]
+/// Get the path a downloaded url is cached at, matching Scoop's cache file naming
+///
+/// Prefers the legacy naming scheme (the url with special characters replaced) if such a file exists
+pub fn cache_file(ctx: &impl ScoopContext, app: &str, version: &str, url: &str) -> PathBuf {
+    let cache_path = ctx.cache_path();
+
+    let legacy = cache_path.join(format!("{app}#{version}#{}", underscore_url(url)));
+    if legacy.exists() {
+        return legacy;
+    }
+
+    let hash = format!("{:x}", Sha256::digest(url.as_bytes()));
+
+    cache_path.join(format!(
+        "{app}#{version}#{}{}",
+        &hash[..7],
+        url_extension(url)
+    ))
+}
+
+#[cfg(feature = "download")]
+/// Replace each run of characters other than word characters, `.` and `-` with an underscore
+fn underscore_url(url: &str) -> String {
+    let mut underscored = String::with_capacity(url.len());
+    let mut replacing = false;
+
+    for c in url.chars() {
+        if c.is_alphanumeric() || matches!(c, '_' | '.' | '-') {
+            underscored.push(c);
+            replacing = false;
+        } else if !replacing {
+            underscored.push('_');
+            replacing = true;
+        }
+    }
+
+    underscored
+}
+
+#[cfg(feature = "download")]
+/// Get the extension of a url, including the leading `.`, in the same way as .NET's `Path.GetExtension`
+fn url_extension(url: &str) -> &str {
+    let file_name = url.rsplit(['/', '\\']).next().unwrap_or(url);
+
+    match file_name.rfind('.') {
+        Some(index) if index + 1 < file_name.len() => &file_name[index..],
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_hash() {
+        assert!(is_valid_hash(&"a".repeat(64)));
+        assert!(is_valid_hash(&format!("sha1:{}", "B".repeat(40))));
+        assert!(is_valid_hash(&format!("sha512:{}", "0".repeat(128))));
+        assert!(is_valid_hash(&format!("blake3:{}", "f".repeat(64))));
+        assert!(is_valid_hash(&format!("sha3:{}", "f".repeat(64))));
+
+        assert!(!is_valid_hash(&"a".repeat(40)));
+        assert!(!is_valid_hash(&format!("sha256:{}", "g".repeat(64))));
+        assert!(!is_valid_hash(&format!("crc32:{}", "0".repeat(8))));
+    }
+}
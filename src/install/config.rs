@@ -0,0 +1,287 @@
+//! The subset of a manifest used when installing an app
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use sprinkles::{
+    Architecture,
+    packages::{Manifest, MergeDefaults},
+};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+/// A manifest field that can either be a single value or a list of values
+pub enum OneOrMany<T> {
+    /// A single value
+    One(T),
+    /// A list of values
+    Many(Vec<T>),
+}
+
+impl<T> OneOrMany<T> {
+    /// Convert into a list of values
+    pub fn into_vec(self) -> Vec<T> {
+        match self {
+            OneOrMany::One(value) => vec![value],
+            OneOrMany::Many(values) => values,
+        }
+    }
+
+    /// Get the value at the given index
+    pub fn get(&self, index: usize) -> Option<&T> {
+        match self {
+            OneOrMany::One(value) => (index == 0).then_some(value),
+            OneOrMany::Many(values) => values.get(index),
+        }
+    }
+}
+
+impl OneOrMany<String> {
+    /// Join the lines of a script into a single string
+    pub fn to_script(&self) -> Option<String> {
+        let script = match self {
+            OneOrMany::One(line) => line.clone(),
+            OneOrMany::Many(lines) => lines.join("\n"),
+        };
+
+        (!script.trim().is_empty()).then_some(script)
+    }
+}
+
+impl<T> Default for OneOrMany<T> {
+    fn default() -> Self {
+        OneOrMany::Many(vec![])
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+/// A single entry in the manifest's `bin` field
+pub enum BinEntry {
+    /// The path to the binary
+    Path(String),
+    /// The path to the binary, followed by an optional alias and arguments
+    Alias(Vec<String>),
+}
+
+/// A binary to create a shim for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bin {
+    /// The path to the binary, relative to the app directory
+    pub path: String,
+    /// The name of the shim
+    pub name: String,
+    /// Arguments to pass to the binary
+    pub args: Option<String>,
+}
+
+impl From<BinEntry> for Bin {
+    fn from(entry: BinEntry) -> Self {
+        let (path, alias, args) = match entry {
+            BinEntry::Path(path) => (path, None, None),
+            BinEntry::Alias(parts) => {
+                let mut parts = parts.into_iter();
+                let path = parts.next().unwrap_or_default();
+                let alias = parts.next().filter(|alias| !alias.is_empty());
+                let args = parts.collect::<Vec<_>>().join(" ");
+
+                (path, alias, (!args.is_empty()).then_some(args))
+            }
+        };
+
+        let name = alias.unwrap_or_else(|| {
+            Path::new(&path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.clone())
+        });
+
+        Self { path, name, args }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+/// A single entry in the manifest's `persist` field
+pub enum PersistEntry {
+    /// The path to persist, used for both the source and target
+    Path(String),
+    /// The path to persist, followed by an optional different name within the persist directory
+    Rename(Vec<String>),
+}
+
+/// A file or directory to persist between versions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Persist {
+    /// The path relative to the app directory
+    pub source: String,
+    /// The path relative to the persist directory
+    pub target: String,
+}
+
+impl From<PersistEntry> for Persist {
+    fn from(entry: PersistEntry) -> Self {
+        let (source, target) = match entry {
+            PersistEntry::Path(path) => (path, None),
+            PersistEntry::Rename(parts) => {
+                let mut parts = parts.into_iter();
+                let source = parts.next().unwrap_or_default();
+                let target = parts.next().filter(|target| !target.is_empty());
+
+                (source, target)
+            }
+        };
+
+        let source = source.trim_end_matches(['/', '\\']).to_string();
+        let target = target.unwrap_or_else(|| source.clone());
+
+        Self { source, target }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+/// The manifest's `installer` or `uninstaller` field
+pub struct Installer {
+    /// The program to run, relative to the app directory
+    pub file: Option<String>,
+    /// Arguments to pass to the program, which may reference script variables such as `$dir`
+    pub args: OneOrMany<String>,
+    /// Whether to keep the program after it has run
+    pub keep: bool,
+    /// The script to run
+    pub script: OneOrMany<String>,
+}
+
+impl Installer {
+    /// Check if the manifest asks for a program to be run
+    pub fn has_program(&self) -> bool {
+        self.file.is_some() || self.args.get(0).is_some()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+/// The manifest's `psmodule` field
+pub struct PsModule {
+    /// The name of the PowerShell module
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+/// Install configuration for a manifest, merged for a given architecture
+pub struct InstallConfig {
+    /// The urls to download
+    pub url: OneOrMany<String>,
+    /// The hashes of each download, optionally prefixed with the algorithm
+    pub hash: OneOrMany<String>,
+    /// The directories within each archive to extract
+    pub extract_dir: OneOrMany<String>,
+    /// The directories to extract each archive to
+    pub extract_to: OneOrMany<String>,
+    /// Whether the downloaded file is an Inno Setup installer
+    pub innosetup: bool,
+    /// The binaries to create shims for
+    pub bin: OneOrMany<BinEntry>,
+    /// Script run before the installer
+    pub pre_install: OneOrMany<String>,
+    /// The installer
+    pub installer: Option<Installer>,
+    /// Script run after the installer
+    pub post_install: OneOrMany<String>,
+    /// The files and directories to persist between versions
+    pub persist: OneOrMany<PersistEntry>,
+    /// Start menu shortcuts, as a target path and shortcut name, followed by optional arguments and icon
+    pub shortcuts: Vec<Vec<String>>,
+    /// Directories to add to `PATH`, relative to the app directory
+    pub env_add_path: OneOrMany<String>,
+    /// Environment variables to set, which may reference script variables such as `$dir`
+    pub env_set: BTreeMap<String, String>,
+    /// The PowerShell module the app provides
+    pub psmodule: Option<PsModule>,
+    /// Script run before the uninstaller
+    pub pre_uninstall: OneOrMany<String>,
+    /// The uninstaller
+    pub uninstaller: Option<Installer>,
+    /// Script run after the app has been removed
+    pub post_uninstall: OneOrMany<String>,
+    /// Notes to show the user after installing
+    pub notes: OneOrMany<String>,
+    /// Suggested apps, keyed by the feature they provide
+    pub suggest: BTreeMap<String, OneOrMany<String>>,
+}
+
+impl InstallConfig {
+    /// Parse the install config from a manifest, merged for the given architecture
+    ///
+    /// # Errors
+    /// - The manifest could not be serialized
+    /// - The manifest has fields with unexpected types
+    pub fn from_manifest(manifest: &Manifest, arch: Architecture) -> serde_json::Result<Self> {
+        let Value::Object(mut fields) = serde_json::to_value(manifest)? else {
+            return Ok(Self::default());
+        };
+        fields.remove("architecture");
+
+        let merged = serde_json::to_value(
+            manifest
+                .architecture
+                .merge_default(manifest.install_config.clone(), arch),
+        )?;
+
+        if let Value::Object(merged) = merged {
+            fields.extend(strip_nulls(merged));
+        }
+
+        serde_json::from_value(Value::Object(strip_nulls(fields)))
+    }
+
+    /// List the binaries to create shims for
+    pub fn bins(&self) -> Vec<Bin> {
+        self.bin
+            .clone()
+            .into_vec()
+            .into_iter()
+            .map(Bin::from)
+            .collect()
+    }
+
+    /// List the files and directories to persist
+    pub fn persist(&self) -> Vec<Persist> {
+        self.persist
+            .clone()
+            .into_vec()
+            .into_iter()
+            .map(Persist::from)
+            .collect()
+    }
+
+    /// List the names of the start menu shortcuts
+    pub fn shortcut_names(&self) -> Vec<&str> {
+        self.shortcuts
+            .iter()
+            .filter_map(|shortcut| shortcut.get(1))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Get the directory the archive at the given index should be extracted to
+    pub fn extract_to(&self, dir: &Path, index: usize) -> PathBuf {
+        match self.extract_to.get(index) {
+            Some(extract_to) => dir.join(extract_to),
+            None => dir.to_path_buf(),
+        }
+    }
+}
+
+/// Remove `null` fields, as they would otherwise fail to deserialize
+fn strip_nulls(fields: Map<String, Value>) -> Map<String, Value> {
+    fields
+        .into_iter()
+        .filter(|(_, value)| !value.is_null())
+        .collect()
+}
@@ -0,0 +1,179 @@
+//! Environment variables changed by apps, stored in the registry in the same way as Scoop
+
+use std::path::{Path, PathBuf};
+
+use winreg::{
+    RegKey, RegValue,
+    enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, KEY_READ, KEY_WRITE, REG_EXPAND_SZ},
+};
+
+/// Open the registry key holding the user's, or the system's environment variables if `global` is true
+fn environment_key(global: bool) -> std::io::Result<RegKey> {
+    if global {
+        RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey_with_flags(
+            r"SYSTEM\CurrentControlSet\Control\Session Manager\Environment",
+            KEY_READ | KEY_WRITE,
+        )
+    } else {
+        RegKey::predef(HKEY_CURRENT_USER)
+            .open_subkey_with_flags("Environment", KEY_READ | KEY_WRITE)
+    }
+}
+
+/// Write a string value, keeping the expandable type used by `PATH` so that any `%VARIABLES%` still work
+fn set_expand_string(key: &RegKey, name: &str, value: &str) -> std::io::Result<()> {
+    let bytes = value
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .flat_map(u16::to_le_bytes)
+        .collect::<Vec<_>>();
+
+    key.set_raw_value(
+        name,
+        &RegValue {
+            bytes: bytes.into(),
+            vtype: REG_EXPAND_SZ,
+        },
+    )
+}
+
+/// Normalize a `PATH` entry for comparison
+fn normalize(entry: &str) -> String {
+    entry.trim_end_matches(['/', '\\']).to_lowercase()
+}
+
+/// Add the given directories to the start of `PATH`, skipping any that are already in it
+///
+/// # Errors
+/// - Opening, reading or writing the registry key failed
+pub fn add_path(dirs: &[PathBuf], global: bool) -> std::io::Result<()> {
+    if dirs.is_empty() {
+        return Ok(());
+    }
+
+    let key = environment_key(global)?;
+    let path = key.get_value::<String, _>("Path").unwrap_or_default();
+
+    let existing = path.split(';').map(normalize).collect::<Vec<_>>();
+    let mut entries = dirs
+        .iter()
+        .map(|dir| dir.display().to_string())
+        .filter(|dir| !existing.contains(&normalize(dir)))
+        .collect::<Vec<_>>();
+
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    entries.extend(
+        path.split(';')
+            .filter(|entry| !entry.is_empty())
+            .map(String::from),
+    );
+
+    set_expand_string(&key, "Path", &entries.join(";"))
+}
+
+/// Set an environment variable
+///
+/// # Errors
+/// - Opening or writing the registry key failed
+pub fn set_var(name: &str, value: &str, global: bool) -> std::io::Result<()> {
+    environment_key(global)?.set_value(name, &value)
+}
+
+/// Remove an environment variable, if it is set
+///
+/// # Errors
+/// - Opening the registry key or deleting the value failed
+pub fn remove_var(name: &str, global: bool) -> std::io::Result<()> {
+    match environment_key(global)?.delete_value(name) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+/// List the entries in `PATH`, without expanding any `%VARIABLES%`
+///
+/// # Errors
+/// - Opening the registry key failed
+pub fn path_entries(global: bool) -> std::io::Result<Vec<String>> {
+    let path = environment_key(global)?
+        .get_value::<String, _>("Path")
+        .unwrap_or_default();
+
+    Ok(path
+        .split(';')
+        .filter(|entry| !entry.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Check if two `PATH` entries refer to the same directory, ignoring case and trailing slashes
+pub fn same_entry(a: &str, b: &str) -> bool {
+    normalize(a) == normalize(b)
+}
+
+/// Get the value of an environment variable, if it is set
+///
+/// # Errors
+/// - Opening the registry key failed
+pub fn get_var(name: &str, global: bool) -> std::io::Result<Option<String>> {
+    Ok(environment_key(global)?.get_value::<String, _>(name).ok())
+}
+
+/// Remove the given directories from `PATH`
+///
+/// Returns the directories that were removed
+///
+/// # Errors
+/// - Opening, reading or writing the registry key failed
+pub fn remove_path(dirs: &[PathBuf], global: bool) -> std::io::Result<Vec<PathBuf>> {
+    if dirs.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let key = environment_key(global)?;
+    let Ok(path) = key.get_value::<String, _>("Path") else {
+        return Ok(vec![]);
+    };
+
+    let dirs = dirs
+        .iter()
+        .map(|dir| (normalize(&dir.display().to_string()), dir))
+        .collect::<Vec<_>>();
+
+    let mut removed = vec![];
+    let entries = path
+        .split(';')
+        .filter(
+            |entry| match dirs.iter().find(|(dir, _)| *dir == normalize(entry)) {
+                Some((_, dir)) => {
+                    removed.push(dir.to_path_buf());
+                    false
+                }
+                None => true,
+            },
+        )
+        .collect::<Vec<_>>();
+
+    if !removed.is_empty() {
+        set_expand_string(&key, "Path", &entries.join(";"))?;
+    }
+
+    Ok(removed)
+}
+
+/// Resolve the paths an app adds to `PATH`, relative to its `current` directory
+pub fn resolve_paths(paths: &[String], current_dir: &Path) -> Vec<PathBuf> {
+    paths
+        .iter()
+        .map(|path| {
+            if path == "." || path.is_empty() {
+                current_dir.to_path_buf()
+            } else {
+                current_dir.join(path)
+            }
+        })
+        .collect()
+}
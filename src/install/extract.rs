@@ -0,0 +1,236 @@
+//! Archive extraction, using the same helper programs as Scoop
+
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::Context;
+
+/// Extensions that 7-Zip can extract
+const SEVEN_ZIP_EXTENSIONS: &[&str] = &[
+    "001", "7z", "bz", "bz2", "cab", "gz", "iso", "lzh", "lzma", "nupkg", "rar", "tar", "tbz",
+    "tbz2", "tgz", "tlz", "txz", "xz", "zip", "zst", "tzst",
+];
+
+/// Compressed extensions that usually contain a tarball
+const TARBALL_EXTENSIONS: &[&str] = &[
+    "bz", "bz2", "gz", "lzma", "tbz", "tbz2", "tgz", "tlz", "txz", "tzst", "xz", "zst",
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// The way a downloaded file is handled
+enum Kind {
+    /// An archive that 7-Zip can extract
+    SevenZip,
+    /// A Windows Installer package
+    Msi,
+    /// An Inno Setup installer
+    InnoSetup,
+    /// A file that is copied as is
+    Raw,
+}
+
+impl Kind {
+    fn new(file_name: &str, innosetup: bool) -> Self {
+        if innosetup {
+            return Kind::InnoSetup;
+        }
+
+        let extension = extension(file_name);
+
+        if extension == "msi" {
+            Kind::Msi
+        } else if SEVEN_ZIP_EXTENSIONS.contains(&extension.as_str()) {
+            Kind::SevenZip
+        } else {
+            Kind::Raw
+        }
+    }
+}
+
+fn extension(file_name: &str) -> String {
+    Path::new(file_name)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Get the name of the file a url points to
+///
+/// Respects Scoop's `#/file.ext` url fragment for renaming downloads
+pub fn url_file_name(url: &str) -> String {
+    if let Some(name) = url
+        .split_once('#')
+        .and_then(|(_, fragment)| fragment.strip_prefix('/'))
+    {
+        return name.to_string();
+    }
+
+    let url = url.split(['#', '?']).next().unwrap_or(url);
+
+    url.rsplit('/').next().unwrap_or(url).to_string()
+}
+
+/// Check whether a downloaded file is copied into the app directory as is, rather than extracted or installed
+pub fn is_copied(url: &str, innosetup: bool) -> bool {
+    Kind::new(&url_file_name(url), innosetup) == Kind::Raw
+}
+
+/// Extract a downloaded file into the target directory
+///
+/// If `extract_dir` is provided, only the contents of that directory within the archive are kept
+///
+/// # Errors
+/// - A required helper program is missing
+/// - The helper program failed
+/// - Moving the extracted files failed
+pub fn extract(
+    file: &Path,
+    url: &str,
+    target: &Path,
+    extract_dir: Option<&str>,
+    innosetup: bool,
+) -> anyhow::Result<()> {
+    let file_name = url_file_name(url);
+    let kind = Kind::new(&file_name, innosetup);
+
+    std::fs::create_dir_all(target)?;
+
+    if kind == Kind::Raw {
+        std::fs::copy(file, target.join(&file_name))?;
+        return Ok(());
+    }
+
+    let output_dir = match extract_dir {
+        Some(_) => target.join(format!("_tmp_{file_name}")),
+        None => target.to_path_buf(),
+    };
+
+    match kind {
+        Kind::SevenZip => {
+            seven_zip(file, &output_dir)?;
+
+            if TARBALL_EXTENSIONS.contains(&extension(&file_name).as_str()) {
+                extract_tarballs(&output_dir)?;
+            }
+        }
+        Kind::Msi => run(Command::new("msiexec")
+            .arg("/a")
+            .arg(file)
+            .arg("/qn")
+            .arg(format!("TARGETDIR={}", output_dir.display())))?,
+        Kind::InnoSetup => run(Command::new(helper("innounp", "innounp")?)
+            .arg("-x")
+            .arg(format!("-d{}", output_dir.display()))
+            .arg("-c{app}")
+            .arg("-y")
+            .arg(file))?,
+        Kind::Raw => unreachable!(),
+    }
+
+    if let Some(extract_dir) = extract_dir {
+        let source = output_dir.join(extract_dir);
+
+        if !source.exists() {
+            anyhow::bail!("Could not find '{extract_dir}' in {file_name}");
+        }
+
+        move_contents(&source, target)?;
+        std::fs::remove_dir_all(&output_dir)?;
+    }
+
+    Ok(())
+}
+
+fn seven_zip(file: &Path, output_dir: &Path) -> anyhow::Result<()> {
+    run(Command::new(helper("7z", "7zip")?)
+        .arg("x")
+        .arg(file)
+        .arg(format!("-o{}", output_dir.display()))
+        .arg("-y"))
+}
+
+/// Extract any tarballs left behind after decompressing a file
+fn extract_tarballs(dir: &Path) -> anyhow::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.extension().is_some_and(|extension| extension == "tar") {
+            seven_zip(&path, dir)?;
+            std::fs::remove_file(path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Move the contents of one directory into another, merging existing directories
+fn move_contents(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let destination = to.join(entry.file_name());
+
+        if destination.is_dir() && entry.file_type()?.is_dir() {
+            move_contents(&entry.path(), &destination)?;
+        } else {
+            std::fs::rename(entry.path(), destination)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Find a helper program in the user's path
+fn helper(exe: &str, package: &str) -> anyhow::Result<PathBuf> {
+    which::which(exe).with_context(|| {
+        format!("Missing helper: {exe}. Install it with `scoop install {package}`")
+    })
+}
+
+fn run(command: &mut Command) -> anyhow::Result<()> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let output = command.output()?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "{program} exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_file_name() {
+        assert_eq!(
+            url_file_name("https://example.com/app-1.0.zip"),
+            "app-1.0.zip"
+        );
+        assert_eq!(
+            url_file_name("https://example.com/download?version=1.0#/app.7z"),
+            "app.7z"
+        );
+        assert_eq!(
+            url_file_name("https://example.com/app.exe?raw=true"),
+            "app.exe"
+        );
+    }
+
+    #[test]
+    fn test_kind() {
+        assert_eq!(Kind::new("app.zip", false), Kind::SevenZip);
+        assert_eq!(Kind::new("app.tar.gz", false), Kind::SevenZip);
+        assert_eq!(Kind::new("app.msi", false), Kind::Msi);
+        assert_eq!(Kind::new("setup.exe", true), Kind::InnoSetup);
+        assert_eq!(Kind::new("app.exe", false), Kind::Raw);
+    }
+}
@@ -0,0 +1,74 @@
+//! Running the installer and uninstaller programs listed in manifests
+
+use std::{path::Path, process::Command};
+
+use super::{
+    config::Installer,
+    scripts::{self, ScriptVariables},
+};
+
+/// Run the program of a manifest's `installer` or `uninstaller`
+///
+/// The program is looked up in the app directory, falling back to `default_file` if the manifest does not name one.
+/// PowerShell scripts are run with PowerShell, and anything else is run directly.
+/// Programs that are not marked to be kept are deleted once they have run successfully
+///
+/// # Errors
+/// - The program could not be found
+/// - Spawning the program failed
+/// - The program exited unsuccessfully
+pub fn run(
+    name: &str,
+    installer: &Installer,
+    default_file: Option<&str>,
+    variables: &ScriptVariables<'_>,
+) -> anyhow::Result<()> {
+    if !installer.has_program() {
+        return Ok(());
+    }
+
+    let Some(file) = installer.file.as_deref().or(default_file) else {
+        anyhow::bail!("{name} for {} does not specify a file", variables.app);
+    };
+
+    let path = variables.dir.join(variables.substitute(file));
+    if !path.exists() {
+        anyhow::bail!(
+            "Could not find {name} for {} at {}",
+            variables.app,
+            path.display()
+        );
+    }
+
+    let args = installer
+        .args
+        .clone()
+        .into_vec()
+        .iter()
+        .map(|arg| variables.substitute(arg))
+        .collect::<Vec<_>>();
+
+    debug!("Running {name} {} {}", path.display(), args.join(" "));
+
+    let mut command = if is_powershell(&path) {
+        scripts::powershell_file(&path)
+    } else {
+        Command::new(&path)
+    };
+
+    let status = command.args(&args).current_dir(variables.dir).status()?;
+    if !status.success() {
+        anyhow::bail!("{name} for {} failed with {status}", variables.app);
+    }
+
+    if !installer.keep {
+        std::fs::remove_file(&path)?;
+    }
+
+    Ok(())
+}
+
+fn is_powershell(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("ps1"))
+}
@@ -0,0 +1,74 @@
+//! Persisting data between app versions, in the same way as Scoop
+
+use std::path::Path;
+
+use super::config::Persist;
+
+/// Link each persisted file or directory in the app directory to the persist directory
+///
+/// Existing persisted data takes precedence over the files shipped with the app,
+/// which are kept alongside with an `.original` extension
+///
+/// # Errors
+/// - Moving or creating the persisted data failed
+/// - Linking the persisted data failed
+pub fn link(persist: &[Persist], app_dir: &Path, persist_dir: &Path) -> anyhow::Result<()> {
+    for entry in persist {
+        let source = app_dir.join(&entry.source);
+        let target = persist_dir.join(&entry.target);
+
+        debug!("Persisting {}", source.display());
+
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        if target.exists() {
+            if source.exists() {
+                let mut original = source.clone().into_os_string();
+                original.push(".original");
+
+                std::fs::rename(&source, original)?;
+            }
+        } else if source.exists() {
+            std::fs::rename(&source, &target)?;
+        } else {
+            std::fs::create_dir_all(&target)?;
+        }
+
+        if let Some(parent) = source.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        if target.is_dir() {
+            super::junction(&source, &target)?;
+        } else {
+            std::fs::hard_link(&target, &source)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove the links to persisted data from an app version directory
+///
+/// The persisted data itself is left in place, so it can be linked into the next version
+///
+/// # Errors
+/// - Removing a link failed
+pub fn unlink(persist: &[Persist], app_dir: &Path) -> std::io::Result<()> {
+    for entry in persist {
+        let source = app_dir.join(&entry.source);
+
+        debug!("Unlinking persisted {}", source.display());
+
+        if source.is_dir() {
+            // Removing a junction with `remove_dir` only removes the link, not the target
+            std::fs::remove_dir(&source)?;
+        } else if source.exists() {
+            std::fs::remove_file(&source)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+//! PowerShell modules, linked into Scoop's modules directory
+
+use std::path::{Path, PathBuf};
+
+use sprinkles::contexts::ScoopContext;
+
+/// Get the directory Scoop links PowerShell modules into
+pub fn modules_dir(ctx: &impl ScoopContext) -> PathBuf {
+    ctx.path().join("modules")
+}
+
+/// Link a PowerShell module into Scoop's modules directory, replacing any existing link
+///
+/// # Errors
+/// - Creating the modules directory failed
+/// - Removing the existing link failed
+/// - Creating the junction failed
+pub fn link(ctx: &impl ScoopContext, name: &str, current_dir: &Path) -> std::io::Result<()> {
+    let modules_dir = modules_dir(ctx);
+    std::fs::create_dir_all(&modules_dir)?;
+
+    remove(ctx, name)?;
+
+    super::junction(&modules_dir.join(name), current_dir)
+}
+
+/// Remove the link to a PowerShell module, if it exists
+///
+/// # Errors
+/// - Removing the link failed
+pub fn remove(ctx: &impl ScoopContext, name: &str) -> std::io::Result<()> {
+    let module = modules_dir(ctx).join(name);
+
+    if module.is_symlink() || module.exists() {
+        // Removing a junction with `remove_dir` only removes the link, not the target
+        std::fs::remove_dir(&module).or_else(|_| std::fs::remove_dir_all(&module))?;
+    }
+
+    Ok(())
+}
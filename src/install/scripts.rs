@@ -0,0 +1,173 @@
+//! Running manifest scripts with PowerShell
+
+use std::{
+    fmt::{Display, Write as _},
+    path::Path,
+    process::Command,
+};
+
+use sprinkles::contexts::ScoopContext;
+
+/// Scoop's libraries that manifest scripts commonly rely on
+const SCOOP_LIBS: &[&str] = &[
+    "core",
+    "manifest",
+    "decompress",
+    "install",
+    "shortcuts",
+    "psmodules",
+    "system",
+];
+
+/// Variables exposed to manifest scripts, matching the ones Scoop provides
+pub struct ScriptVariables<'a> {
+    /// The name of the app
+    pub app: &'a str,
+    /// The version of the app
+    pub version: &'a str,
+    /// The directory the app is installed to
+    pub dir: &'a Path,
+    /// The version directory of the app, even after `current` has been linked
+    pub original_dir: &'a Path,
+    /// The persist directory of the app
+    pub persist_dir: &'a Path,
+    /// The architecture being installed
+    pub architecture: String,
+    /// Whether the app is installed globally
+    pub global: bool,
+    /// The command being run (i.e. `install` or `uninstall`)
+    pub cmd: &'a str,
+}
+
+impl ScriptVariables<'_> {
+    fn preamble(&self) -> String {
+        let mut preamble = String::new();
+
+        let variables: [(&str, &dyn Display); 7] = [
+            ("app", &self.app),
+            ("version", &self.version),
+            ("dir", &self.dir.display()),
+            ("original_dir", &self.original_dir.display()),
+            ("persist_dir", &self.persist_dir.display()),
+            ("architecture", &self.architecture),
+            ("cmd", &self.cmd),
+        ];
+
+        for (name, value) in variables {
+            _ = writeln!(preamble, "${name} = {}", quote(value));
+        }
+
+        _ = writeln!(
+            preamble,
+            "$global = ${}",
+            if self.global { "true" } else { "false" }
+        );
+
+        preamble
+    }
+
+    /// Replace references to the variables in a manifest value, such as `$dir\bin` in `env_set`
+    pub fn substitute(&self, value: &str) -> String {
+        let variables: [(&str, String); 8] = [
+            ("original_dir", self.original_dir.display().to_string()),
+            ("persist_dir", self.persist_dir.display().to_string()),
+            ("architecture", self.architecture.clone()),
+            ("version", self.version.to_string()),
+            ("global", self.global.to_string()),
+            ("app", self.app.to_string()),
+            ("dir", self.dir.display().to_string()),
+            ("cmd", self.cmd.to_string()),
+        ];
+
+        variables
+            .iter()
+            .fold(value.to_string(), |value, (name, replacement)| {
+                value.replace(&format!("${name}"), replacement)
+            })
+    }
+}
+
+/// Quote a value as a PowerShell string literal
+pub(super) fn quote(value: impl Display) -> String {
+    format!("'{}'", value.to_string().replace('\'', "''"))
+}
+
+/// Run a manifest script
+///
+/// The script is run in a fresh PowerShell process, with Scoop's libraries loaded if they are installed
+///
+/// # Errors
+/// - Writing the script to a temporary file failed
+/// - Spawning PowerShell failed
+/// - The script exited unsuccessfully
+pub fn run(
+    ctx: &impl ScoopContext,
+    name: &str,
+    script: &str,
+    variables: &ScriptVariables<'_>,
+) -> anyhow::Result<()> {
+    let mut contents = String::from("$ErrorActionPreference = 'Stop'\n");
+
+    let libs_dir = ctx.apps_path().join("scoop").join("current").join("lib");
+    for lib in SCOOP_LIBS {
+        let lib_path = libs_dir.join(format!("{lib}.ps1"));
+
+        if lib_path.exists() {
+            writeln!(contents, ". {}", quote(lib_path.display()))?;
+        }
+    }
+
+    contents += &variables.preamble();
+    contents += script;
+
+    let script_path = std::env::temp_dir().join(format!("sfsu-{}-{name}.ps1", variables.app));
+    std::fs::write(&script_path, contents)?;
+
+    debug!("Running {name} script for {}", variables.app);
+
+    let status = powershell_file(&script_path).status();
+
+    _ = std::fs::remove_file(&script_path);
+
+    let status = status?;
+    if !status.success() {
+        anyhow::bail!("{name} script for {} failed with {status}", variables.app);
+    }
+
+    Ok(())
+}
+
+/// Create a command that runs the PowerShell script at the given path
+pub fn powershell_file(path: &Path) -> Command {
+    let mut command = Command::new(powershell());
+
+    command
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-ExecutionPolicy",
+            "Bypass",
+            "-File",
+        ])
+        .arg(path);
+
+    command
+}
+
+/// Create a command that runs the given PowerShell commands
+pub fn powershell_command(script: &str) -> Command {
+    let mut command = Command::new(powershell());
+
+    command.args(["-NoProfile", "-NonInteractive", "-Command", script]);
+
+    command
+}
+
+/// Prefer PowerShell Core if it is installed
+fn powershell() -> &'static str {
+    if which::which("pwsh").is_ok() {
+        "pwsh"
+    } else {
+        "powershell"
+    }
+}
@@ -0,0 +1,364 @@
+//! Shim creation, compatible with the shims Scoop creates
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use sprinkles::contexts::ScoopContext;
+
+use super::config::Bin;
+
+/// Get the directory shims are stored in
+pub fn shims_dir(ctx: &impl ScoopContext) -> PathBuf {
+    ctx.path().join("shims")
+}
+
+/// The extensions of files that make up a shim, alongside the extensionless shell script
+const SHIM_EXTENSIONS: &[&str] = &["exe", "shim", "ps1", "cmd"];
+
+/// A shim for a binary
+pub struct Shim {
+    /// The name of the shim
+    pub name: String,
+    /// The binary the shim points to
+    pub target: PathBuf,
+    /// Arguments to pass to the binary
+    pub args: Option<String>,
+}
+
+impl Shim {
+    /// Create a shim description for a binary within the given app directory
+    pub fn new(bin: &Bin, app_dir: &Path) -> Self {
+        Self {
+            name: bin.name.clone(),
+            target: app_dir.join(&bin.path),
+            args: bin.args.clone(),
+        }
+    }
+
+    /// Write the shim to the shims directory
+    ///
+    /// # Errors
+    /// - The shim target does not exist
+    /// - Scoop's shim executable could not be found
+    /// - Writing the shim files failed
+    pub fn create(&self, ctx: &impl ScoopContext) -> anyhow::Result<()> {
+        if !self.target.exists() {
+            anyhow::bail!("Could not find binary {}", self.target.display());
+        }
+
+        let shims_dir = shims_dir(ctx);
+        std::fs::create_dir_all(&shims_dir)?;
+
+        let base = shims_dir.join(&self.name);
+        let with_extension = |extension: &str| shims_dir.join(format!("{}.{extension}", self.name));
+        let target = self.target.display();
+        let args = self.args.as_deref().unwrap_or_default();
+
+        let extension = self
+            .target
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        match extension.as_str() {
+            "exe" | "com" => {
+                let shim_exe = ctx
+                    .apps_path()
+                    .join("scoop")
+                    .join("current")
+                    .join("supporting")
+                    .join("shims")
+                    .join("kiennq")
+                    .join("shim.exe");
+
+                std::fs::copy(&shim_exe, with_extension("exe")).with_context(|| {
+                    format!("Could not copy Scoop's shim from {}", shim_exe.display())
+                })?;
+
+                let mut shim_file = format!("path = \"{target}\"\n");
+                if let Some(args) = &self.args {
+                    shim_file += &format!("args = {args}\n");
+                }
+
+                std::fs::write(with_extension("shim"), shim_file)?;
+            }
+            "ps1" => {
+                std::fs::write(
+                    with_extension("ps1"),
+                    format!(
+                        "# {target}\n\
+                        $path = \"{target}\"\n\
+                        if ($MyInvocation.ExpectingInput) {{ $input | & $path {args} @args }} else {{ & $path {args} @args }}\n\
+                        exit $LASTEXITCODE\n"
+                    ),
+                )?;
+
+                std::fs::write(
+                    with_extension("cmd"),
+                    format!(
+                        "@rem {target}\r\n\
+                        @echo off\r\n\
+                        where /q pwsh.exe\r\n\
+                        if %errorlevel% equ 0 (\r\n\
+                        \x20   pwsh -noprofile -ex unrestricted -file \"{target}\" {args} %*\r\n\
+                        ) else (\r\n\
+                        \x20   powershell -noprofile -ex unrestricted -file \"{target}\" {args} %*\r\n\
+                        )\r\n"
+                    ),
+                )?;
+
+                std::fs::write(
+                    &base,
+                    format!(
+                        "#!/bin/sh\n\
+                        # {target}\n\
+                        if command -v pwsh.exe > /dev/null 2>&1; then\n\
+                        \x20   pwsh.exe -noprofile -ex unrestricted -file \"{target}\" {args} \"$@\"\n\
+                        else\n\
+                        \x20   powershell.exe -noprofile -ex unrestricted -file \"{target}\" {args} \"$@\"\n\
+                        fi\n"
+                    ),
+                )?;
+            }
+            _ => {
+                std::fs::write(
+                    with_extension("cmd"),
+                    format!("@rem {target}\r\n@\"{target}\" {args} %*\r\n"),
+                )?;
+
+                std::fs::write(
+                    &base,
+                    format!(
+                        "#!/bin/sh\n\
+                        # {target}\n\
+                        MSYS2_ARG_CONV_EXCL=/C cmd.exe /C \"{target}\" {args} \"$@\"\n"
+                    ),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Join arguments into a single command line for a shim, quoting those that contain whitespace or quotes
+///
+/// Quotes and the backslashes before them are escaped the way Windows programs split their command lines
+pub fn join_args(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| quote_arg(arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains(|c: char| c.is_whitespace() || c == '"') {
+        return arg.to_string();
+    }
+
+    let mut quoted = String::from('"');
+    let mut backslashes = 0;
+
+    for c in arg.chars() {
+        if c == '\\' {
+            backslashes += 1;
+            continue;
+        }
+
+        // Backslashes are only escapes when they come before a quote
+        let escapes = if c == '"' {
+            backslashes * 2 + 1
+        } else {
+            backslashes
+        };
+        quoted.push_str(&"\\".repeat(escapes));
+        quoted.push(c);
+        backslashes = 0;
+    }
+
+    // The closing quote would otherwise be escaped by any trailing backslashes
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+
+    quoted
+}
+
+/// List the files that make up the shim with the given name
+pub fn shim_files(ctx: &impl ScoopContext, name: &str) -> Vec<PathBuf> {
+    let shims_dir = shims_dir(ctx);
+
+    let mut files = SHIM_EXTENSIONS
+        .iter()
+        .map(|extension| shims_dir.join(format!("{name}.{extension}")))
+        .collect::<Vec<_>>();
+    files.push(shims_dir.join(name));
+
+    files
+}
+
+/// List the names of all shims in the shims directory
+///
+/// # Errors
+/// - Reading the shims directory failed
+pub fn list(ctx: &impl ScoopContext) -> std::io::Result<Vec<String>> {
+    let shims_dir = shims_dir(ctx);
+
+    if !shims_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut names = std::fs::read_dir(shims_dir)?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_file()))
+        .map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+
+            match file_name.rsplit_once('.') {
+                Some((name, extension)) if SHIM_EXTENSIONS.contains(&extension) => name.to_string(),
+                _ => file_name,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    names.sort_unstable_by_key(|name| name.to_lowercase());
+    names.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+
+    Ok(names)
+}
+
+/// Check if a shim with the given name exists
+pub fn exists(ctx: &impl ScoopContext, name: &str) -> bool {
+    shim_files(ctx, name).iter().any(|file| file.exists())
+}
+
+/// Read the binary the shim with the given name points to
+///
+/// The target is read from the `.shim` file if there is one,
+/// otherwise from the comment Scoop writes at the top of the script shims
+pub fn target(ctx: &impl ScoopContext, name: &str) -> Option<PathBuf> {
+    let shims_dir = shims_dir(ctx);
+
+    if let Ok(contents) = std::fs::read_to_string(shims_dir.join(format!("{name}.shim"))) {
+        return contents
+            .lines()
+            .find_map(|line| line.strip_prefix("path = "))
+            .map(|path| PathBuf::from(path.trim().trim_matches('"')));
+    }
+
+    [("cmd", "@rem "), ("ps1", "# ")]
+        .iter()
+        .filter_map(|(extension, prefix)| {
+            let contents =
+                std::fs::read_to_string(shims_dir.join(format!("{name}.{extension}"))).ok()?;
+
+            contents
+                .lines()
+                .next()?
+                .strip_prefix(prefix)
+                .map(|target| PathBuf::from(target.trim()))
+        })
+        .next()
+}
+
+/// Read the arguments the shim with the given name always passes to its binary, if any
+///
+/// Only `.shim` files store their arguments separately, so script shims have none
+pub fn args(ctx: &impl ScoopContext, name: &str) -> Option<String> {
+    let contents = std::fs::read_to_string(shims_dir(ctx).join(format!("{name}.shim"))).ok()?;
+
+    contents
+        .lines()
+        .find_map(|line| line.strip_prefix("args = "))
+        .map(|args| args.trim().to_string())
+}
+
+/// Check if the shim with the given name points into `app_dir`
+pub fn belongs_to(ctx: &impl ScoopContext, name: &str, app_dir: &Path) -> bool {
+    let app_dir = app_dir.display().to_string().to_lowercase();
+
+    shim_files(ctx, name)
+        .iter()
+        .filter(|file| file.extension().is_none_or(|extension| extension != "exe"))
+        .filter_map(|file| std::fs::read_to_string(file).ok())
+        .any(|contents| contains_dir(&contents.to_lowercase(), &app_dir))
+}
+
+/// Check if the text contains a path within `dir`
+///
+/// The directory must be followed by a separator, so `apps\python` does not match paths in `apps\python310`
+fn contains_dir(text: &str, dir: &str) -> bool {
+    let dir = dir.trim_end_matches(['\\', '/']);
+
+    text.match_indices(dir)
+        .any(|(index, _)| text[index + dir.len()..].starts_with(['\\', '/']))
+}
+
+/// Remove all files belonging to the shim with the given name
+///
+/// Returns `false` without removing anything if the shim does not point into `app_dir`,
+/// as it then belongs to another app
+///
+/// # Errors
+/// - Removing the shim files failed
+pub fn remove(ctx: &impl ScoopContext, name: &str, app_dir: &Path) -> std::io::Result<bool> {
+    if !belongs_to(ctx, name, app_dir) {
+        return Ok(false);
+    }
+
+    remove_any(ctx, name)?;
+
+    Ok(true)
+}
+
+/// Remove all files belonging to the shim with the given name, regardless of which app it belongs to
+///
+/// # Errors
+/// - Removing the shim files failed
+pub fn remove_any(ctx: &impl ScoopContext, name: &str) -> std::io::Result<()> {
+    for file in shim_files(ctx, name) {
+        if file.exists() {
+            std::fs::remove_file(file)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_args() {
+        let args = [
+            "--flag".to_string(),
+            "two words".to_string(),
+            r#"say "hi""#.to_string(),
+            r"C:\Program Files\".to_string(),
+            String::new(),
+        ];
+
+        assert_eq!(
+            join_args(&args),
+            r#"--flag "two words" "say \"hi\"" "C:\Program Files\\" """#
+        );
+    }
+
+    #[test]
+    fn test_contains_dir() {
+        let dir = r"c:\scoop\apps\python";
+
+        assert!(contains_dir(
+            r#"path = "c:\scoop\apps\python\current\python.exe""#,
+            dir
+        ));
+        assert!(!contains_dir(
+            r#"path = "c:\scoop\apps\python310\current\python.exe""#,
+            dir
+        ));
+        assert!(contains_dir(
+            r"c:\scoop\apps\python310\current\a.exe c:\scoop\apps\python\current\b.exe",
+            dir
+        ));
+    }
+}
@@ -0,0 +1,102 @@
+//! Start menu shortcuts, stored in the same place as Scoop's
+
+use std::{fmt::Write as _, path::PathBuf};
+
+use super::scripts::{self, ScriptVariables, quote};
+
+/// Get the directory Scoop's start menu shortcuts are stored in
+///
+/// # Errors
+/// - The `APPDATA` or `ProgramData` environment variable is missing
+pub fn shortcuts_dir(global: bool) -> anyhow::Result<PathBuf> {
+    let base = std::env::var_os(if global { "ProgramData" } else { "APPDATA" })
+        .map(PathBuf::from)
+        .ok_or_else(|| anyhow::anyhow!("Could not find the start menu directory"))?;
+
+    Ok(base
+        .join("Microsoft")
+        .join("Windows")
+        .join("Start Menu")
+        .join("Programs")
+        .join("Scoop Apps"))
+}
+
+/// Get the path of the start menu shortcut with the given name
+///
+/// # Errors
+/// - Finding the shortcuts directory failed
+pub fn path(name: &str, global: bool) -> anyhow::Result<PathBuf> {
+    Ok(shortcuts_dir(global)?.join(format!("{name}.lnk")))
+}
+
+/// Create a start menu shortcut from an entry in a manifest's `shortcuts` field
+///
+/// Each entry is a target path relative to the app directory and a shortcut name,
+/// followed by optional arguments and an icon path. Names may include subdirectories
+///
+/// # Errors
+/// - The entry is missing its target or name
+/// - Finding or creating the shortcuts directory failed
+/// - Creating the shortcut with PowerShell failed
+pub fn create(entry: &[String], variables: &ScriptVariables<'_>) -> anyhow::Result<()> {
+    let [target, name, rest @ ..] = entry else {
+        anyhow::bail!("Invalid shortcut entry: {entry:?}");
+    };
+
+    let shortcut = path(name, variables.global)?;
+    if let Some(parent) = shortcut.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let dir = variables.dir;
+    let target = dir.join(variables.substitute(target));
+
+    // Shortcuts can only be created through COM, which PowerShell makes much simpler
+    let mut script = format!(
+        "$shortcut = (New-Object -ComObject WScript.Shell).CreateShortcut({})\n",
+        quote(shortcut.display())
+    );
+    writeln!(script, "$shortcut.TargetPath = {}", quote(target.display()))?;
+    writeln!(
+        script,
+        "$shortcut.WorkingDirectory = {}",
+        quote(dir.display())
+    )?;
+
+    if let Some(arguments) = rest.first() {
+        writeln!(
+            script,
+            "$shortcut.Arguments = {}",
+            quote(variables.substitute(arguments))
+        )?;
+    }
+
+    if let Some(icon) = rest.get(1) {
+        let icon = dir.join(variables.substitute(icon));
+        writeln!(script, "$shortcut.IconLocation = {}", quote(icon.display()))?;
+    }
+
+    script.push_str("$shortcut.Save()");
+
+    let status = scripts::powershell_command(&script).status()?;
+    if !status.success() {
+        anyhow::bail!("Failed to create shortcut {name}: PowerShell exited with {status}");
+    }
+
+    Ok(())
+}
+
+/// Remove the start menu shortcut with the given name, if it exists
+///
+/// # Errors
+/// - Finding the shortcuts directory failed
+/// - Removing the shortcut failed
+pub fn remove(name: &str, global: bool) -> anyhow::Result<()> {
+    let shortcut = path(name, global)?;
+
+    if shortcut.exists() {
+        std::fs::remove_file(shortcut)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+//! Searching the known buckets that haven't been added, to find out which bucket to add for an app
+//!
+//! Each known bucket is shallow cloned into the cache the first time it is searched, and that copy is pulled afterwards
+
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+use sprinkles::{
+    buckets::Bucket, contexts::ScoopContext, git::implementations::git2,
+    progress::indicatif::ProgressBar,
+};
+
+use crate::{freshness, index};
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+/// An app found in a known bucket that hasn't been added
+pub struct Match {
+    /// The name of the app
+    pub name: String,
+    /// The version of the app
+    pub version: Option<String>,
+    /// The known bucket the app is in
+    pub bucket: String,
+}
+
+/// Get the path the cached copy of a known bucket is cloned to
+pub fn path(ctx: &impl ScoopContext, bucket_name: &str) -> PathBuf {
+    ctx.cache_path()
+        .join("sfsu-known-buckets")
+        .join(bucket_name)
+}
+
+/// List the known buckets that haven't been added, as pairs of name and source
+pub fn not_added(ctx: &impl ScoopContext) -> Vec<(String, String)> {
+    let mut buckets = ctx
+        .known_buckets()
+        .into_iter()
+        .map(|(name, source)| ((*name).to_string(), (*source).to_string()))
+        .filter(|(name, _)| !ctx.buckets_path().join(name).exists())
+        .collect::<Vec<_>>();
+
+    buckets.sort();
+
+    buckets
+}
+
+/// Clone each known bucket that hasn't been added and isn't already cached, and pull the ones that are
+///
+/// Buckets that fail to clone or pull are reported and skipped, so the others can still be searched
+pub fn fetch(ctx: &impl ScoopContext, buckets: &[(String, String)]) {
+    if crate::is_offline() {
+        warn!("Skipping fetching the known buckets in offline mode");
+        return;
+    }
+
+    let (cached, uncached): (Vec<_>, Vec<_>) = buckets
+        .iter()
+        .partition(|(name, _)| path(ctx, name).exists());
+
+    for (name, _) in cached {
+        if let Err(e) = pull(ctx, &path(ctx, name)) {
+            eprintln!("Failed to update known bucket {name}: {e}");
+        }
+    }
+
+    for (name, source) in uncached {
+        eprintln!("Fetching known bucket {name}");
+
+        let bucket_path = path(ctx, name);
+
+        if let Err(e) = clone(source, &bucket_path) {
+            eprintln!("Failed to fetch known bucket {name}: {e}");
+
+            // Remove the partial clone, so it isn't mistaken for a cached copy next time
+            if bucket_path.exists() {
+                if let Err(e) = std::fs::remove_dir_all(&bucket_path) {
+                    warn!("Failed to remove partial clone of {name}: {e}");
+                }
+            }
+        }
+    }
+}
+
+/// Clone a known bucket into the cache
+///
+/// Only the latest commit is fetched, as only the current manifests are searched
+fn clone(source: &str, bucket_path: &Path) -> Result<(), git2::Error> {
+    let mut fetch_options = git2::FetchOptions::new();
+    fetch_options.depth(1);
+
+    git2::build::RepoBuilder::new()
+        .fetch_options(fetch_options)
+        .clone(source, bucket_path)?;
+
+    Ok(())
+}
+
+/// Pull the latest changes into the cached copy of a known bucket
+fn pull(ctx: &impl ScoopContext, bucket_path: &Path) -> anyhow::Result<()> {
+    let repo = Bucket::from_path(bucket_path)?.open_repo()?;
+
+    if repo.outdated()? {
+        let pb = ProgressBar::hidden();
+        repo.pull(ctx, Some(&freshness::stats_callback(&pb)))?;
+    }
+
+    Ok(())
+}
+
+/// Find the apps in the cached known buckets whose names match the pattern
+pub fn search(
+    ctx: &impl ScoopContext,
+    buckets: &[(String, String)],
+    pattern: &Regex,
+) -> Vec<Match> {
+    let mut matches = buckets
+        .par_iter()
+        .flat_map(|(bucket, _)| {
+            index::manifest_paths(&index::manifests_path(&path(ctx, bucket)))
+                .unwrap_or_default()
+                .into_par_iter()
+                .filter_map(|manifest_path| {
+                    let name = manifest_path.file_stem()?.to_string_lossy().to_string();
+
+                    if !pattern.is_match(&name) {
+                        return None;
+                    }
+
+                    let version = std::fs::read(&manifest_path)
+                        .ok()
+                        .and_then(|contents| serde_json::from_slice::<Value>(&contents).ok())
+                        .and_then(|manifest| manifest["version"].as_str().map(String::from));
+
+                    Some(Match {
+                        name,
+                        version,
+                        bucket: bucket.clone(),
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    matches.par_sort_by(|a, b| a.bucket.cmp(&b.bucket).then_with(|| a.name.cmp(&b.name)));
+
+    matches
+}
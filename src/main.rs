@@ -6,20 +6,27 @@
 // and it cannot be disabled for a single line AFAIK
 #![allow(tail_expr_drop_order)]
 
-// TODO: Replace regex with glob
-
 mod calm_panic;
 mod commands;
 mod diagnostics;
 mod errors;
 pub mod float;
+mod freshness;
+mod fuzzy;
 mod handlers;
+mod history;
+mod index;
+mod install;
+mod known_buckets;
 mod limits;
 mod logging;
 mod models;
 mod output;
+mod pattern;
 mod progress;
+mod scope;
 mod validations;
+mod version_order;
 mod wrappers;
 
 use std::{
@@ -74,6 +81,16 @@ struct Args {
     )]
     json: bool,
 
+    #[clap(
+        long,
+        global = true,
+        help = "The format to print outputs in, if the command supports it",
+        value_enum,
+        default_value_t,
+        default_value_if("json", "true", "json")
+    )]
+    format: output::format::Format,
+
     #[clap(short, long, global = true, help = "Show more information in outputs")]
     verbose: bool,
 
@@ -100,6 +117,14 @@ struct Args {
     )]
     arch: Architecture,
 
+    #[clap(
+        long,
+        global = true,
+        help = "Disable all network access, relying only on local buckets, caches and installed apps",
+        env = "SFSU_OFFLINE"
+    )]
+    offline: bool,
+
     #[clap(
         global = true,
         short = 'y',
@@ -110,6 +135,18 @@ struct Args {
 }
 
 pub(crate) static COLOR_ENABLED: AtomicBool = AtomicBool::new(true);
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+static GLOBAL: AtomicBool = AtomicBool::new(false);
+
+/// Check if network access has been disabled with the `--offline` flag
+pub(crate) fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Check if the global Scoop context was selected with the `--global` flag
+pub(crate) fn is_global() -> bool {
+    GLOBAL.load(Ordering::Relaxed)
+}
 
 #[cfg(feature = "contexts")]
 impl TryFrom<&Args> for AnyContext {
@@ -128,6 +165,8 @@ impl TryFrom<&Args> for AnyContext {
 async fn main() -> anyhow::Result<()> {
     logging::panics::handle();
 
+    commands::completions::handle_requests();
+
     let args = Args::parse();
 
     let ctx: AnyContext = {
@@ -155,6 +194,10 @@ async fn main() -> anyhow::Result<()> {
         COLOR_ENABLED.store(false, Ordering::Relaxed);
     }
 
+    OFFLINE.store(args.offline, Ordering::Relaxed);
+    #[cfg(feature = "contexts")]
+    GLOBAL.store(args.global, Ordering::Relaxed);
+
     ctx.config().validate()?;
 
     debug!("Running command: {:?}", args.command);
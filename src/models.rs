@@ -4,3 +4,4 @@ pub mod min;
 #[cfg(not(feature = "v2"))]
 pub mod outdated;
 pub mod status;
+pub mod virustotal;
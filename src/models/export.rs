@@ -2,9 +2,11 @@
 
 use chrono::{DateTime, Local, SecondsFormat};
 use rayon::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use sprinkles::{
+    Architecture,
     buckets::{Bucket as SfsuBucket, Error as BucketError},
     config,
     contexts::ScoopContext,
@@ -12,13 +14,25 @@ use sprinkles::{
     packages::Error as PackageError,
 };
 
+use crate::install;
+
 use super::min::Info;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Config keys that only make sense on the machine they were exported from, which `scoop export` leaves out
+pub const MACHINE_CONFIG_KEYS: &[&str] = &[
+    "last_update",
+    "root_path",
+    "global_path",
+    "cache_path",
+    "alias",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 /// The export data
 pub struct Export {
     #[serde(skip_serializing_if = "Option::is_none")]
     /// The Scoop configuration
+    #[schemars(with = "Option<serde_json::Value>")]
     pub config: Option<config::Scoop>,
     /// The installed apps
     pub apps: Vec<App>,
@@ -28,7 +42,7 @@ pub struct Export {
 
 // TODO: Remove this struct in favour of `MinInfo`
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "PascalCase")]
 /// An installed app
 pub struct App {
@@ -44,7 +58,7 @@ pub struct App {
     pub info: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "PascalCase")]
 /// An installed bucket
 pub struct Bucket {
@@ -81,6 +95,52 @@ impl Export {
             config: Some(config),
         })
     }
+
+    /// Convert to the exact format `scoop export` produces, so it can be imported with `scoop import`
+    ///
+    /// Apps installed for a non-default architecture have it noted in their info,
+    /// and machine specific config values are left out
+    ///
+    /// # Errors
+    /// - The export could not be serialized
+    pub fn into_scoop(mut self, ctx: &impl ScoopContext) -> serde_json::Result<serde_json::Value> {
+        let default_arch = Architecture::ARCH.to_string();
+
+        self.apps.par_iter_mut().for_each(|app| {
+            let Some(arch) = install::installed_architecture(&ctx.apps_path().join(&app.name))
+                .map(|arch| arch.to_string())
+                .filter(|arch| arch != &default_arch)
+            else {
+                return;
+            };
+
+            if app.info.is_empty() {
+                app.info = arch;
+            } else {
+                app.info = format!("{}, {arch}", app.info);
+            }
+        });
+
+        let mut value = serde_json::to_value(self)?;
+
+        if let Some(config) = value.get_mut("config").and_then(|c| c.as_object_mut()) {
+            for key in MACHINE_CONFIG_KEYS {
+                config.remove(*key);
+            }
+        }
+
+        Ok(value)
+    }
+}
+
+impl App {
+    #[must_use]
+    /// Get the architecture noted in the app's info, if it was installed for a non-default architecture
+    pub fn architecture(&self) -> Option<Architecture> {
+        self.info
+            .split(", ")
+            .find_map(|note| serde_json::from_value(note.into()).ok())
+    }
 }
 
 impl From<Info> for App {
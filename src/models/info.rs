@@ -1,12 +1,13 @@
 //! Summary info for a package
 
+use schemars::JsonSchema;
 use serde::Serialize;
 
 use sprinkles::packages::models::manifest::{NestedArray, PackageLicense};
 
 use crate::wrappers::{bool::NicerBool, serialize::SerializeDisplay};
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 /// Summary package information
 pub struct Package {
     /// The name of the package
@@ -23,6 +24,7 @@ pub struct Package {
     pub website: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     /// The license of the package
+    #[schemars(with = "Option<serde_json::Value>")]
     pub license: Option<PackageLicense>,
     #[serde(skip_serializing_if = "Option::is_none")]
     /// The last time the package was updated
@@ -30,7 +32,8 @@ pub struct Package {
     #[serde(skip_serializing_if = "Option::is_none")]
     /// The last time the package was updated by
     pub updated_by: Option<String>,
-    /// Whether the package is installed
+    /// Whether the package is installed, as "Yes" or "No"
+    #[schemars(with = "String")]
     pub installed: NicerBool,
     #[serde(skip_serializing_if = "Option::is_none")]
     /// The list of the package's binaries
@@ -39,5 +42,6 @@ pub struct Package {
     pub notes: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     /// The package's shortcuts
+    #[schemars(with = "Option<String>")]
     pub shortcuts: Option<SerializeDisplay<NestedArray<String>>>,
 }
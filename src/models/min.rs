@@ -2,6 +2,7 @@ use anyhow::Context;
 use chrono::{DateTime, Local};
 use quork::traits::truthy::ContainsTruth;
 use rayon::prelude::*;
+use schemars::JsonSchema;
 use serde::Serialize;
 use sprinkles::{
     contexts::ScoopContext,
@@ -10,9 +11,9 @@ use sprinkles::{
 use std::path::Path;
 use std::time::UNIX_EPOCH;
 
-use crate::wrappers::time::NicerTime;
+use crate::{scope::Scope, wrappers::time::NicerTime};
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 /// Minimal package info
 pub struct Info {
     /// The name of the package
@@ -22,9 +23,13 @@ pub struct Info {
     /// The package's source (eg. bucket name)
     pub source: String,
     /// The last time the package was updated
+    #[schemars(with = "String")]
     pub updated: NicerTime<Local>,
     /// The package's notes
     pub notes: String,
+    /// The Scoop install the package belongs to, when listing multiple installs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<Scope>,
 }
 
 impl Info {
@@ -115,6 +120,7 @@ impl Info {
             source: install_manifest.get_source(),
             updated: updated_time.into(),
             notes: notes.join(", "),
+            scope: None,
         })
     }
 }
@@ -1,10 +1,13 @@
 //! Outdated package information
 
+use schemars::JsonSchema;
 use serde::Serialize;
 
 use sprinkles::packages::Manifest;
 
-#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
+use crate::scope::Scope;
+
+#[derive(Debug, Clone, Serialize, JsonSchema, PartialEq, Eq, Hash)]
 /// The outdated info
 pub struct Info {
     /// The name of the package
@@ -13,6 +16,11 @@ pub struct Info {
     pub current: String,
     /// The available version
     pub available: String,
+    /// The version the app has been pinned to, which it will not be updated past
+    pub pinned: Option<String>,
+    /// The Scoop install the package belongs to, when checking multiple installs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<Scope>,
 }
 
 impl Info {
@@ -28,6 +36,8 @@ impl Info {
                 name: unsafe { remote.name() }.to_string(),
                 current: local.version.to_string(),
                 available: remote.version.to_string(),
+                pinned: None,
+                scope: None,
             })
         }
     }
@@ -1,7 +1,11 @@
 //! Status information
 
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{DateTime, Local, TimeDelta};
 use itertools::Itertools as _;
 use quork::traits::truthy::ContainsTruth;
+use schemars::JsonSchema;
 use serde::Serialize;
 
 use sprinkles::{
@@ -13,7 +17,9 @@ use sprinkles::{
     },
 };
 
-#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
+use crate::{scope::Scope, wrappers::time::NicerTime};
+
+#[derive(Debug, Clone, Serialize, JsonSchema, PartialEq, Eq, Hash)]
 #[allow(clippy::struct_field_names)]
 /// The status info
 pub struct Info {
@@ -24,9 +30,21 @@ pub struct Info {
     /// The available version
     pub available: String,
     /// The missing dependencies
+    #[schemars(with = "Vec<String>")]
     pub missing_dependencies: Vec<package::Reference>,
+    /// Whether the app's manifest has been removed from its bucket
+    pub removed: bool,
+    /// Whether the app is held, and so will not be updated
+    pub held: bool,
+    /// Whether the app's last install failed
+    pub failed: bool,
+    /// The version the app has been pinned to
+    pub pinned: Option<String>,
     /// Additional information
     pub info: Option<String>,
+    /// The Scoop install the package belongs to, when checking multiple installs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<Scope>,
 }
 
 impl Info {
@@ -55,12 +73,23 @@ impl Info {
         };
 
         debug!("Local manifest name: {}", unsafe { local_manifest.name() });
-        let remote_manifest = bucket.get_manifest(unsafe { local_manifest.name() })?;
+        let remote_manifest = match bucket.get_manifest(unsafe { local_manifest.name() }) {
+            Ok(manifest) => Some(manifest),
+            Err(_) if !manifest_exists(ctx, bucket, unsafe { local_manifest.name() }) => None,
+            Err(e) => return Err(e.into()),
+        };
+        let removed = remote_manifest.is_none();
 
         let install_manifest = local_manifest.install_manifest(ctx)?;
 
         let held = install_manifest.hold.unwrap_or_default();
 
+        let pinned =
+            crate::install::pinned_version(&ctx.apps_path().join(unsafe { local_manifest.name() }));
+        let pinned_info = pinned
+            .as_ref()
+            .map(|version| format!("Pinned to {version}"));
+
         let missing_dependencies = local_manifest
             .depends()
             .into_iter()
@@ -74,21 +103,155 @@ impl Info {
             })
             .collect_vec();
 
-        let mut info = String::new();
+        let mut info = vec![];
 
         if failed {
-            info += "Install failed";
+            info.push("Install failed");
         }
         if held {
-            info += "Held package";
+            info.push("Held package");
+        }
+        if let Some(pinned_info) = &pinned_info {
+            info.push(pinned_info);
+        }
+        if removed {
+            info.push("Manifest removed");
         }
 
+        let current = local_manifest.version.to_string();
+
         Ok(Info {
-            name: unsafe { remote_manifest.name() }.to_string(),
-            current: local_manifest.version.to_string(),
-            available: remote_manifest.version.to_string(),
+            name: unsafe { local_manifest.name() }.to_string(),
+            available: remote_manifest
+                .map_or_else(|| current.clone(), |manifest| manifest.version.to_string()),
+            current,
             missing_dependencies,
-            info: (!info.is_empty()).then_some(info),
+            removed,
+            held,
+            failed,
+            pinned,
+            info: (!info.is_empty()).then(|| info.join(", ")),
+            scope: None,
         })
     }
 }
+
+/// Check whether a bucket still contains a manifest for the given app
+fn manifest_exists(ctx: &impl ScoopContext, bucket: &Bucket, name: &str) -> bool {
+    let bucket_path = ctx.buckets_path().join(bucket.name().as_ref());
+    let file_name = format!("{name}.json");
+
+    bucket_path.join("bucket").join(&file_name).exists() || bucket_path.join(file_name).exists()
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// How long an app or bucket can go without being updated before it is stale, parsed from e.g. `7d` or `12h`
+pub struct Threshold(TimeDelta);
+
+impl Threshold {
+    #[must_use]
+    /// Check if something last updated at the given time has gone past the threshold
+    pub fn is_stale(self, updated: &DateTime<Local>) -> bool {
+        Local::now().signed_duration_since(updated) > self.0
+    }
+}
+
+impl FromStr for Threshold {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some(unit_start) = s.find(|c: char| !c.is_ascii_digit()) else {
+            return Err(format!(
+                "missing unit in `{s}`, expected one of m, h, d or w"
+            ));
+        };
+
+        let (amount, unit) = s.split_at(unit_start);
+
+        let amount = amount
+            .parse()
+            .map_err(|e| format!("invalid duration `{s}`: {e}"))?;
+
+        let delta = match unit {
+            "m" => TimeDelta::try_minutes(amount),
+            "h" => TimeDelta::try_hours(amount),
+            "d" => TimeDelta::try_days(amount),
+            "w" => TimeDelta::try_weeks(amount),
+            _ => {
+                return Err(format!(
+                    "unknown unit `{unit}`, expected one of m, h, d or w"
+                ));
+            }
+        };
+
+        delta
+            .map(Self)
+            .ok_or_else(|| format!("duration `{s}` is too long"))
+    }
+}
+
+impl Display for Threshold {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let minutes = self.0.num_minutes();
+
+        match minutes {
+            m if m % (60 * 24 * 7) == 0 => write!(f, "{}w", m / (60 * 24 * 7)),
+            m if m % (60 * 24) == 0 => write!(f, "{}d", m / (60 * 24)),
+            m if m % 60 == 0 => write!(f, "{}h", m / 60),
+            m => write!(f, "{m}m"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+/// An app or bucket that has not been updated within the staleness threshold
+pub struct Stale {
+    /// The name of the app or bucket
+    pub name: String,
+    /// The last time it was updated
+    #[schemars(with = "String")]
+    pub updated: NicerTime<Local>,
+    /// The number of whole days since it was updated
+    pub days: i64,
+}
+
+impl Stale {
+    #[must_use]
+    /// Create a stale entry from when it was last updated
+    pub fn new(name: String, updated: DateTime<Local>) -> Self {
+        Self {
+            name,
+            days: Local::now().signed_duration_since(updated).num_days(),
+            updated: updated.into(),
+        }
+    }
+}
+
+impl Display for Stale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (last updated {} days ago)", self.name, self.days)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_threshold() {
+        assert_eq!("7d".parse(), Ok(Threshold(TimeDelta::days(7))));
+        assert_eq!("12h".parse(), Ok(Threshold(TimeDelta::hours(12))));
+        assert_eq!("2w".parse(), Ok(Threshold(TimeDelta::weeks(2))));
+
+        assert!("7".parse::<Threshold>().is_err());
+        assert!("d".parse::<Threshold>().is_err());
+        assert!("7y".parse::<Threshold>().is_err());
+    }
+
+    #[test]
+    fn test_display_threshold() {
+        assert_eq!(Threshold(TimeDelta::days(14)).to_string(), "2w");
+        assert_eq!(Threshold(TimeDelta::days(3)).to_string(), "3d");
+        assert_eq!(Threshold(TimeDelta::minutes(90)).to_string(), "90m");
+    }
+}
@@ -0,0 +1,131 @@
+//! `VirusTotal` scan reports
+
+use std::fmt::Display;
+
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+#[derive(Debug, Copy, Clone, ValueEnum, Serialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+/// How dangerous a file is, based on how many engines detected it
+pub enum Status {
+    /// No engines detected the file
+    Undetected,
+    /// Some engines detected the file
+    Suspicious,
+    /// More than 10% of engines detected the file
+    Malicious,
+}
+
+impl Status {
+    #[must_use]
+    #[allow(clippy::cast_precision_loss, clippy::cast_sign_loss)]
+    /// Get the status from the number of engines that detected the file, and the total number of engines
+    pub fn from_stats(dangerous: u64, total: u64) -> Self {
+        let dangerous = dangerous as f64;
+        let total = total as f64;
+        let ratio = dangerous / total;
+
+        if ratio > 0.1 {
+            Self::Malicious
+        } else if dangerous > 0.0 {
+            Self::Suspicious
+        } else {
+            Self::Undetected
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema, PartialEq, Eq)]
+/// The scan report for a single download of an app
+pub struct Report {
+    /// The name of the app
+    pub name: String,
+    /// The bucket the app is from
+    pub bucket: String,
+    /// The hash or url that was looked up
+    pub lookup: String,
+    /// The overall status of the download
+    pub status: Status,
+    /// The number of engines that detected the download
+    pub detected: u64,
+    /// The total number of engines that scanned the download
+    pub total: u64,
+    /// A link to the full report on `VirusTotal`, if one could be generated
+    pub link: Option<String>,
+}
+
+impl Report {
+    #[must_use]
+    /// Create a report from the detection stats
+    pub fn new(
+        name: String,
+        bucket: String,
+        lookup: String,
+        link: Option<String>,
+        detected: u64,
+        total: u64,
+    ) -> Self {
+        Self {
+            name,
+            bucket,
+            lookup,
+            status: Status::from_stats(detected, total),
+            detected,
+            total,
+            link,
+        }
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{}: {}/{}",
+            self.bucket, self.name, self.detected, self.total
+        )?;
+
+        if let Some(link) = &self.link {
+            write!(f, ". See more at {link}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_ord() {
+        assert!(Status::Malicious > Status::Suspicious);
+        assert!(Status::Suspicious > Status::Undetected);
+    }
+
+    #[test]
+    fn test_status_from_stats() {
+        assert_eq!(Status::from_stats(0, 70), Status::Undetected);
+        assert_eq!(Status::from_stats(2, 70), Status::Suspicious);
+        assert_eq!(Status::from_stats(10, 70), Status::Malicious);
+    }
+
+    #[test]
+    fn test_report_display() {
+        let report = Report::new(
+            "sfsu".into(),
+            "extras".into(),
+            "abc".into(),
+            Some("https://www.virustotal.com/gui/file/abc".into()),
+            1,
+            70,
+        );
+
+        assert_eq!(
+            report.to_string(),
+            "extras/sfsu: 1/70. See more at https://www.virustotal.com/gui/file/abc"
+        );
+    }
+}
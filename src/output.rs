@@ -6,6 +6,8 @@
 //!
 pub mod colours;
 pub mod consts;
+pub mod format;
+pub mod powershell;
 pub mod sectioned;
 pub mod structured;
 pub mod truncate;
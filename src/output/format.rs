@@ -0,0 +1,238 @@
+//! Machine readable output formats, shared by all commands with structured output
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, ValueEnum)]
+/// The format to print command outputs in
+pub enum Format {
+    #[default]
+    /// A human readable table or list
+    Table,
+    /// Pretty printed JSON
+    Json,
+    /// YAML
+    Yaml,
+    /// TOML, with lists placed under an `items` key
+    Toml,
+    /// Comma separated values, with a header row
+    Csv,
+    /// Tab separated values, with a header row
+    Tsv,
+}
+
+impl Format {
+    /// Check if the format is machine readable, rather than the human readable default
+    pub fn is_structured(self) -> bool {
+        self != Format::Table
+    }
+
+    /// Serialize the value in this format
+    ///
+    /// [`Format::Table`] has no generic representation, so it falls back to JSON.
+    /// Commands should print their own human readable output instead.
+    ///
+    /// # Errors
+    /// - The value could not be serialized
+    pub fn serialize(self, value: &impl Serialize) -> anyhow::Result<String> {
+        Ok(match self {
+            Format::Table | Format::Json => serde_json::to_string_pretty(value)?,
+            Format::Yaml => serde_yaml::to_string(value)?,
+            Format::Toml => to_toml(serde_json::to_value(value)?)?,
+            Format::Csv => to_csv(&serde_json::to_value(value)?, ','),
+            Format::Tsv => to_csv(&serde_json::to_value(value)?, '\t'),
+        })
+    }
+
+    /// Serialize the value in this format and print it to stdout
+    ///
+    /// # Errors
+    /// - The value could not be serialized
+    pub fn print(self, value: &impl Serialize) -> anyhow::Result<()> {
+        let output = self.serialize(value)?;
+
+        if output.ends_with('\n') {
+            print!("{output}");
+        } else {
+            println!("{output}");
+        }
+
+        Ok(())
+    }
+}
+
+/// Convert a value to TOML
+///
+/// TOML documents must be tables and cannot contain nulls,
+/// so lists are placed under an `items` key and null values are left out.
+fn to_toml(value: Value) -> anyhow::Result<String> {
+    let value = match strip_nulls(value) {
+        Value::Null => Value::Object(serde_json::Map::new()),
+        value @ Value::Object(_) => value,
+        value => serde_json::json!({ "items": value }),
+    };
+
+    Ok(toml::to_string_pretty(&value)?)
+}
+
+/// Recursively remove null values from objects and lists
+fn strip_nulls(value: Value) -> Value {
+    match value {
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, strip_nulls(value)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .filter(|value| !value.is_null())
+                .map(strip_nulls)
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+/// Convert a value to CSV, with cells separated by the given delimiter
+///
+/// Each item in a list becomes a row, with the columns taken from the keys of the objects within it.
+/// Objects of lists, such as search results grouped by bucket, are flattened into a single list.
+fn to_csv(value: &Value, delimiter: char) -> String {
+    let separator = delimiter.to_string();
+
+    let rows = match value {
+        Value::Array(rows) => rows.clone(),
+        Value::Object(fields) if fields.values().all(Value::is_array) => fields
+            .values()
+            .filter_map(Value::as_array)
+            .flatten()
+            .cloned()
+            .collect(),
+        value => vec![value.clone()],
+    };
+
+    let mut headers: Vec<&str> = vec![];
+    for row in &rows {
+        if let Value::Object(fields) = row {
+            for key in fields.keys() {
+                if !headers.contains(&key.as_str()) {
+                    headers.push(key);
+                }
+            }
+        }
+    }
+
+    let mut output = String::new();
+
+    if !headers.is_empty() {
+        output.push_str(
+            &headers
+                .iter()
+                .map(|header| escape(header, delimiter))
+                .collect::<Vec<_>>()
+                .join(&separator),
+        );
+        output.push('\n');
+    }
+
+    for row in &rows {
+        let cells = match row {
+            Value::Object(fields) => headers
+                .iter()
+                .map(|header| {
+                    fields
+                        .get(*header)
+                        .map(|value| cell(value, delimiter))
+                        .unwrap_or_default()
+                })
+                .collect(),
+            value => vec![cell(value, delimiter)],
+        };
+
+        output.push_str(&cells.join(&separator));
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Convert a single value to a CSV cell
+fn cell(value: &Value, delimiter: char) -> String {
+    let text = match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                Value::String(text) => text.clone(),
+                item => item.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(";"),
+        value => value.to_string(),
+    };
+
+    escape(&text, delimiter)
+}
+
+/// Quote a CSV cell if it contains the delimiter or any other special characters
+fn escape(text: &str, delimiter: char) -> String {
+    if text.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_csv() {
+        let value = serde_json::json!({
+            "main": [
+                { "name": "git", "version": "2.45.0", "bins": ["git.exe", "bash.exe"] },
+                { "name": "sfsu", "version": "1.17.0", "notes": "Says \"hi\", twice" },
+            ],
+        });
+
+        assert_eq!(
+            to_csv(&value, ','),
+            "name,version,bins,notes\n\
+             git,2.45.0,git.exe;bash.exe,\n\
+             sfsu,1.17.0,,\"Says \"\"hi\"\", twice\"\n"
+        );
+    }
+
+    #[test]
+    fn test_to_tsv() {
+        let value = serde_json::json!([
+            { "name": "git", "description": "Distributed version control, fast" },
+            { "name": "sfsu", "description": "Tabs\tare quoted" },
+        ]);
+
+        assert_eq!(
+            to_csv(&value, '\t'),
+            "name\tdescription\n\
+             git\tDistributed version control, fast\n\
+             sfsu\t\"Tabs\tare quoted\"\n"
+        );
+    }
+
+    #[test]
+    fn test_to_toml() {
+        let value = serde_json::json!([
+            { "name": "git", "version": "2.45.0", "notes": null },
+        ]);
+
+        assert_eq!(
+            to_toml(value).unwrap(),
+            "[[items]]\nname = \"git\"\nversion = \"2.45.0\"\n"
+        );
+    }
+}
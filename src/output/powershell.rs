@@ -0,0 +1,105 @@
+//! Tables laid out like PowerShell's `Format-Table`, for scripts that parse Scoop's output
+
+use std::fmt::Display;
+
+#[must_use = "does nothing unless printed"]
+#[derive(Debug, Clone)]
+/// A table with a row of dashes under the headers, and columns separated by a single space
+///
+/// Unlike [`super::structured::Structured`], columns are never truncated, so the layout only depends on the values
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Construct a new table with the given headers
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(ToString::to_string).collect(),
+            rows: vec![],
+        }
+    }
+
+    /// Add a row to the table
+    ///
+    /// # Panics
+    /// - If the row does not have a value for each header
+    pub fn push(&mut self, row: Vec<String>) {
+        assert_eq!(
+            row.len(),
+            self.headers.len(),
+            "row length must match headers"
+        );
+
+        self.rows.push(row);
+    }
+}
+
+impl Display for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let widths = self
+            .headers
+            .iter()
+            .enumerate()
+            .map(|(i, header)| {
+                self.rows
+                    .iter()
+                    .map(|row| row[i].chars().count())
+                    .chain([header.chars().count()])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+
+        let dashes = self
+            .headers
+            .iter()
+            .map(|header| "-".repeat(header.chars().count()))
+            .collect::<Vec<_>>();
+
+        for row in [&self.headers, &dashes].into_iter().chain(&self.rows) {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(value, width)| format!("{value:<width$}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            // PowerShell doesn't pad the last column
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_layout() {
+        let mut table = Table::new(&["Name", "Version", "Source", "Binaries"]);
+        table.push(vec![
+            "python".into(),
+            "3.12.0".into(),
+            "main".into(),
+            "python.exe | pythonw.exe".into(),
+        ]);
+        table.push(vec![
+            "7zip".into(),
+            "24.08".into(),
+            "main".into(),
+            String::new(),
+        ]);
+
+        assert_eq!(
+            table.to_string(),
+            "Name   Version Source Binaries\n\
+             ----   ------- ------ --------\n\
+             python 3.12.0  main   python.exe | pythonw.exe\n\
+             7zip   24.08   main\n"
+        );
+    }
+}
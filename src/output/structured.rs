@@ -1,6 +1,6 @@
 //! Structured output for the CLI
 
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
 use hashbrown::HashMap;
 use indexmap::IndexMap;
@@ -10,10 +10,73 @@ use serde_json::{Map, Value};
 
 use crate::{float::usize::convert_to_f64, wrappers::header::Header};
 
-use super::{consts::WALL, truncate::FixedLength};
+use super::{
+    consts::{SUFFIX, WALL},
+    truncate::FixedLength,
+};
 
 pub mod vertical;
 
+/// The narrowest a column will be collapsed to before it is hidden
+const MIN_COLUMN_WIDTH: usize = SUFFIX.len() + WALL.len() + 4;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default)]
+/// How important it is to keep a column readable when the terminal is too narrow
+pub enum Priority {
+    /// Collapsed first, and hidden entirely if there is still not enough space
+    Low,
+    #[default]
+    /// Collapsed after low priority columns
+    Normal,
+    /// Collapsed only once all other columns have been collapsed
+    High,
+    /// Never truncated below its longest value
+    Required,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The maximum width of a column, parsed from `column=width`
+pub struct MaxWidth {
+    column: String,
+    width: usize,
+}
+
+impl FromStr for MaxWidth {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((column, width)) = s.split_once('=') else {
+            return Err(format!("expected `column=width`, found `{s}`"));
+        };
+
+        let width = width
+            .parse()
+            .map_err(|e| format!("invalid width `{width}`: {e}"))?;
+
+        Ok(Self {
+            column: column.to_string(),
+            width,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, clap::Args)]
+/// Command line options for customising tables
+pub struct Options {
+    #[clap(
+        long,
+        help = "Only show the given columns, in the given order",
+        value_delimiter = ','
+    )]
+    columns: Vec<String>,
+
+    #[clap(
+        long,
+        help = "Limit the width of a column, as `column=width`. Can be passed multiple times"
+    )]
+    max_width: Vec<MaxWidth>,
+}
+
 #[must_use = "Structured is lazy, and only takes effect when used in formatting"]
 /// A table of data
 ///
@@ -21,13 +84,18 @@ pub mod vertical;
 /// to be constructed and used within the same function.
 pub struct Structured {
     objects: Vec<Map<String, Value>>,
+    priorities: HashMap<String, Priority>,
+    columns: Vec<String>,
+    max_widths: HashMap<String, usize>,
 }
 
 impl Structured {
     /// Construct a new [`Structured`] formatter
     ///
+    /// Nested objects are flattened into columns with dotted names, such as `license.identifier`.
+    /// Rows missing a column, such as those where a nested object is `null`, are left empty in that column
+    ///
     /// # Panics
-    /// - If the length of headers is not equal to the length of values
     /// - If the values provided are not objects
     pub fn new(values: &[impl Serialize]) -> Self {
         let objects = values
@@ -36,17 +104,178 @@ impl Structured {
                 let value = serde_json::to_value(v).expect("valid value");
 
                 if let Value::Object(object) = value {
-                    object
+                    flatten(object)
                 } else {
                     panic!("Expected object, got {value:?}");
                 }
             })
             .collect::<Vec<_>>();
 
-        Structured { objects }
+        Structured {
+            objects: fill_missing(objects),
+            priorities: HashMap::new(),
+            columns: vec![],
+            max_widths: HashMap::new(),
+        }
+    }
+
+    /// Apply the table options passed on the command line
+    pub fn with_options(mut self, options: &Options) -> Self {
+        self.columns.clone_from(&options.columns);
+        self.max_widths.extend(
+            options
+                .max_width
+                .iter()
+                .map(|max_width| (max_width.column.clone(), max_width.width)),
+        );
+        self
+    }
+
+    /// Set the priority of a column
+    ///
+    /// Columns default to [`Priority::Normal`], except for the first column which defaults to [`Priority::Required`]
+    pub fn with_priority(mut self, header: &str, priority: Priority) -> Self {
+        self.priorities.insert(header.to_string(), priority);
+        self
+    }
+
+    fn priority(&self, index: usize, header: &str) -> Priority {
+        match self.priorities.get(header) {
+            Some(priority) => *priority,
+            None if index == 0 => Priority::Required,
+            None => Priority::Normal,
+        }
     }
 }
 
+/// Flatten nested objects into a single object, joining the keys of nested fields with dots
+fn flatten(object: Map<String, Value>) -> Map<String, Value> {
+    let mut flattened = Map::new();
+
+    for (key, value) in object {
+        if let Value::Object(nested) = value {
+            for (nested_key, value) in flatten(nested) {
+                flattened.insert(format!("{key}.{nested_key}"), value);
+            }
+        } else {
+            flattened.insert(key, value);
+        }
+    }
+
+    flattened
+}
+
+/// Give every object the same columns, filling the columns an object is missing with [`Value::Null`]
+///
+/// Columns are ordered by where they first appear, with nested columns kept next to their siblings.
+/// A column that is `null` in every object is dropped if other objects have nested columns in its place
+fn fill_missing(objects: Vec<Map<String, Value>>) -> Vec<Map<String, Value>> {
+    let mut headers: Vec<&String> = vec![];
+
+    for header in objects.iter().flat_map(Map::keys) {
+        if headers.contains(&header) {
+            continue;
+        }
+
+        let position = header
+            .match_indices('.')
+            .map(|(index, _)| &header[..index])
+            .find_map(|parent| {
+                headers.iter().rposition(|existing| {
+                    *existing == parent
+                        || existing
+                            .strip_prefix(parent)
+                            .is_some_and(|rest| rest.starts_with('.'))
+                })
+            });
+
+        match position {
+            Some(position) => headers.insert(position + 1, header),
+            None => headers.push(header),
+        }
+    }
+
+    let headers = headers
+        .iter()
+        .filter(|header| {
+            let has_nested = headers.iter().any(|other| {
+                other
+                    .strip_prefix(header.as_str())
+                    .is_some_and(|rest| rest.starts_with('.'))
+            });
+            let always_null = objects
+                .iter()
+                .all(|object| object.get(header.as_str()).is_none_or(Value::is_null));
+
+            !(has_nested && always_null)
+        })
+        .map(|header| (*header).clone())
+        .collect_vec();
+
+    objects
+        .into_iter()
+        .map(|mut object| {
+            headers
+                .iter()
+                .map(|header| (header.clone(), object.remove(header).unwrap_or(Value::Null)))
+                .collect()
+        })
+        .collect()
+}
+
+/// Fit columns into the available width, collapsing and then hiding the lowest priority columns first
+///
+/// Each column is given as its desired width and priority.
+/// Returns the width of each column, or [`None`] if the column should be hidden
+fn fit_columns(columns: &[(usize, Priority)], available: usize) -> Vec<Option<usize>> {
+    let mut widths = columns
+        .iter()
+        .map(|(width, _)| Some(*width))
+        .collect::<Vec<_>>();
+
+    let total = |widths: &[Option<usize>]| widths.iter().flatten().sum::<usize>();
+
+    // Lowest priority first, and rightmost first within each priority
+    let collapse_order = {
+        let mut order = (0..columns.len())
+            .filter(|&index| columns[index].1 != Priority::Required)
+            .collect_vec();
+        order.sort_by_key(|&index| (columns[index].1, std::cmp::Reverse(index)));
+        order
+    };
+
+    for &index in &collapse_order {
+        let overflow = total(&widths).saturating_sub(available);
+        if overflow == 0 {
+            return widths;
+        }
+
+        if let Some(width) = &mut widths[index] {
+            let min_width = MIN_COLUMN_WIDTH.min(*width);
+            *width -= overflow.min(*width - min_width);
+        }
+    }
+
+    for &index in &collapse_order {
+        if total(&widths) <= available {
+            break;
+        }
+
+        widths[index] = None;
+    }
+
+    // Give any space freed by hiding columns back to the highest priority collapsed columns
+    for &index in collapse_order.iter().rev() {
+        let spare = available.saturating_sub(total(&widths));
+
+        if let Some(width) = &mut widths[index] {
+            *width += spare.min(columns[index].0 - *width);
+        }
+    }
+
+    widths
+}
+
 struct Values<'a> {
     header_values: Vec<&'a Value>,
 }
@@ -100,21 +329,45 @@ impl Display for Structured {
                     base
                 });
 
+        let header_values = if self.columns.is_empty() {
+            header_values
+        } else {
+            let mut header_values = header_values;
+
+            self.columns
+                .iter()
+                .filter_map(|column| Some((column.clone(), header_values.shift_remove(column)?)))
+                .collect()
+        };
+
         let access_lengths = header_values
             .iter()
-            .map(|(header, values)| (header, header.len().max(values.max_length())))
+            .map(|(header, values)| {
+                let len = header.len().max(values.max_length());
+
+                match self.max_widths.get(header) {
+                    Some(max_width) => (header, len.min(*max_width)),
+                    None => (header, len),
+                }
+            })
             .collect_vec();
 
         let term_columns = console::Term::stdout().size().1;
 
+        let fits = access_lengths
+            .iter()
+            .map(|(_, len)| len + WALL.len())
+            .sum::<usize>()
+            <= usize::from(term_columns);
+
         #[allow(
             clippy::cast_precision_loss,
             clippy::cast_sign_loss,
             clippy::cast_possible_truncation
         )]
         // Number of columns each header has access to in the terminal
-        // The index of each column is the index of the header in the indexmap iterator
-        let column_lengths = {
+        // Hidden columns are not included
+        let column_lengths: HashMap<String, usize> = if fits {
             let term_columns: f64 = term_columns.into();
 
             let total = convert_to_f64(access_lengths.iter().map(|(_, len)| len).sum::<usize>())
@@ -129,14 +382,40 @@ impl Display for Structured {
                     acc.entry((*header).to_string()).or_insert(columns);
                     acc
                 })
+        } else {
+            let columns = access_lengths
+                .iter()
+                .enumerate()
+                .map(|(index, (header, len))| (len + WALL.len(), self.priority(index, header)))
+                .collect_vec();
+
+            access_lengths
+                .iter()
+                .zip(fit_columns(&columns, term_columns.into()))
+                .filter_map(|((header, _), width)| Some(((*header).to_string(), width?)))
+                .collect()
         };
 
+        // Never expand a column past its maximum width, even when there is space to spare
+        let column_lengths = column_lengths
+            .into_iter()
+            .map(|(header, len)| match self.max_widths.get(&header) {
+                Some(max_width) => (
+                    header,
+                    len.min((max_width + WALL.len()).max(MIN_COLUMN_WIDTH)),
+                ),
+                None => (header, len),
+            })
+            .collect::<HashMap<_, _>>();
+
         // Finalise values
         let mut finalised_values = header_values;
 
         // Print Headers
         for (header, _) in &finalised_values {
-            let header_size = column_lengths.get(header).copied().unwrap_or_default();
+            let Some(header_size) = column_lengths.get(header).copied() else {
+                continue;
+            };
 
             let truncated = console::style(FixedLength::new(Header::new(header))).green();
             write!(f, "{truncated:header_size$}{WALL}")?;
@@ -148,11 +427,13 @@ impl Display for Structured {
         // Print Values
         for _ in 0..self.objects.len() {
             for (header, values) in &mut finalised_values {
-                let value_size = column_lengths.get(header).copied().unwrap_or_default();
-
                 let Some(current_value) = values.pop() else {
                     panic!("ran out of values early. this is a bug.");
                 };
+
+                let Some(value_size) = column_lengths.get(header).copied() else {
+                    continue;
+                };
                 let element = match current_value {
                     Value::Null => String::new(),
                     Value::Bool(bool) => bool.to_string(),
@@ -160,10 +441,13 @@ impl Display for Structured {
                     Value::String(string) => string.to_string(),
                     Value::Array(array) => array
                         .iter()
-                        .map(|v| v.as_str().unwrap_or("<object>"))
+                        .map(|v| match v {
+                            Value::String(string) => string.to_string(),
+                            v => v.to_string(),
+                        })
                         .join(", "),
-
-                    Value::Object(_) => panic!("Objects not supported within other objects"),
+                    // Objects are flattened when constructed, so this is only a fallback
+                    Value::Object(_) => current_value.to_string(),
                 };
 
                 let with_suffix = FixedLength::new(element);
@@ -178,3 +462,77 @@ impl Display for Structured {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_columns() {
+        let columns = [
+            (10, Priority::Required),
+            (20, Priority::Normal),
+            (30, Priority::Low),
+        ];
+
+        assert_eq!(fit_columns(&columns, 60), [Some(10), Some(20), Some(30)]);
+        assert_eq!(fit_columns(&columns, 40), [Some(10), Some(20), Some(10)]);
+        assert_eq!(fit_columns(&columns, 25), [Some(10), Some(15), None]);
+    }
+
+    #[test]
+    fn test_flatten() {
+        let Value::Object(object) = serde_json::json!({
+            "name": "sfsu",
+            "license": { "identifier": "MIT", "source": { "url": "https://example.com" } },
+        }) else {
+            unreachable!()
+        };
+
+        let keys = flatten(object)
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect_vec();
+
+        assert_eq!(keys, ["name", "license.identifier", "license.source.url"]);
+    }
+
+    #[test]
+    fn test_fill_missing() {
+        let objects = [
+            serde_json::json!({ "name": "sfsu", "license": null, "version": "1.0.0" }),
+            serde_json::json!({ "name": "scoop", "license": { "identifier": "MIT" } }),
+        ]
+        .into_iter()
+        .map(|value| match value {
+            Value::Object(object) => flatten(object),
+            _ => unreachable!(),
+        })
+        .collect_vec();
+
+        let filled = fill_missing(objects);
+
+        for object in &filled {
+            assert_eq!(
+                object.keys().collect_vec(),
+                ["name", "license.identifier", "version"]
+            );
+        }
+
+        assert_eq!(filled[0]["license.identifier"], Value::Null);
+        assert_eq!(filled[1]["version"], Value::Null);
+    }
+
+    #[test]
+    fn test_parse_max_width() {
+        assert_eq!(
+            "name=20".parse::<MaxWidth>(),
+            Ok(MaxWidth {
+                column: "name".to_string(),
+                width: 20,
+            })
+        );
+        assert!("name".parse::<MaxWidth>().is_err());
+        assert!("name=wide".parse::<MaxWidth>().is_err());
+    }
+}
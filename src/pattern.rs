@@ -0,0 +1,45 @@
+//! Matching app names against glob patterns, for users who don't want to write regexes
+
+/// Convert a glob pattern to an equivalent regex pattern
+///
+/// `*` matches any run of characters and `?` matches any single character.
+/// Everything else is matched literally, and the pattern must match the whole text
+pub fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+
+    for c in glob.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+
+    regex.push('$');
+
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use super::*;
+
+    #[test]
+    fn test_glob_to_regex() {
+        assert_eq!(glob_to_regex("python3*"), "^python3.*$");
+        assert_eq!(glob_to_regex("7z?"), "^7z.$");
+        assert_eq!(glob_to_regex("c++"), r"^c\+\+$");
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        let pattern = Regex::new(&glob_to_regex("python3*")).unwrap();
+
+        assert!(pattern.is_match("python3"));
+        assert!(pattern.is_match("python312"));
+        assert!(!pattern.is_match("python"));
+        assert!(!pattern.is_match("ipython3"));
+    }
+}
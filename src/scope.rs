@@ -0,0 +1,59 @@
+//! Listing apps from the user and global Scoop installs at the same time
+
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::Serialize;
+#[cfg(feature = "contexts")]
+use sprinkles::contexts::{AnyContext, Global, ScoopContext, User};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, ValueEnum, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+/// The Scoop install(s) to list apps from
+pub enum Scope {
+    /// The current user's Scoop install
+    User,
+    /// The global Scoop install, shared by all users
+    Global,
+    /// Both the user and global Scoop installs
+    #[value(alias = "both")]
+    All,
+}
+
+impl Scope {
+    #[cfg(feature = "contexts")]
+    /// Open the context for each Scoop install in this scope, paired with the scope it belongs to
+    ///
+    /// When listing all scopes, the global install is left out if it does not exist
+    ///
+    /// # Errors
+    /// - Opening a context failed
+    pub fn contexts(self) -> anyhow::Result<Vec<(Scope, AnyContext)>> {
+        Ok(match self {
+            Scope::User => vec![(Scope::User, AnyContext::User(User::new()?))],
+            Scope::Global => vec![(Scope::Global, AnyContext::Global(Global::new()?))],
+            Scope::All => {
+                let mut contexts = Scope::User.contexts()?;
+
+                match Global::new() {
+                    Ok(global) if global.apps_path().exists() => {
+                        contexts.push((Scope::Global, AnyContext::Global(global)));
+                    }
+                    Ok(_) => debug!("Global Scoop has no apps directory, skipping it"),
+                    Err(e) => debug!("Could not open the global Scoop install, skipping it: {e}"),
+                }
+
+                contexts
+            }
+        })
+    }
+}
+
+impl std::fmt::Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Scope::User => write!(f, "user"),
+            Scope::Global => write!(f, "global"),
+            Scope::All => write!(f, "all"),
+        }
+    }
+}
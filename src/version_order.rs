@@ -0,0 +1,79 @@
+//! Ordering app versions, which are rarely strict semver
+
+use std::cmp::Ordering;
+
+/// Compare two app versions
+///
+/// Versions are split into parts on `.`, `-`, `_` and `+`, and compared part by part.
+/// Numeric parts are compared as numbers, so `1.10` is newer than `1.9`, and other parts are compared as text, ignoring case.
+/// When one version has extra parts, it is newer if they are numeric (`1.0.1` > `1.0`),
+/// and older otherwise, as they are then a pre-release tag (`1.0-beta` < `1.0`)
+pub fn compare(a: &str, b: &str) -> Ordering {
+    let mut a_parts = parts(a);
+    let mut b_parts = parts(b);
+
+    loop {
+        match (a_parts.next(), b_parts.next()) {
+            (Some(a_part), Some(b_part)) => {
+                let ordering = compare_part(a_part, b_part);
+
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(a_part), None) => return extra_part(a_part),
+            (None, Some(b_part)) => return extra_part(b_part).reverse(),
+            (None, None) => return Ordering::Equal,
+        }
+    }
+}
+
+fn parts(version: &str) -> impl Iterator<Item = &str> {
+    version
+        .split(['.', '-', '_', '+'])
+        .filter(|part| !part.is_empty())
+}
+
+fn compare_part(a: &str, b: &str) -> Ordering {
+    match (a.parse::<u64>(), b.parse::<u64>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        // Numbers are newer than tags, so `1.0.1` is newer than `1.0-beta`
+        (Ok(_), Err(_)) => Ordering::Greater,
+        (Err(_), Ok(_)) => Ordering::Less,
+        (Err(_), Err(_)) => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
+
+/// Order a version with an extra part against the version without it
+fn extra_part(part: &str) -> Ordering {
+    if part.parse::<u64>().is_ok() {
+        Ordering::Greater
+    } else {
+        Ordering::Less
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numeric_parts() {
+        assert_eq!(compare("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare("2024.1", "2023.12"), Ordering::Greater);
+        assert_eq!(compare("1.2.3", "1.2.3"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_extra_parts() {
+        assert_eq!(compare("1.0.1", "1.0"), Ordering::Greater);
+        assert_eq!(compare("1.0-beta", "1.0"), Ordering::Less);
+        assert_eq!(compare("1.0", "1.0-rc1"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_tags() {
+        assert_eq!(compare("1.0-alpha", "1.0-beta"), Ordering::Less);
+        assert_eq!(compare("1.0.1", "1.0-beta"), Ordering::Greater);
+    }
+}